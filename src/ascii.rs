@@ -0,0 +1,327 @@
+//! Rendering of small graphs for terminals.
+//!
+//! Graphs are drawn as a list of boxed vertices with arrows to their neighbours,
+//! spanning trees of DFS and BFS are drawn like the output of `tree`.
+
+use std::{
+    collections::{HashSet, VecDeque},
+    fmt::Display,
+    hash::Hash,
+};
+
+use crate::{graph::Graph, marker::GraphType};
+
+/// Set of characters used for drawing.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Charset {
+    /// Plain ASCII, safe for any terminal.
+    Ascii,
+    /// Box-drawing characters.
+    #[default]
+    Unicode,
+}
+
+/// Traversal that produces the spanning tree.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Traversal {
+    Dfs,
+    Bfs,
+}
+
+struct Symbols {
+    top_left: &'static str,
+    top_right: &'static str,
+    bottom_left: &'static str,
+    bottom_right: &'static str,
+    horizontal: &'static str,
+    vertical: &'static str,
+    branch: &'static str,
+    last_branch: &'static str,
+    arrow_head: &'static str,
+}
+
+const ASCII: Symbols = Symbols {
+    top_left: "+",
+    top_right: "+",
+    bottom_left: "+",
+    bottom_right: "+",
+    horizontal: "-",
+    vertical: "|",
+    branch: "|",
+    last_branch: "`",
+    arrow_head: ">",
+};
+
+const UNICODE: Symbols = Symbols {
+    top_left: "┌",
+    top_right: "┐",
+    bottom_left: "└",
+    bottom_right: "┘",
+    horizontal: "─",
+    vertical: "│",
+    branch: "├",
+    last_branch: "└",
+    arrow_head: "▶",
+};
+
+impl Charset {
+    fn symbols(&self) -> &'static Symbols {
+        match self {
+            Charset::Ascii => &ASCII,
+            Charset::Unicode => &UNICODE,
+        }
+    }
+}
+
+fn weight(weight: i64) -> String {
+    if weight != 0 {
+        format!(" (w={})", weight)
+    } else {
+        String::new()
+    }
+}
+
+impl<Key, Value, Type> Graph<Key, Value, Type>
+where
+    Key: Hash + Eq + Clone + Display + Ord,
+    Value: Display,
+    Type: GraphType,
+{
+    /// Draws every vertex as a box with its key and value,
+    /// followed by arrows to its neighbours. Vertices are sorted by key.
+    pub fn to_ascii(&self, charset: Charset) -> String {
+        let symbols = charset.symbols();
+
+        let vertices = self.sorted_vertices();
+
+        let mut blocks = Vec::with_capacity(vertices.len());
+        for vertex in vertices {
+            let content = format!("{}: {}", vertex.key(), vertex.value);
+            let line = symbols.horizontal.repeat(content.chars().count() + 2);
+
+            let mut block = format!(
+                "{}{}{}\n{} {} {}\n{}{}{}\n",
+                symbols.top_left,
+                line,
+                symbols.top_right,
+                symbols.vertical,
+                content,
+                symbols.vertical,
+                symbols.bottom_left,
+                line,
+                symbols.bottom_right
+            );
+
+            let mut edges: Vec<_> = vertex.adjancency_list().iter().collect();
+            edges.sort_unstable_by(|a, b| a.to().cmp(b.to()));
+            let head = if Type::is_directed() {
+                symbols.arrow_head
+            } else {
+                symbols.horizontal
+            };
+            for (i, edge) in edges.iter().enumerate() {
+                let branch = if i + 1 == edges.len() {
+                    symbols.last_branch
+                } else {
+                    symbols.branch
+                };
+                block += &format!(
+                    "  {}{}{} {}{}\n",
+                    branch,
+                    symbols.horizontal.repeat(2),
+                    head,
+                    edge.to(),
+                    weight(edge.weight)
+                );
+            }
+
+            blocks.push(block);
+        }
+
+        blocks.join("\n")
+    }
+
+    /// Draws the spanning tree that the traversal from the root builds.
+    /// Children are drawn in the order of adjacency lists.
+    /// Returns None if the root is not in the graph.
+    pub fn to_ascii_tree(
+        &self,
+        root: &Key,
+        traversal: Traversal,
+        charset: Charset,
+    ) -> Option<String> {
+        let root = self.get_vertex(root)?;
+        let symbols = charset.symbols();
+
+        // Children of every vertex of the tree with weights of the tree edges
+        let mut children: Vec<Vec<(usize, i64)>> = vec![Vec::new()];
+        let mut vertices = vec![root];
+        let mut visited: HashSet<&Key> = HashSet::from([root.key()]);
+
+        match traversal {
+            Traversal::Bfs => {
+                let mut queue = VecDeque::from([0]);
+                while let Some(i) = queue.pop_front() {
+                    for edge in vertices[i].adjancency_list() {
+                        if visited.insert(edge.to()) {
+                            let child = vertices.len();
+                            vertices
+                                .push(self.get_vertex(edge.to()).expect("edge leads to a vertex"));
+                            children.push(Vec::new());
+                            children[i].push((child, edge.weight));
+                            queue.push_back(child);
+                        }
+                    }
+                }
+            }
+            Traversal::Dfs => {
+                // Every entry is a vertex and the position in its adjacency list
+                let mut stack = vec![(0, 0)];
+                while let Some((i, next)) = stack.pop() {
+                    let adj = vertices[i].adjancency_list();
+                    let Some(edge) = adj.get(next) else {
+                        continue;
+                    };
+                    stack.push((i, next + 1));
+                    if visited.insert(edge.to()) {
+                        let child = vertices.len();
+                        vertices.push(self.get_vertex(edge.to()).expect("edge leads to a vertex"));
+                        children.push(Vec::new());
+                        children[i].push((child, edge.weight));
+                        stack.push((child, 0));
+                    }
+                }
+            }
+        }
+
+        let label = |i: usize| format!("{} [{}]", vertices[i].key(), vertices[i].value);
+        let mut buffer = format!("{}\n", label(0));
+
+        // Every entry is a vertex, the weight of the edge to it,
+        // the prefix of its line and whether it is the last child
+        let mut stack: Vec<(usize, i64, String, bool)> = children[0]
+            .iter()
+            .rev()
+            .enumerate()
+            .map(|(j, (child, w))| (*child, *w, String::new(), j == 0))
+            .collect();
+        while let Some((i, w, prefix, last)) = stack.pop() {
+            let branch = if last {
+                symbols.last_branch
+            } else {
+                symbols.branch
+            };
+            buffer += &format!(
+                "{}{}{} {}{}\n",
+                prefix,
+                branch,
+                symbols.horizontal.repeat(2),
+                label(i),
+                weight(w)
+            );
+
+            let prefix = if last {
+                format!("{}    ", prefix)
+            } else {
+                format!("{}{}   ", prefix, symbols.vertical)
+            };
+            for (j, (child, w)) in children[i].iter().rev().enumerate() {
+                stack.push((*child, *w, prefix.clone(), j == 0));
+            }
+        }
+
+        Some(buffer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::marker::{Directed, Undirected};
+    use pretty_assertions::assert_eq;
+
+    fn sample() -> Graph<i32, String, Directed> {
+        let mut graph: Graph<i32, String, Directed> = Graph::new();
+        for (key, value) in [(1, "A"), (2, "B"), (3, "C"), (4, "D")] {
+            graph.insert(key, String::from(value));
+        }
+        graph.insert_edge(1, 2, 5).unwrap();
+        graph.insert_edge(1, 3, 0).unwrap();
+        graph.insert_edge(2, 4, 0).unwrap();
+        graph.insert_edge(3, 4, 0).unwrap();
+        graph
+    }
+
+    #[test]
+    fn to_ascii_test() {
+        let mut graph: Graph<i32, String, Directed> = Graph::new();
+        graph.insert(1, String::from("A"));
+        graph.insert(2, String::from("B"));
+        graph.insert_edge(1, 2, 5).unwrap();
+        graph.insert_edge(1, 1, 0).unwrap();
+
+        assert_eq!(
+            graph.to_ascii(Charset::Unicode),
+            "┌──────┐\n│ 1: A │\n└──────┘\n  ├──▶ 1\n  └──▶ 2 (w=5)\n\n┌──────┐\n│ 2: B │\n└──────┘\n"
+        );
+
+        let mut undirected: Graph<char, i32, Undirected> = Graph::new();
+        undirected.insert('a', 1);
+        undirected.insert('b', 2);
+        undirected.insert_edge('a', 'b', 0).unwrap();
+        assert_eq!(
+            undirected.to_ascii(Charset::Ascii),
+            "+------+\n| a: 1 |\n+------+\n  `--- b\n\n+------+\n| b: 2 |\n+------+\n  `--- a\n"
+        );
+
+        // Keys are ordered as numbers, not as text
+        let mut numbers: Graph<i32, String, Directed> = Graph::new();
+        numbers.insert(10, String::from("J"));
+        numbers.insert(2, String::from("B"));
+        numbers.insert_edge(2, 10, 0).unwrap();
+        assert_eq!(
+            numbers.to_ascii(Charset::Unicode),
+            "┌──────┐\n│ 2: B │\n└──────┘\n  └──▶ 10\n\n┌───────┐\n│ 10: J │\n└───────┘\n"
+        );
+    }
+
+    #[test]
+    fn to_ascii_tree_test() {
+        let graph = sample();
+
+        assert_eq!(
+            graph.to_ascii_tree(&1, Traversal::Dfs, Charset::Unicode),
+            Some(String::from(
+                "1 [A]\n├── 2 [B] (w=5)\n│   └── 4 [D]\n└── 3 [C]\n"
+            ))
+        );
+        assert_eq!(
+            graph.to_ascii_tree(&1, Traversal::Bfs, Charset::Ascii),
+            Some(String::from(
+                "1 [A]\n|-- 2 [B] (w=5)\n|   `-- 4 [D]\n`-- 3 [C]\n"
+            ))
+        );
+
+        // DFS goes deep before visiting siblings, BFS takes the closest parent
+        let mut graph = sample();
+        graph.insert_edge(2, 3, 0).unwrap();
+        assert_eq!(
+            graph.to_ascii_tree(&1, Traversal::Dfs, Charset::Ascii),
+            Some(String::from(
+                "1 [A]\n`-- 2 [B] (w=5)\n    |-- 4 [D]\n    `-- 3 [C]\n"
+            ))
+        );
+        assert_eq!(
+            graph.to_ascii_tree(&1, Traversal::Bfs, Charset::Ascii),
+            Some(String::from(
+                "1 [A]\n|-- 2 [B] (w=5)\n|   `-- 4 [D]\n`-- 3 [C]\n"
+            ))
+        );
+
+        assert_eq!(
+            graph.to_ascii_tree(&9, Traversal::Bfs, Charset::Ascii),
+            None
+        );
+    }
+}
@@ -0,0 +1,165 @@
+//! Formats that the CLI can read and write, with detection of the input format.
+
+use std::{fmt::Display, hash::Hash, path::Path, str::FromStr};
+
+use graph_rs::{
+    csv::{self, EdgeListOptions},
+    gexf::{Gexf, GexfConvertible},
+    gml::{GmlConvertible, GraphModellingLanguage},
+    graph::Graph,
+    graphml::{GraphMl, GraphMlConvertible},
+    json::{JsonConvertible, JsonType, NodeLinkJson},
+    marker::GraphType,
+    pajek::{PajekConvertible, PajekNet},
+    tgf::TgfConvertible,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    Tgf,
+    GraphMl,
+    Json,
+    Gml,
+    Pajek,
+    Gexf,
+    Csv,
+    Dot,
+    Mermaid,
+    PlantUml,
+    Svg,
+}
+
+impl Format {
+    /// Names of the formats that can be read.
+    pub const READABLE: [&'static str; 7] = ["tgf", "graphml", "json", "gml", "net", "gexf", "csv"];
+
+    /// Names of all formats, all of them can be written.
+    pub const WRITABLE: [&'static str; 11] = [
+        "tgf", "graphml", "json", "gml", "net", "gexf", "csv", "dot", "mermaid", "plantuml", "svg",
+    ];
+
+    pub fn from_name(name: &str) -> Option<Format> {
+        Some(match name {
+            "tgf" => Format::Tgf,
+            "graphml" => Format::GraphMl,
+            "json" => Format::Json,
+            "gml" => Format::Gml,
+            "net" => Format::Pajek,
+            "gexf" => Format::Gexf,
+            "csv" => Format::Csv,
+            "dot" | "gv" => Format::Dot,
+            "mermaid" | "mmd" => Format::Mermaid,
+            "plantuml" | "puml" => Format::PlantUml,
+            "svg" => Format::Svg,
+            _ => return None,
+        })
+    }
+
+    /// Returns the format by the extension of the file name.
+    pub fn from_extension(path: &str) -> Option<Format> {
+        let extension = Path::new(path).extension()?.to_str()?.to_lowercase();
+        match extension.as_str() {
+            "xml" => Some(Format::GraphMl),
+            other => Format::from_name(other),
+        }
+    }
+
+    /// Guesses the format of the content by its first meaningful line.
+    pub fn detect(content: &str) -> Format {
+        let trimmed = content.trim_start();
+        if trimmed.starts_with('{') {
+            return Format::Json;
+        }
+        if trimmed.starts_with('<') {
+            return if trimmed.contains("<gexf") {
+                Format::Gexf
+            } else {
+                Format::GraphMl
+            };
+        }
+
+        let mut lines = content
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty() && !l.starts_with('%'));
+        match lines.next() {
+            Some(line) if line.starts_with('*') => Format::Pajek,
+            Some(line) if line.starts_with("graph") || line.starts_with("Creator") => Format::Gml,
+            _ if content.lines().any(|l| l.trim() == "#") => Format::Tgf,
+            Some(line) if line.contains(',') => Format::Csv,
+            _ => Format::Tgf,
+        }
+    }
+
+    /// Returns the direction declared by the content if the format declares one.
+    pub fn declared_direction(&self, content: &str) -> Result<Option<bool>, String> {
+        let directed = match self {
+            Format::GraphMl => GraphMl::from(content).is_directed()?,
+            Format::Json => NodeLinkJson::from(content).is_directed()?,
+            Format::Gml => GraphModellingLanguage::from(content).is_directed()?,
+            Format::Pajek => PajekNet::from(content).is_directed()?,
+            Format::Gexf => Gexf::from(content).is_directed()?,
+            _ => return Ok(None),
+        };
+        Ok(Some(directed))
+    }
+}
+
+impl Display for Format {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Format::Tgf => "tgf",
+            Format::GraphMl => "graphml",
+            Format::Json => "json",
+            Format::Gml => "gml",
+            Format::Pajek => "net",
+            Format::Gexf => "gexf",
+            Format::Csv => "csv",
+            Format::Dot => "dot",
+            Format::Mermaid => "mermaid",
+            Format::PlantUml => "plantuml",
+            Format::Svg => "svg",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Reads the graph in the format. Vertex values are kept as strings.
+pub fn read<Key, Type>(format: Format, content: &str) -> Result<Graph<Key, String, Type>, String>
+where
+    Key: Hash + Eq + Clone + Display + FromStr + Ord + JsonType,
+    Type: GraphType,
+{
+    let content = String::from(content);
+    match format {
+        Format::Tgf => Graph::from_tgf(content.into()),
+        Format::GraphMl => Graph::from_graphml(content.into()),
+        Format::Json => Graph::from_json(content.into()),
+        Format::Gml => Graph::from_gml(content.into()),
+        Format::Pajek => Graph::from_pajek(content.into()),
+        Format::Gexf => Graph::from_gexf(content.into()),
+        Format::Csv => csv::read_edge_list(&content, &EdgeListOptions::default()),
+        other => Err(format!("{} can't be read, only written", other)),
+    }
+}
+
+/// Writes the graph in the format.
+pub fn write<Key, Type>(format: Format, graph: &Graph<Key, String, Type>) -> String
+where
+    Key: Hash + Eq + Clone + Display + FromStr + Ord + JsonType,
+    Type: GraphType,
+{
+    match format {
+        Format::Tgf => graph.to_tgf().into(),
+        Format::GraphMl => graph.to_graphml().into(),
+        Format::Json => graph.to_json().into(),
+        Format::Gml => graph.to_gml().into(),
+        Format::Pajek => graph.to_pajek().into(),
+        Format::Gexf => graph.to_gexf().into(),
+        Format::Csv => csv::write_edge_list(graph, &EdgeListOptions::default()),
+        Format::Dot => graph.to_dot(),
+        Format::Mermaid => graph.to_mermaid(),
+        Format::PlantUml => graph.to_plantuml(),
+        Format::Svg => graph.to_svg(),
+    }
+}
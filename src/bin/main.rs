@@ -1,36 +1,1065 @@
-use std::fs;
+mod formats;
+mod server;
+mod shell;
 
-use clap::{Arg, Command};
-use graph_rs::{graph::Graph, tgf::TgfConvertible};
+use std::{
+    fmt::Display,
+    fs,
+    hash::Hash,
+    io::{self, Read},
+    ops::RangeInclusive,
+    process::ExitCode,
+    str::FromStr,
+};
+
+use clap::{value_parser, Arg, ArgAction, ArgMatches, Command};
+use graph_rs::{
+    ascii::{Charset, Traversal},
+    generators::{
+        classic,
+        random::{self, RandomOptions},
+    },
+    graph::Graph,
+    graph6::{self, Graph6Format},
+    marker::{Directed, GraphType, Undirected},
+    tgf::TgfConvertible,
+};
+
+use formats::Format;
+use server::Server;
+use shell::{EdgeEditing, Session};
+use uuid::Uuid;
 
 const INPUT_FILE_NAME_ARG: &str = "file_name";
+const FROM_ARG: &str = "from";
+const TO_ARG: &str = "to";
+const START_ARG: &str = "start";
+const UNWEIGHTED_ARG: &str = "unweighted";
+const STRONG_ARG: &str = "strong";
+const TREE_ARG: &str = "tree";
+const ROOT_ARG: &str = "root";
+const ASCII_ARG: &str = "ascii";
+const OUTPUT_FILE_NAME_ARG: &str = "output";
+const FROM_FORMAT_ARG: &str = "from_format";
+const TO_FORMAT_ARG: &str = "to_format";
+const UNDIRECTED_ARG: &str = "undirected";
+const KEY_TYPE_ARG: &str = "key_type";
+const PORT_ARG: &str = "port";
+const QUERY_ARG: &str = "query";
+const MODEL_ARG: &str = "model";
+const PARAMS_ARG: &str = "params";
+const SEED_ARG: &str = "seed";
+const WEIGHTS_ARG: &str = "weights";
+const FORMAT_ARG: &str = "format";
+
+/// Models of the generate command and names of their parameters, in order.
+const MODELS: [(&str, &[&str]); 17] = [
+    ("gnp", &["N", "P"]),
+    ("gnm", &["N", "M"]),
+    ("barabasi-albert", &["N", "M"]),
+    ("watts-strogatz", &["N", "K", "BETA"]),
+    ("regular", &["N", "D"]),
+    ("dag", &["N", "P"]),
+    ("complete", &["N"]),
+    ("path", &["N"]),
+    ("cycle", &["N"]),
+    ("star", &["N"]),
+    ("wheel", &["N"]),
+    ("grid", &["ROWS", "COLUMNS"]),
+    ("grid3d", &["X", "Y", "Z"]),
+    ("hypercube", &["D"]),
+    ("bipartite", &["M", "N"]),
+    ("tree", &["K", "N"]),
+    ("petersen", &[]),
+];
+
+/// Random models, the only ones that use the seed and the weights.
+const RANDOM_MODELS: [&str; 6] = [
+    "gnp",
+    "gnm",
+    "barabasi-albert",
+    "watts-strogatz",
+    "regular",
+    "dag",
+];
 
-fn main() -> std::io::Result<()> {
-    let matches = Command::new("graph-rs")
+/// Failure of a command. Every kind of failure exits with its own code,
+/// so scripts can tell a missing answer from a broken input.
+enum Failure {
+    /// The graph has no answer to the query, e.g. there is no path. Exit code 1.
+    NoAnswer(String),
+    /// The arguments refer to something that is not in the graph. Exit code 2,
+    /// the same as for arguments rejected by clap.
+    Usage(String),
+    /// The input is not a valid graph. Exit code 65 (EX_DATAERR).
+    Data(String),
+    /// The input can't be read. Exit code 66 (EX_NOINPUT).
+    Input(String),
+    /// The output can't be written. Exit code 73 (EX_CANTCREAT).
+    Output(String),
+}
+
+impl Failure {
+    fn exit_code(&self) -> ExitCode {
+        ExitCode::from(match self {
+            Failure::NoAnswer(_) => 1,
+            Failure::Usage(_) => 2,
+            Failure::Data(_) => 65,
+            Failure::Input(_) => 66,
+            Failure::Output(_) => 73,
+        })
+    }
+
+    fn message(&self) -> &str {
+        match self {
+            Failure::NoAnswer(message)
+            | Failure::Usage(message)
+            | Failure::Data(message)
+            | Failure::Input(message)
+            | Failure::Output(message) => message,
+        }
+    }
+}
+
+/// Arguments of every command that reads a tgf file:
+/// the file name and the instantiation of `Graph` to read it into.
+fn graph_args() -> [Arg; 3] {
+    [
+        Arg::new(INPUT_FILE_NAME_ARG)
+            .required(true)
+            .help("tgf file name, - for stdin"),
+        Arg::new(KEY_TYPE_ARG)
+            .long("key-type")
+            .value_parser(["int", "string", "uuid"])
+            .default_value("int")
+            .help("type of vertex ids"),
+        Arg::new(UNDIRECTED_ARG)
+            .long("undirected")
+            .action(ArgAction::SetTrue)
+            .help("read the graph as undirected"),
+    ]
+}
+
+fn key_arg(name: &'static str, help: &'static str) -> Arg {
+    Arg::new(name).required(true).help(help)
+}
+
+fn command() -> Command {
+    Command::new("graph-rs")
         .version("1.0")
         .author("Semion Voevoda semionvoevoda@gmail.com")
-        .about(concat!(
-            "Graph-rs is a CLI tool for reading from tgf files and then ",
-            "outputing vertecies IDs with its values and adjacent vertecies IDs"
+        .about("Graph-rs is a CLI tool for reading graphs from tgf files and analyzing them")
+        .after_help(concat!(
+            "Exit codes: 0 on success, 1 if the graph has no answer (no path, a cycle), ",
+            "2 on invalid arguments, 65 if the input is not a valid graph, ",
+            "66 if the input can't be read, 73 if the output can't be written."
         ))
-        .arg(
-            Arg::new(INPUT_FILE_NAME_ARG)
-                .required(true)
-                .help("tgf file name"),
+        .subcommand_required(true)
+        .subcommand(
+            Command::new("print")
+                .about("Print vertices with their values and adjacent vertices")
+                .args(graph_args()),
+        )
+        .subcommand(
+            Command::new("info")
+                .about("Print vertex and edge counts, degree statistics and density")
+                .args(graph_args()),
+        )
+        .subcommand(
+            Command::new("path")
+                .about("Print the shortest path between two vertices and its weight")
+                .args(graph_args())
+                .arg(key_arg(FROM_ARG, "first vertex of the path"))
+                .arg(key_arg(TO_ARG, "last vertex of the path"))
+                .arg(
+                    Arg::new(UNWEIGHTED_ARG)
+                        .long("unweighted")
+                        .action(ArgAction::SetTrue)
+                        .help(concat!(
+                            "find the path with the fewest edges, ignoring weights; ",
+                            "graphs without weights are always searched this way"
+                        )),
+                ),
         )
-        .get_matches();
+        .subcommand(
+            Command::new("components")
+                .about("Print connected components, one per line")
+                .args(graph_args())
+                .arg(
+                    Arg::new(STRONG_ARG)
+                        .long("strong")
+                        .action(ArgAction::SetTrue)
+                        .help("print strongly connected components instead of weakly ones"),
+                ),
+        )
+        .subcommand(
+            Command::new("toposort")
+                .about("Print vertices in topological order, one per line")
+                .args(graph_args()),
+        )
+        .subcommand(
+            Command::new("mst")
+                .about("Print edges of the minimum spanning forest and its total weight")
+                .args(graph_args()),
+        )
+        .subcommand(
+            Command::new("bfs")
+                .about("Print vertices in breadth-first order, one per line")
+                .args(graph_args())
+                .arg(key_arg(START_ARG, "vertex to start from")),
+        )
+        .subcommand(
+            Command::new("dfs")
+                .about("Print vertices in depth-first order, one per line")
+                .args(graph_args())
+                .arg(key_arg(START_ARG, "vertex to start from")),
+        )
+        .subcommand(
+            Command::new("query")
+                .about("Print rows matching a Cypher-like query, separated by tabs")
+                .args(graph_args())
+                .arg(
+                    Arg::new(QUERY_ARG)
+                        .required(true)
+                        .help("query, e.g. 'MATCH (a)-[w>5]->(b) WHERE a.value = \"X\" RETURN b'"),
+                ),
+        )
+        .subcommand(
+            Command::new("render")
+                .about("Draw the graph as boxes or a spanning tree in the terminal")
+                .args(graph_args())
+                .arg(
+                    Arg::new(TREE_ARG)
+                        .long("tree")
+                        .value_parser(["dfs", "bfs"])
+                        .requires(ROOT_ARG)
+                        .help("draw the DFS or BFS spanning tree from --root"),
+                )
+                .arg(
+                    Arg::new(ROOT_ARG)
+                        .long("root")
+                        .help("root vertex of the spanning tree"),
+                )
+                .arg(
+                    Arg::new(ASCII_ARG)
+                        .long("ascii")
+                        .action(ArgAction::SetTrue)
+                        .help("draw with plain ASCII instead of box-drawing characters"),
+                ),
+        )
+        .subcommand(
+            Command::new("graph6")
+                .about("Print graphs of a graph6, sparse6 or digraph6 file, one graph per line")
+                .arg(
+                    Arg::new(INPUT_FILE_NAME_ARG)
+                        .required(true)
+                        .help("file name, - for stdin"),
+                ),
+        )
+        .subcommand(
+            Command::new("shell")
+                .about("Edit and query the graph in an interactive shell")
+                .arg(
+                    Arg::new(INPUT_FILE_NAME_ARG)
+                        .help("file to open, in any format that convert reads"),
+                )
+                .arg(
+                    Arg::new(UNDIRECTED_ARG)
+                        .long("undirected")
+                        .action(ArgAction::SetTrue)
+                        .help("edit the graph as undirected"),
+                ),
+        )
+        .subcommand(
+            Command::new("serve")
+                .about("Answer queries and edits of the graph over HTTP on localhost, in JSON")
+                .arg(
+                    Arg::new(INPUT_FILE_NAME_ARG)
+                        .required(true)
+                        .help("file to serve, in any format that convert reads"),
+                )
+                .arg(
+                    Arg::new(PORT_ARG)
+                        .long("port")
+                        .value_parser(value_parser!(u16))
+                        .default_value("8080")
+                        .help("port to listen on, 0 for any free port"),
+                )
+                .arg(
+                    Arg::new(UNDIRECTED_ARG)
+                        .long("undirected")
+                        .action(ArgAction::SetTrue)
+                        .help("serve the graph as undirected"),
+                ),
+        )
+        .subcommand(
+            Command::new("convert")
+                .about("Convert the graph from one format to another")
+                .arg(
+                    Arg::new(INPUT_FILE_NAME_ARG)
+                        .required(true)
+                        .help("input file name, - for stdin"),
+                )
+                .arg(
+                    Arg::new(OUTPUT_FILE_NAME_ARG)
+                        .required(true)
+                        .help("output file name, - for stdout"),
+                )
+                .arg(
+                    Arg::new(FROM_FORMAT_ARG)
+                        .long("from")
+                        .value_parser(Format::READABLE)
+                        .help("input format, detected by the extension or the content if omitted"),
+                )
+                .arg(
+                    Arg::new(TO_FORMAT_ARG)
+                        .long("to")
+                        .value_parser(Format::WRITABLE)
+                        .help("output format, detected by the extension if omitted"),
+                )
+                .arg(
+                    Arg::new(UNDIRECTED_ARG)
+                        .long("undirected")
+                        .action(ArgAction::SetTrue)
+                        .help(concat!(
+                            "read the graph as undirected; otherwise the direction declared ",
+                            "by the input is used, and graphs without one are directed"
+                        )),
+                ),
+        )
+        .subcommand(
+            Command::new("generate")
+                .about("Print a synthetic graph of the model, vertices are numbered from 0")
+                .after_help(models_help())
+                .arg(
+                    Arg::new(MODEL_ARG)
+                        .required(true)
+                        .value_parser(MODELS.map(|(name, _)| name))
+                        .help("model of the graph, see the list below"),
+                )
+                .arg(
+                    Arg::new(PARAMS_ARG)
+                        .num_args(0..)
+                        .help("parameters of the model, in order"),
+                )
+                .arg(
+                    Arg::new(SEED_ARG)
+                        .long("seed")
+                        .value_parser(value_parser!(u64))
+                        .default_value("0")
+                        .help("seed of random models, the same seed gives the same graph"),
+                )
+                .arg(
+                    Arg::new(WEIGHTS_ARG)
+                        .long("weights")
+                        .value_name("MIN..MAX")
+                        .value_parser(parse_weights)
+                        .help("draw weights of edges of random models from the range"),
+                )
+                .arg(
+                    Arg::new(FORMAT_ARG)
+                        .long("format")
+                        .value_parser(Format::WRITABLE)
+                        .default_value("tgf")
+                        .help("output format"),
+                )
+                .arg(
+                    Arg::new(UNDIRECTED_ARG)
+                        .long("undirected")
+                        .action(ArgAction::SetTrue)
+                        .help("generate an undirected graph; regular graphs are always undirected"),
+                ),
+        )
+}
+
+fn main() -> ExitCode {
+    let matches = command().get_matches();
+
+    match run(&matches) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(failure) => {
+            eprintln!("error: {}", failure.message());
+            failure.exit_code()
+        }
+    }
+}
+
+fn run(matches: &ArgMatches) -> Result<(), Failure> {
+    let (name, matches) = matches.subcommand().expect("subcommand is required");
+    match (name, matches.try_get_one::<bool>(UNDIRECTED_ARG)) {
+        ("shell", Ok(Some(true))) => return shell::<Undirected>(matches),
+        ("shell", _) => return shell::<Directed>(matches),
+        ("serve", Ok(Some(true))) => return serve::<Undirected>(matches),
+        ("serve", _) => return serve::<Directed>(matches),
+        ("generate", _) => return generate(matches),
+        _ => {}
+    }
+
+    let raw = read(matches)?;
+
+    match name {
+        "graph6" => return print_graph6(&raw),
+        "convert" => return convert(matches, &raw),
+        _ => {}
+    }
+
+    let key_type: &String = matches
+        .get_one(KEY_TYPE_ARG)
+        .expect("key type has a default");
+    match (key_type.as_str(), matches.get_flag(UNDIRECTED_ARG)) {
+        ("int", false) => analyze::<i64, Directed>(name, matches, raw),
+        ("int", true) => analyze::<i64, Undirected>(name, matches, raw),
+        ("string", false) => analyze::<String, Directed>(name, matches, raw),
+        ("string", true) => analyze::<String, Undirected>(name, matches, raw),
+        ("uuid", false) => analyze::<Uuid, Directed>(name, matches, raw),
+        ("uuid", true) => analyze::<Uuid, Undirected>(name, matches, raw),
+        _ => unreachable!("clap checks key types"),
+    }
+}
+
+/// Analyses that are defined only for directed graphs.
+/// For undirected graphs they fail with a usage error.
+trait DirectedOnly<Key> {
+    fn strong_components(&self) -> Result<Vec<Vec<&Key>>, Failure>;
+    fn toposort(&self) -> Result<Option<Vec<&Key>>, Failure>;
+}
+
+impl<Key, Value> DirectedOnly<Key> for Graph<Key, Value, Directed>
+where
+    Key: Hash + Eq + Clone,
+{
+    fn strong_components(&self) -> Result<Vec<Vec<&Key>>, Failure> {
+        Ok(self.strongly_connected_components())
+    }
+
+    fn toposort(&self) -> Result<Option<Vec<&Key>>, Failure> {
+        Ok(self.topological_sort())
+    }
+}
+
+impl<Key, Value> DirectedOnly<Key> for Graph<Key, Value, Undirected>
+where
+    Key: Hash + Eq + Clone,
+{
+    fn strong_components(&self) -> Result<Vec<Vec<&Key>>, Failure> {
+        Err(Failure::Usage(String::from(
+            "strongly connected components are defined only for directed graphs",
+        )))
+    }
+
+    fn toposort(&self) -> Result<Option<Vec<&Key>>, Failure> {
+        Err(Failure::Usage(String::from(
+            "topological order is defined only for directed graphs",
+        )))
+    }
+}
+
+/// Reads the tgf file into `Graph<Key, String, Type>` and runs the command on it.
+fn analyze<Key, Type>(name: &str, matches: &ArgMatches, raw: String) -> Result<(), Failure>
+where
+    Key: Hash + Eq + Clone + Display + FromStr + Ord,
+    Type: GraphType,
+    Graph<Key, String, Type>: DirectedOnly<Key>,
+{
+    let graph: Graph<Key, String, Type> =
+        Graph::from_tgf(raw.into()).map_err(|e| match e.as_str() {
+            "couldn't parse vertex" => Failure::Data(format!("{}, see --key-type", e)),
+            _ => Failure::Data(e),
+        })?;
+    let key = |name: &str| -> Result<Key, Failure> {
+        let raw: &String = matches.get_one(name).expect("key is required");
+        match raw.parse() {
+            Ok(key) if graph.contains(&key) => Ok(key),
+            _ => Err(Failure::Usage(format!(
+                "vertex {} is not in the graph",
+                raw
+            ))),
+        }
+    };
+
+    match name {
+        "print" => println!("{}", graph),
+        "info" => print!("{}", info(&graph)),
+        "path" => {
+            let (from, to) = (&key(FROM_ARG)?, &key(TO_ARG)?);
+            let (path, length) = find_path(&graph, from, to, matches.get_flag(UNWEIGHTED_ARG))
+                .map_err(|e| Failure::Data(e.to_string()))?
+                .ok_or_else(|| {
+                    Failure::NoAnswer(format!("there is no path from {} to {}", from, to))
+                })?;
+            println!("{}", join(&path, " -> "));
+            println!("{}", length);
+        }
+        "components" => {
+            let components = if matches.get_flag(STRONG_ARG) {
+                graph.strong_components()?
+            } else {
+                graph.connected_components()
+            };
+            for component in components {
+                println!("{}", join(&component, " "));
+            }
+        }
+        "toposort" => {
+            let order = graph
+                .toposort()?
+                .ok_or_else(|| Failure::NoAnswer(String::from("graph contains a cycle")))?;
+            for key in order {
+                println!("{}", key);
+            }
+        }
+        "mst" => {
+            let (forest, total) = graph.minimum_spanning_forest();
+            for edge in forest {
+                if edge.weight != 0 {
+                    println!("{} -- {} (w={})", edge.from(), edge.to(), edge.weight);
+                } else {
+                    println!("{} -- {}", edge.from(), edge.to());
+                }
+            }
+            println!("total weight: {}", total);
+        }
+        "bfs" => {
+            for vertex in graph.bfs(&key(START_ARG)?) {
+                println!("{}", vertex.key());
+            }
+        }
+        "dfs" => {
+            for vertex in graph.dfs(&key(START_ARG)?) {
+                println!("{}", vertex.key());
+            }
+        }
+        "query" => {
+            let query: &String = matches.get_one(QUERY_ARG).expect("query is required");
+            let result = graph.query(query).map_err(Failure::Usage)?;
+            print!("{}", result);
+            if result.rows.is_empty() {
+                return Err(Failure::NoAnswer(String::from("query has no matches")));
+            }
+        }
+        "render" => {
+            let charset = if matches.get_flag(ASCII_ARG) {
+                Charset::Ascii
+            } else {
+                Charset::Unicode
+            };
+            let traversal = match matches.get_one::<String>(TREE_ARG).map(String::as_str) {
+                None => {
+                    print!("{}", graph.to_ascii(charset));
+                    return Ok(());
+                }
+                Some("dfs") => Traversal::Dfs,
+                Some(_) => Traversal::Bfs,
+            };
+            let tree = graph
+                .to_ascii_tree(&key(ROOT_ARG)?, traversal, charset)
+                .expect("root is checked");
+            print!("{}", tree);
+        }
+        _ => unreachable!("all subcommands are handled"),
+    }
+
+    Ok(())
+}
+
+fn read(matches: &ArgMatches) -> Result<String, Failure> {
+    let file_name: &String = matches
+        .get_one(INPUT_FILE_NAME_ARG)
+        .expect("file name is required");
+
+    if file_name == "-" {
+        let mut buffer = String::new();
+        io::stdin()
+            .read_to_string(&mut buffer)
+            .map_err(|e| Failure::Input(format!("couldn't read stdin: {}", e)))?;
+        return Ok(buffer);
+    }
+
+    fs::read_to_string(file_name)
+        .map_err(|e| Failure::Input(format!("couldn't read '{}': {}", file_name, e)))
+}
+
+fn write(file_name: &str, content: &str) -> Result<(), Failure> {
+    if file_name == "-" {
+        print!("{}", content);
+        return Ok(());
+    }
+
+    fs::write(file_name, content)
+        .map_err(|e| Failure::Output(format!("couldn't write '{}': {}", file_name, e)))
+}
+
+fn convert(matches: &ArgMatches, raw: &str) -> Result<(), Failure> {
+    let input: &String = matches
+        .get_one(INPUT_FILE_NAME_ARG)
+        .expect("file name is required");
+    let output: &String = matches
+        .get_one(OUTPUT_FILE_NAME_ARG)
+        .expect("file name is required");
+
+    let from = match matches.get_one::<String>(FROM_FORMAT_ARG) {
+        Some(name) => Format::from_name(name).expect("clap checks format names"),
+        None => Format::from_extension(input)
+            .filter(|f| Format::READABLE.contains(&f.to_string().as_str()))
+            .unwrap_or_else(|| Format::detect(raw)),
+    };
+    let to = match matches.get_one::<String>(TO_FORMAT_ARG) {
+        Some(name) => Format::from_name(name).expect("clap checks format names"),
+        None => Format::from_extension(output).ok_or_else(|| {
+            Failure::Usage(format!(
+                "couldn't detect the format of '{}', use --to",
+                output
+            ))
+        })?,
+    };
+
+    let undirected = matches.get_flag(UNDIRECTED_ARG)
+        || from.declared_direction(raw).map_err(Failure::Data)? == Some(false);
+
+    let converted = if undirected {
+        convert_as::<Undirected>(from, to, raw)?
+    } else {
+        convert_as::<Directed>(from, to, raw)?
+    };
+
+    write(output, &converted)
+}
+
+fn convert_as<Type: GraphType>(from: Format, to: Format, raw: &str) -> Result<String, Failure> {
+    let graph: Graph<String, String, Type> = formats::read(from, raw)
+        .map_err(|e| Failure::Data(format!("couldn't read {}: {}", from, e)))?;
+    Ok(formats::write(to, &graph))
+}
+
+/// Lists the models of the generate command with their parameters.
+fn models_help() -> String {
+    let mut help = String::from("Models:");
+    for (name, params) in MODELS {
+        help += &format!("\n  {:<16}{}", name, params.join(" "));
+    }
+    help + concat!(
+        "\n\nP and BETA are probabilities, the rest are integers. ",
+        "A dag is always directed, regular graphs are always undirected."
+    )
+}
+
+fn parse_weights(raw: &str) -> Result<RangeInclusive<i64>, String> {
+    let (min, max) = raw
+        .split_once("..")
+        .ok_or_else(|| String::from("expected MIN..MAX"))?;
+    let bound = |raw: &str| {
+        raw.trim()
+            .parse::<i64>()
+            .map_err(|e| format!("invalid bound '{}': {}", raw, e))
+    };
+    let weights = bound(min)?..=bound(max)?;
+    if weights.is_empty() {
+        return Err(String::from("range of weights is empty"));
+    }
+    Ok(weights)
+}
+
+/// Generates the graph of the model and prints it to stdout.
+fn generate(matches: &ArgMatches) -> Result<(), Failure> {
+    let model: &String = matches.get_one(MODEL_ARG).expect("model is required");
+    let params: Vec<&String> = matches
+        .get_many(PARAMS_ARG)
+        .map(|params| params.collect())
+        .unwrap_or_default();
+    let (_, names) = MODELS
+        .into_iter()
+        .find(|(name, _)| name == model)
+        .expect("clap checks models");
+    if params.len() != names.len() {
+        return Err(Failure::Usage(format!(
+            "{} takes {} parameters: {}",
+            model,
+            names.len(),
+            names.join(" ")
+        )));
+    }
+
+    let weights: Option<RangeInclusive<i64>> = matches.get_one(WEIGHTS_ARG).cloned();
+    if weights.is_some() && !RANDOM_MODELS.contains(&model.as_str()) {
+        return Err(Failure::Usage(format!(
+            "{} is not a random model, --weights applies only to random models",
+            model
+        )));
+    }
+    let undirected = matches.get_flag(UNDIRECTED_ARG);
+    if model == "dag" && undirected {
+        return Err(Failure::Usage(String::from("a dag is always directed")));
+    }
+
+    let format: &String = matches.get_one(FORMAT_ARG).expect("format has a default");
+    let format = Format::from_name(format).expect("clap checks format names");
+    let options = RandomOptions {
+        seed: *matches.get_one(SEED_ARG).expect("seed has a default"),
+        weights,
+    };
+    let param = Params {
+        model,
+        names,
+        values: &params,
+    };
+
+    let generated = match model.as_str() {
+        "regular" => {
+            let graph = random::random_regular(param.get(0)?, param.get(1)?, &options);
+            formats::write(format, &label(graph.map_err(Failure::Usage)?))
+        }
+        "dag" => {
+            let graph = random::random_dag(param.get(0)?, param.get(1)?, &options);
+            formats::write(format, &label(graph.map_err(Failure::Usage)?))
+        }
+        _ if undirected => {
+            formats::write(format, &label(generate_as::<Undirected>(&param, &options)?))
+        }
+        _ => formats::write(format, &label(generate_as::<Directed>(&param, &options)?)),
+    };
+
+    write("-", &generated)
+}
+
+/// Parameters of a model given on the command line.
+struct Params<'a> {
+    model: &'a str,
+    names: &'a [&'a str],
+    values: &'a [&'a String],
+}
+
+impl Params<'_> {
+    /// Parses the parameter at the position.
+    fn get<T: FromStr>(&self, i: usize) -> Result<T, Failure> {
+        self.values[i].parse().map_err(|_| {
+            Failure::Usage(format!(
+                "invalid {} of {}: '{}'",
+                self.names[i], self.model, self.values[i]
+            ))
+        })
+    }
+}
+
+/// Generates the graph of a model that can be both directed and undirected.
+fn generate_as<Type: GraphType>(
+    param: &Params,
+    options: &RandomOptions,
+) -> Result<Graph<usize, (), Type>, Failure> {
+    let (value, weight) = (|_| (), |_, _| 0);
+    match param.model {
+        "gnp" => random::gnp(param.get(0)?, param.get(1)?, options),
+        "gnm" => random::gnm(param.get(0)?, param.get(1)?, options),
+        "barabasi-albert" => random::barabasi_albert(param.get(0)?, param.get(1)?, options),
+        "watts-strogatz" => {
+            random::watts_strogatz(param.get(0)?, param.get(1)?, param.get(2)?, options)
+        }
+        "complete" => classic::complete(param.get(0)?, value, weight),
+        "path" => classic::path(param.get(0)?, value, weight),
+        "cycle" => classic::cycle(param.get(0)?, value, weight),
+        "star" => classic::star(param.get(0)?, value, weight),
+        "wheel" => classic::wheel(param.get(0)?, value, weight),
+        "grid" => classic::grid_2d(param.get(0)?, param.get(1)?, value, weight),
+        "grid3d" => classic::grid_3d(param.get(0)?, param.get(1)?, param.get(2)?, value, weight),
+        "hypercube" => classic::hypercube(param.get(0)?, value, weight),
+        "bipartite" => classic::complete_bipartite(param.get(0)?, param.get(1)?, value, weight),
+        "tree" => classic::k_ary_tree(param.get(0)?, param.get(1)?, value, weight),
+        "petersen" => Ok(classic::petersen(value, weight)),
+        _ => unreachable!("dag and regular graphs have a fixed direction"),
+    }
+    .map_err(Failure::Usage)
+}
+
+/// Gives every generated vertex its key as the value, so every format can write it.
+fn label<Type: GraphType>(graph: Graph<usize, (), Type>) -> Graph<usize, String, Type> {
+    graph.map_values(|key, ()| key.to_string())
+}
 
-    // unsafe block is okay, because if this arg is not specified, it will panic before this block
-    let file_name: &String = unsafe { matches.get_one(INPUT_FILE_NAME_ARG).unwrap_unchecked() };
+/// Reads the graph of the file in any readable format, or returns an empty graph
+/// if no file is given.
+fn load<Type: GraphType>(matches: &ArgMatches) -> Result<Graph<String, String, Type>, Failure> {
+    let file: Option<&String> = matches.get_one(INPUT_FILE_NAME_ARG);
+    Ok(match file {
+        Some(file) => {
+            let raw = read(matches)?;
+            let format = Format::from_extension(file)
+                .filter(|f| Format::READABLE.contains(&f.to_string().as_str()))
+                .unwrap_or_else(|| Format::detect(&raw));
+            formats::read(format, &raw)
+                .map_err(|e| Failure::Data(format!("couldn't read {}: {}", format, e)))?
+        }
+        None => Graph::new(),
+    })
+}
+
+/// Opens the file, if it is given, and starts the shell on its graph.
+fn shell<Type>(matches: &ArgMatches) -> Result<(), Failure>
+where
+    Type: GraphType,
+    Graph<String, String, Type>: EdgeEditing,
+{
+    let file: Option<&String> = matches.get_one(INPUT_FILE_NAME_ARG);
+    shell::run(Session::new(load(matches)?, file.cloned())).map_err(Failure::Input)
+}
+
+/// Loads the file and serves its graph until the process is stopped.
+fn serve<Type>(matches: &ArgMatches) -> Result<(), Failure>
+where
+    Type: GraphType,
+    Graph<String, String, Type>: EdgeEditing + DirectedOnly<String>,
+{
+    let graph = load(matches)?;
+    let port: &u16 = matches.get_one(PORT_ARG).expect("port has a default");
+    let listener = server::bind(*port).map_err(Failure::Output)?;
+    let address = listener
+        .local_addr()
+        .map_err(|e| Failure::Output(e.to_string()))?;
+
+    eprintln!("listening on http://{}", address);
+    Server::new(graph).serve(listener).map_err(Failure::Output)
+}
+
+/// Length of the path found by `find_path`.
+enum PathLength {
+    /// Total weight of the edges.
+    Weight(i64),
+    /// Number of edges, if weights are ignored.
+    Edges(usize),
+}
+
+impl Display for PathLength {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PathLength::Weight(weight) => write!(f, "weight: {}", weight),
+            PathLength::Edges(edges) => write!(f, "edges: {}", edges),
+        }
+    }
+}
+
+/// Finds the path with the smallest weight, or with the fewest edges
+/// if no edge is weighted or weights are ignored.
+/// Returns None if there is no path.
+fn find_path<'a, Key, Value, Type>(
+    graph: &'a Graph<Key, Value, Type>,
+    from: &Key,
+    to: &Key,
+    ignore_weights: bool,
+) -> Result<Option<(Vec<&'a Key>, PathLength)>, &'static str>
+where
+    Key: Hash + Eq + Clone,
+    Type: GraphType,
+{
+    // Weight 0 means that the edge is unweighted
+    if ignore_weights || graph.edges().all(|e| e.weight == 0) {
+        Ok(graph.fewest_edges_path(from, to)?.map(|path| {
+            let edges = path.len() - 1;
+            (path, PathLength::Edges(edges))
+        }))
+    } else {
+        Ok(graph
+            .shortest_path(from, to)?
+            .map(|(path, weight)| (path, PathLength::Weight(weight))))
+    }
+}
+
+fn join(keys: &[&impl Display], separator: &str) -> String {
+    keys.iter()
+        .map(|k| k.to_string())
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+/// Describes the size of the graph, degrees of its vertices and its density.
+fn info<Key, Value, Type>(graph: &Graph<Key, Value, Type>) -> String
+where
+    Key: Hash + Eq + Clone,
+    Type: GraphType,
+{
+    let (n, m) = (graph.len(), graph.edges_count());
 
-    let tgf_raw = fs::read_to_string(file_name)?;
+    let mut buffer = format!(
+        "type: {}\nvertices: {}\nedges: {}\n",
+        Type::type_name(),
+        n,
+        m
+    );
 
-    let graph: Graph<i32, String> = match Graph::from_tgf(tgf_raw.into()) {
-        Ok(graph) => graph,
-        Err(err) => panic!("error: {}", err),
+    let degrees: Vec<usize> = graph
+        .vertecies()
+        .map(|v| graph.degree(v.key()).expect("the vertex is in the graph"))
+        .collect();
+    if Type::is_directed() {
+        let mut in_degrees: std::collections::HashMap<&Key, usize> =
+            graph.vertecies().map(|v| (v.key(), 0)).collect();
+        for edge in graph.edges() {
+            *in_degrees
+                .get_mut(edge.to())
+                .expect("edge leads to a vertex") += 1;
+        }
+        let in_degrees: Vec<usize> = in_degrees.into_values().collect();
+        buffer += &degree_stats("out-degree", &degrees);
+        buffer += &degree_stats("in-degree", &in_degrees);
+    } else {
+        buffer += &degree_stats("degree", &degrees);
+    }
+
+    let pairs = n * n.saturating_sub(1);
+    let density = match (pairs, Type::is_directed()) {
+        (0, _) => 0.0,
+        (_, true) => m as f64 / pairs as f64,
+        (_, false) => 2.0 * m as f64 / pairs as f64,
     };
+    buffer += &format!("density: {:.4}\n", density);
+
+    buffer
+}
 
-    println!("{}", graph);
+fn degree_stats(name: &str, degrees: &[usize]) -> String {
+    if degrees.is_empty() {
+        return format!("{}: min 0, max 0, avg 0.00\n", name);
+    }
+    let min = degrees.iter().min().expect("degrees are not empty");
+    let max = degrees.iter().max().expect("degrees are not empty");
+    let avg = degrees.iter().sum::<usize>() as f64 / degrees.len() as f64;
+    format!("{}: min {}, max {}, avg {:.2}\n", name, min, max, avg)
+}
 
+/// Prints every graph of the file, one graph per line.
+/// The format of each line is detected by its header or its first character.
+fn print_graph6(raw: &str) -> Result<(), Failure> {
+    let lines = raw
+        .lines()
+        .enumerate()
+        .filter(|(_, l)| !l.trim().is_empty());
+    for (i, line) in lines {
+        let result = match Graph6Format::detect(line) {
+            Graph6Format::Graph6 => graph6::from_graph6(line).map(|g| describe(&g)),
+            Graph6Format::Sparse6 => graph6::from_sparse6(line).map(|g| describe(&g)),
+            Graph6Format::Digraph6 => graph6::from_digraph6(line).map(|g| describe(&g)),
+        };
+        let graph = result.map_err(|e| Failure::Data(format!("line {}: {}", i + 1, e)))?;
+        println!("{}", graph);
+    }
     Ok(())
 }
+
+/// Describes the graph decoded from graph6, sparse6 or digraph6:
+/// the number of vertices and edges followed by the sorted list of edges.
+fn describe<Type: GraphType>(graph: &Graph<usize, (), Type>) -> String {
+    let mut edges: Vec<(usize, usize)> = graph
+        .edges()
+        .map(|e| {
+            if Type::is_directed() {
+                (*e.from(), *e.to())
+            } else {
+                (*e.from().min(e.to()), *e.from().max(e.to()))
+            }
+        })
+        .collect();
+    edges.sort_unstable();
+
+    let arrow = if Type::is_directed() { "->" } else { "--" };
+    let mut buffer = format!(
+        "{} graph: {} vertices, {} edges",
+        Type::type_name(),
+        graph.len(),
+        edges.len()
+    );
+    for (from, to) in edges {
+        buffer += &format!("\n{} {} {}", from, arrow, to);
+    }
+    buffer
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    /// Runs the command on the tgf file and returns the exit code and the message.
+    fn failure(command_name: &str, tgf: &str) -> Option<(ExitCode, String)> {
+        let path = std::env::temp_dir().join(format!(
+            "graph-rs-{}-{}.tgf",
+            std::process::id(),
+            command_name
+        ));
+        fs::write(&path, tgf).expect("temporary file must be writable");
+        let path = path.to_string_lossy().into_owned();
+        let matches = command().get_matches_from(["graph-rs", command_name, path.as_str()]);
+        let result = run(&matches).err();
+        fs::remove_file(&path).expect("temporary file must be removable");
+        result.map(|failure| (failure.exit_code(), String::from(failure.message())))
+    }
+
+    #[test]
+    fn malformed_tgf_test() {
+        assert_eq!(
+            failure("info", "1 A\n#\n1\n"),
+            Some((
+                ExitCode::from(65),
+                String::from("edge from 1 has no destination")
+            ))
+        );
+        assert_eq!(
+            failure("print", "1 A\n#\n1 2\n"),
+            Some((
+                ExitCode::from(65),
+                String::from("edge from 1 references an unknown vertex")
+            ))
+        );
+        assert_eq!(
+            failure("components", "1 A\n#\n1 x\n"),
+            Some((
+                ExitCode::from(65),
+                String::from("couldn't parse vertex, see --key-type")
+            ))
+        );
+        assert_eq!(failure("toposort", "1 A\n2 B\n#\n1 2\n"), None);
+    }
+
+    #[test]
+    fn generate_too_large_test() {
+        for (args, vertices) in [
+            (vec!["hypercube", "62"], 1usize << 62),
+            (vec!["gnp", "18446744073709551615", "0"], usize::MAX),
+        ] {
+            let matches =
+                command().get_matches_from(["graph-rs", "generate"].into_iter().chain(args));
+            let failure = run(&matches).expect_err("graph must be too large");
+            assert_eq!(failure.exit_code(), ExitCode::from(2));
+            assert_eq!(
+                failure.message(),
+                format!("graph with {} vertices is too large", vertices)
+            );
+        }
+    }
+
+    /// Generates the same random graph and writes it in the format.
+    fn generated<Type: GraphType>(format: Format) -> String {
+        let options = RandomOptions {
+            seed: 7,
+            weights: Some(1..=9),
+        };
+        let graph = random::gnm(12, 30, &options).expect("gnm(12, 30) is valid");
+        formats::write(format, &label::<Type>(graph))
+    }
+
+    #[test]
+    fn stable_output_test() {
+        for name in Format::WRITABLE {
+            let format = Format::from_name(name).expect("writable formats have names");
+            assert_eq!(
+                generated::<Directed>(format),
+                generated::<Directed>(format),
+                "directed {}",
+                name
+            );
+            assert_eq!(
+                generated::<Undirected>(format),
+                generated::<Undirected>(format),
+                "undirected {}",
+                name
+            );
+        }
+    }
+}
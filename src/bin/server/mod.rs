@@ -0,0 +1,762 @@
+//! HTTP server that answers queries about a graph and edits it, with JSON responses.
+//! It listens only on localhost and handles one request at a time.
+//!
+//! | Request                          | Response                                     |
+//! |----------------------------------|----------------------------------------------|
+//! | `GET /graph`                     | the graph in the node-link format            |
+//! | `GET /vertices/{id}`             | `{"id", "value", "degree"}`                  |
+//! | `GET /vertices/{id}/neighbors`   | `[{"id", "weight"}]`                         |
+//! | `GET /path?from={id}&to={id}`    | `{"path", "weight"}`, or `{"path", "edges"}` |
+//! | `GET /components[?strong=true]`  | `[[id]]`                                     |
+//! | `POST /vertices`                 | body `{"id", "value"}`                       |
+//! | `DELETE /vertices/{id}`          |                                              |
+//! | `POST /edges`                    | body `{"source", "target", "weight"}`        |
+//! | `DELETE /edges/{source}/{target}`|                                              |
+//!
+//! Failures are answered with `{"error": message}` and the matching status code.
+
+use std::{
+    fmt::Display,
+    io::{BufRead, BufReader, Read, Write},
+    net::{Ipv4Addr, TcpListener, TcpStream},
+    time::{Duration, Instant},
+};
+
+use graph_rs::{graph::Graph, json, marker::GraphType, vertex::Vertex};
+
+use crate::{find_path, shell::EdgeEditing, DirectedOnly, PathLength};
+
+/// Requests with larger bodies are answered with 413.
+const MAX_BODY_LEN: usize = 1 << 20;
+
+/// Request line and headers are cut off after this many bytes.
+const MAX_HEAD_LEN: u64 = 64 << 10;
+
+/// A client that hasn't sent the whole request in this time is disconnected,
+/// so a slow client can't block the other clients for longer.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// JSON value of a response. Its Display writes compact JSON.
+#[derive(Debug, PartialEq)]
+enum Json {
+    Bool(bool),
+    Number(String),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Display for Json {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Json::Bool(b) => write!(f, "{}", b),
+            Json::Number(raw) => write!(f, "{}", raw),
+            Json::String(s) => write!(f, "{}", json::quote(s)),
+            Json::Array(items) => {
+                write!(f, "[")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                write!(f, "]")
+            }
+            Json::Object(members) => {
+                write!(f, "{{")?;
+                for (i, (name, value)) in members.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}: {}", json::quote(name), value)?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+/// Status and body of a response.
+#[derive(Debug, PartialEq)]
+pub struct Response {
+    status: u16,
+    body: Json,
+}
+
+impl Response {
+    fn ok(body: Json) -> Self {
+        Response { status: 200, body }
+    }
+
+    fn created(body: Json) -> Self {
+        Response { status: 201, body }
+    }
+
+    fn error(status: u16, message: impl Into<String>) -> Self {
+        Response {
+            status,
+            body: object(vec![("error", Json::String(message.into()))]),
+        }
+    }
+
+    fn reason(&self) -> &'static str {
+        match self.status {
+            200 => "OK",
+            201 => "Created",
+            400 => "Bad Request",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            413 => "Content Too Large",
+            _ => "Internal Server Error",
+        }
+    }
+}
+
+fn object(members: Vec<(&str, Json)>) -> Json {
+    Json::Object(
+        members
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), value))
+            .collect(),
+    )
+}
+
+fn keys(keys: &[&String]) -> Json {
+    Json::Array(keys.iter().map(|k| Json::String(k.to_string())).collect())
+}
+
+/// Decodes %XX escapes of the URL, and pluses in queries.
+fn decode(text: &str, query: bool) -> Result<String, Response> {
+    let invalid = || Response::error(400, format!("invalid escape in '{}'", text));
+
+    let mut bytes = Vec::with_capacity(text.len());
+    let mut rest = text.bytes();
+    while let Some(b) = rest.next() {
+        match b {
+            b'%' => {
+                let hex = [
+                    rest.next().ok_or_else(invalid)?,
+                    rest.next().ok_or_else(invalid)?,
+                ];
+                let hex = std::str::from_utf8(&hex).map_err(|_| invalid())?;
+                bytes.push(u8::from_str_radix(hex, 16).map_err(|_| invalid())?);
+            }
+            b'+' if query => bytes.push(b' '),
+            b => bytes.push(b),
+        }
+    }
+    String::from_utf8(bytes).map_err(|_| invalid())
+}
+
+/// Reads from the stream until the deadline, however the client splits the data.
+struct Deadline<'a> {
+    stream: &'a TcpStream,
+    deadline: Instant,
+}
+
+impl Read for Deadline<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let left = self.deadline.saturating_duration_since(Instant::now());
+        if left.is_zero() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::TimedOut,
+                "the request took too long",
+            ));
+        }
+        self.stream.set_read_timeout(Some(left))?;
+        self.stream.read(buf)
+    }
+}
+
+/// The graph being served.
+pub struct Server<Type: GraphType> {
+    graph: Graph<String, String, Type>,
+    timeout: Duration,
+}
+
+impl<Type> Server<Type>
+where
+    Type: GraphType,
+    Graph<String, String, Type>: EdgeEditing + DirectedOnly<String>,
+{
+    pub fn new(graph: Graph<String, String, Type>) -> Self {
+        Server {
+            graph,
+            timeout: REQUEST_TIMEOUT,
+        }
+    }
+
+    /// Accepts connections until the listener fails.
+    pub fn serve(&mut self, listener: TcpListener) -> Result<(), String> {
+        for stream in listener.incoming() {
+            let stream = stream.map_err(|e| format!("couldn't accept a connection: {}", e))?;
+            // A client that disconnects in the middle must not stop the server
+            if let Err(e) = self.respond(stream) {
+                eprintln!("error: {}", e);
+            }
+        }
+        Ok(())
+    }
+
+    /// Reads one request from the stream and writes the response.
+    fn respond(&mut self, stream: TcpStream) -> std::io::Result<()> {
+        let deadline = Deadline {
+            stream: &stream,
+            deadline: Instant::now() + self.timeout,
+        };
+        let mut reader = BufReader::new(deadline).take(MAX_HEAD_LEN);
+
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line)?;
+        let mut length = Ok(0);
+        loop {
+            let mut header = String::new();
+            if reader.read_line(&mut header)? == 0 || header.trim().is_empty() {
+                break;
+            }
+            if let Some((name, value)) = header.split_once(':') {
+                if name.trim().eq_ignore_ascii_case("content-length") {
+                    length = match value.trim().parse::<usize>() {
+                        Ok(length) if length > MAX_BODY_LEN => Err(Response::error(
+                            413,
+                            format!("request body is larger than {} bytes", MAX_BODY_LEN),
+                        )),
+                        Ok(length) => Ok(length),
+                        Err(_) => Err(Response::error(400, "invalid Content-Length")),
+                    };
+                }
+            }
+        }
+
+        let response = match (
+            length,
+            &request_line.split_whitespace().collect::<Vec<_>>()[..],
+        ) {
+            (Err(response), _) => response,
+            (Ok(length), [method, target, _]) => {
+                let mut body = vec![0; length];
+                reader.set_limit(length as u64);
+                reader.read_exact(&mut body)?;
+                self.handle(method, target, &String::from_utf8_lossy(&body))
+            }
+            (Ok(_), _) => Response::error(400, "malformed request line"),
+        };
+
+        let body = format!("{}\n", response.body);
+        let mut stream = &stream;
+        write!(
+            stream,
+            "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            response.status,
+            response.reason(),
+            body.len(),
+            body
+        )?;
+        stream.flush()
+    }
+
+    /// Routes the request and executes it.
+    pub fn handle(&mut self, method: &str, target: &str, body: &str) -> Response {
+        match self.route(method, target, body) {
+            Ok(response) | Err(response) => response,
+        }
+    }
+
+    fn route(&mut self, method: &str, target: &str, body: &str) -> Result<Response, Response> {
+        let (path, query) = target.split_once('?').unwrap_or((target, ""));
+        let segments = path
+            .split('/')
+            .filter(|s| !s.is_empty())
+            .map(|s| decode(s, false))
+            .collect::<Result<Vec<_>, _>>()?;
+        let segments: Vec<&str> = segments.iter().map(String::as_str).collect();
+
+        match (method, &segments[..]) {
+            ("GET", ["graph"]) => {
+                let mut vertices: Vec<&Vertex<String, String>> = self.graph.vertecies().collect();
+                vertices.sort_by(|a, b| a.key().cmp(b.key()));
+                // An undirected edge is written from the smaller key
+                let mut edges: Vec<(&String, &String, i64)> = self
+                    .graph
+                    .edges()
+                    .map(|e| match (e.from(), e.to()) {
+                        (from, to) if !Type::is_directed() && to < from => (to, from, e.weight),
+                        (from, to) => (from, to, e.weight),
+                    })
+                    .collect();
+                edges.sort();
+
+                Ok(Response::ok(object(vec![
+                    ("directed", Json::Bool(Type::is_directed())),
+                    ("multigraph", Json::Bool(false)),
+                    (
+                        "nodes",
+                        Json::Array(
+                            vertices
+                                .into_iter()
+                                .map(|v| {
+                                    object(vec![
+                                        ("id", Json::String(v.key().clone())),
+                                        ("value", Json::String(v.value.clone())),
+                                    ])
+                                })
+                                .collect(),
+                        ),
+                    ),
+                    (
+                        "links",
+                        Json::Array(
+                            edges
+                                .into_iter()
+                                .map(|(from, to, weight)| {
+                                    object(vec![
+                                        ("source", Json::String(from.clone())),
+                                        ("target", Json::String(to.clone())),
+                                        ("weight", Json::Number(weight.to_string())),
+                                    ])
+                                })
+                                .collect(),
+                        ),
+                    ),
+                ])))
+            }
+            ("GET", ["vertices", key]) => {
+                let vertex = self.vertex(key)?;
+                Ok(Response::ok(object(vec![
+                    ("id", Json::String(vertex.key().clone())),
+                    ("value", Json::String(vertex.value.clone())),
+                    (
+                        "degree",
+                        Json::Number(
+                            self.graph
+                                .degree(vertex.key())
+                                .expect("the vertex was just found")
+                                .to_string(),
+                        ),
+                    ),
+                ])))
+            }
+            ("GET", ["vertices", key, "neighbors"]) => {
+                let neighbours = self
+                    .vertex(key)?
+                    .adjancency_list()
+                    .iter()
+                    .map(|e| {
+                        object(vec![
+                            ("id", Json::String(e.to().clone())),
+                            ("weight", Json::Number(e.weight.to_string())),
+                        ])
+                    })
+                    .collect();
+                Ok(Response::ok(Json::Array(neighbours)))
+            }
+            ("GET", ["path"]) => {
+                let query = parse_query(query)?;
+                let param = |name: &str| {
+                    query
+                        .iter()
+                        .find(|(n, _)| n == name)
+                        .map(|(_, v)| v.clone())
+                        .ok_or_else(|| Response::error(400, format!("'{}' is required", name)))
+                };
+                let (from, to) = (param("from")?, param("to")?);
+                self.vertex(&from)?;
+                self.vertex(&to)?;
+                self.path(&from, &to)
+            }
+            ("GET", ["components"]) => {
+                let strong = parse_query(query)?
+                    .iter()
+                    .any(|(n, v)| n == "strong" && v == "true");
+                let components = if strong {
+                    self.graph
+                        .strong_components()
+                        .map_err(|e| Response::error(400, e.message()))?
+                } else {
+                    self.graph.connected_components()
+                };
+                Ok(Response::ok(Json::Array(
+                    components.iter().map(|c| keys(c)).collect(),
+                )))
+            }
+            ("POST", ["vertices"]) => {
+                let body = parse_body(body)?;
+                let key = member(&body, "id")?;
+                let value = member(&body, "value").unwrap_or_default();
+                let status = match self.graph.insert(key.clone(), value) {
+                    Some(_) => 200,
+                    None => 201,
+                };
+                Ok(Response {
+                    status,
+                    body: object(vec![("id", Json::String(key))]),
+                })
+            }
+            ("DELETE", ["vertices", key]) => {
+                self.vertex(key)?;
+                self.graph.remove_vertex(&key.to_string());
+                Ok(Response::ok(object(vec![(
+                    "id",
+                    Json::String(key.to_string()),
+                )])))
+            }
+            ("POST", ["edges"]) => {
+                let body = parse_body(body)?;
+                let (from, to) = (member(&body, "source")?, member(&body, "target")?);
+                let weight = match member(&body, "weight") {
+                    Ok(weight) => weight
+                        .parse()
+                        .map_err(|_| Response::error(400, "'weight' must be an integer"))?,
+                    Err(_) => 0,
+                };
+                self.vertex(&from)?;
+                self.vertex(&to)?;
+                let edge = object(vec![
+                    ("source", Json::String(from.clone())),
+                    ("target", Json::String(to.clone())),
+                    ("weight", Json::Number(weight.to_string())),
+                ]);
+                match self
+                    .graph
+                    .add_edge(from, to, weight)
+                    .map_err(|e| Response::error(400, e))?
+                {
+                    Some(_) => Ok(Response::ok(edge)),
+                    None => Ok(Response::created(edge)),
+                }
+            }
+            ("DELETE", ["edges", from, to]) => {
+                self.vertex(from)?;
+                self.vertex(to)?;
+                match self
+                    .graph
+                    .delete_edge(from, to)
+                    .map_err(|e| Response::error(400, e))?
+                {
+                    Some(edge) => Ok(Response::ok(object(vec![
+                        ("source", Json::String(from.to_string())),
+                        ("target", Json::String(to.to_string())),
+                        ("weight", Json::Number(edge.weight.to_string())),
+                    ]))),
+                    None => Err(Response::error(
+                        404,
+                        format!("there is no edge from {} to {}", from, to),
+                    )),
+                }
+            }
+            (_, ["graph"] | ["vertices", ..] | ["path"] | ["components"] | ["edges", ..]) => Err(
+                Response::error(405, format!("{} is not allowed here", method)),
+            ),
+            _ => Err(Response::error(404, format!("{} is not found", path))),
+        }
+    }
+
+    fn vertex(&self, key: &str) -> Result<&graph_rs::vertex::Vertex<String, String>, Response> {
+        self.graph
+            .get_vertex(&key.to_string())
+            .ok_or_else(|| Response::error(404, format!("vertex {} is not in the graph", key)))
+    }
+
+    fn path(&self, from: &String, to: &String) -> Result<Response, Response> {
+        let (path, length) = find_path(&self.graph, from, to, false)
+            .map_err(|e| Response::error(400, e))?
+            .ok_or_else(|| {
+                Response::error(404, format!("there is no path from {} to {}", from, to))
+            })?;
+        let length = match length {
+            PathLength::Weight(weight) => ("weight", Json::Number(weight.to_string())),
+            PathLength::Edges(edges) => ("edges", Json::Number(edges.to_string())),
+        };
+        Ok(Response::ok(object(vec![("path", keys(&path)), length])))
+    }
+}
+
+fn parse_query(query: &str) -> Result<Vec<(String, String)>, Response> {
+    query
+        .split('&')
+        .filter(|p| !p.is_empty())
+        .map(|pair| {
+            let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+            Ok((decode(name, true)?, decode(value, true)?))
+        })
+        .collect()
+}
+
+fn parse_body(body: &str) -> Result<Vec<(String, String)>, Response> {
+    json::parse_members(body).map_err(|e| Response::error(400, format!("invalid json: {}", e)))
+}
+
+/// Returns the scalar member of the request body as a key.
+fn member(body: &[(String, String)], name: &str) -> Result<String, Response> {
+    body.iter()
+        .find(|(n, _)| n == name)
+        .map(|(_, value)| value.clone())
+        .ok_or_else(|| Response::error(400, format!("'{}' is required", name)))
+}
+
+/// Binds to the port on localhost. Port 0 picks a free port.
+pub fn bind(port: u16) -> Result<TcpListener, String> {
+    TcpListener::bind((Ipv4Addr::LOCALHOST, port))
+        .map_err(|e| format!("couldn't listen on port {}: {}", port, e))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use graph_rs::marker::{Directed, Undirected};
+    use pretty_assertions::assert_eq;
+
+    fn sample() -> Server<Directed> {
+        let mut graph: Graph<String, String, Directed> = Graph::new();
+        for key in ["a", "b", "c", "d e"] {
+            graph.insert(key.to_string(), key.to_uppercase());
+        }
+        graph.add_edge("a".into(), "b".into(), 1).unwrap();
+        graph.add_edge("b".into(), "c".into(), 2).unwrap();
+        graph.add_edge("a".into(), "c".into(), 5).unwrap();
+        Server::new(graph)
+    }
+
+    fn request<Type>(server: &mut Server<Type>, method: &str, target: &str, body: &str) -> String
+    where
+        Type: GraphType,
+        Graph<String, String, Type>: EdgeEditing + DirectedOnly<String>,
+    {
+        let response = server.handle(method, target, body);
+        format!("{} {}", response.status, response.body)
+    }
+
+    #[test]
+    fn queries_test() {
+        let mut server = sample();
+
+        assert_eq!(
+            request(&mut server, "GET", "/graph", ""),
+            concat!(
+                r#"200 {"directed": true, "multigraph": false, "nodes": ["#,
+                r#"{"id": "a", "value": "A"}, {"id": "b", "value": "B"}, "#,
+                r#"{"id": "c", "value": "C"}, {"id": "d e", "value": "D E"}], "links": ["#,
+                r#"{"source": "a", "target": "b", "weight": 1}, "#,
+                r#"{"source": "a", "target": "c", "weight": 5}, "#,
+                r#"{"source": "b", "target": "c", "weight": 2}]}"#
+            )
+        );
+
+        assert_eq!(
+            request(&mut server, "GET", "/vertices/a", ""),
+            r#"200 {"id": "a", "value": "A", "degree": 2}"#
+        );
+        assert_eq!(
+            request(&mut server, "GET", "/vertices/d%20e", ""),
+            r#"200 {"id": "d e", "value": "D E", "degree": 0}"#
+        );
+        assert_eq!(
+            request(&mut server, "GET", "/vertices/z", ""),
+            r#"404 {"error": "vertex z is not in the graph"}"#
+        );
+        assert_eq!(
+            request(&mut server, "GET", "/vertices/b/neighbors", ""),
+            r#"200 [{"id": "c", "weight": 2}]"#
+        );
+        assert_eq!(
+            request(&mut server, "GET", "/path?from=a&to=c", ""),
+            r#"200 {"path": ["a", "b", "c"], "weight": 3}"#
+        );
+        assert_eq!(
+            request(&mut server, "GET", "/path?from=c&to=a", ""),
+            r#"404 {"error": "there is no path from c to a"}"#
+        );
+        assert_eq!(
+            request(&mut server, "GET", "/path?from=a", ""),
+            r#"400 {"error": "'to' is required"}"#
+        );
+
+        let components = server.handle("GET", "/components?strong=true", "");
+        assert_eq!(components.status, 200);
+        assert!(matches!(components.body, Json::Array(c) if c.len() == 4));
+
+        assert_eq!(
+            request(&mut server, "PUT", "/vertices/a", ""),
+            r#"405 {"error": "PUT is not allowed here"}"#
+        );
+        assert_eq!(
+            request(&mut server, "GET", "/nothing", ""),
+            r#"404 {"error": "/nothing is not found"}"#
+        );
+    }
+
+    #[test]
+    fn mutations_test() {
+        let mut server = sample();
+
+        assert_eq!(
+            request(
+                &mut server,
+                "POST",
+                "/vertices",
+                r#"{"id": "x", "value": "X"}"#
+            ),
+            r#"201 {"id": "x"}"#
+        );
+        assert_eq!(
+            request(
+                &mut server,
+                "POST",
+                "/vertices",
+                r#"{"id": "x", "value": "Y"}"#
+            ),
+            r#"200 {"id": "x"}"#
+        );
+        assert_eq!(
+            request(
+                &mut server,
+                "POST",
+                "/edges",
+                r#"{"source": "c", "target": "x", "weight": 4}"#
+            ),
+            r#"201 {"source": "c", "target": "x", "weight": 4}"#
+        );
+        assert_eq!(
+            request(&mut server, "GET", "/path?from=a&to=x", ""),
+            r#"200 {"path": ["a", "b", "c", "x"], "weight": 7}"#
+        );
+        assert_eq!(
+            request(&mut server, "DELETE", "/edges/b/c", ""),
+            r#"200 {"source": "b", "target": "c", "weight": 2}"#
+        );
+        assert_eq!(
+            request(&mut server, "DELETE", "/edges/b/c", ""),
+            r#"404 {"error": "there is no edge from b to c"}"#
+        );
+        assert_eq!(
+            request(&mut server, "DELETE", "/vertices/c", ""),
+            r#"200 {"id": "c"}"#
+        );
+        assert_eq!(
+            request(&mut server, "GET", "/vertices/a/neighbors", ""),
+            r#"200 [{"id": "b", "weight": 1}]"#
+        );
+        assert_eq!(
+            request(&mut server, "POST", "/edges", r#"{"source": "a"}"#),
+            r#"400 {"error": "'target' is required"}"#
+        );
+        assert_eq!(
+            request(
+                &mut server,
+                "POST",
+                "/edges",
+                r#"{"source": "a", "target": "b", "weight": 1.5}"#
+            ),
+            r#"400 {"error": "'weight' must be an integer"}"#
+        );
+        assert_eq!(
+            request(&mut server, "POST", "/vertices", "[]"),
+            r#"400 {"error": "invalid json: expected an object"}"#
+        );
+        assert_eq!(
+            request(&mut server, "POST", "/vertices", "{"),
+            r#"400 {"error": "invalid json: expected object member name"}"#
+        );
+    }
+
+    #[test]
+    fn undirected_test() {
+        let mut server: Server<Undirected> = Server::new(Graph::new());
+        request(&mut server, "POST", "/vertices", r#"{"id": 1}"#);
+        request(&mut server, "POST", "/vertices", r#"{"id": 2}"#);
+        request(
+            &mut server,
+            "POST",
+            "/edges",
+            r#"{"source": 1, "target": 2}"#,
+        );
+
+        assert_eq!(
+            request(&mut server, "GET", "/vertices/2/neighbors", ""),
+            r#"200 [{"id": "1", "weight": 0}]"#
+        );
+        assert_eq!(
+            request(&mut server, "GET", "/graph", ""),
+            concat!(
+                r#"200 {"directed": false, "multigraph": false, "nodes": ["#,
+                r#"{"id": "1", "value": ""}, {"id": "2", "value": ""}], "links": ["#,
+                r#"{"source": "1", "target": "2", "weight": 0}]}"#
+            )
+        );
+        assert_eq!(
+            request(&mut server, "GET", "/components?strong=true", ""),
+            r#"400 {"error": "strongly connected components are defined only for directed graphs"}"#
+        );
+    }
+
+    #[test]
+    fn serve_test() {
+        let listener = bind(0).unwrap();
+        let address = listener.local_addr().unwrap();
+        std::thread::spawn(move || sample().serve(listener));
+
+        let mut stream = TcpStream::connect(address).unwrap();
+        write!(
+            stream,
+            "GET /vertices/b HTTP/1.1\r\nHost: localhost\r\n\r\n"
+        )
+        .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.ends_with("\r\n\r\n{\"id\": \"b\", \"value\": \"B\", \"degree\": 1}\n"));
+
+        for (length, status) in [
+            ("18446744073709551616", "400 Bad Request"),
+            ("1048577", "413 Content Too Large"),
+        ] {
+            let mut stream = TcpStream::connect(address).unwrap();
+            write!(
+                stream,
+                "POST /vertices HTTP/1.1\r\nContent-Length: {}\r\n\r\n",
+                length
+            )
+            .unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            assert!(
+                response.starts_with(&format!("HTTP/1.1 {}\r\n", status)),
+                "{}",
+                response
+            );
+        }
+    }
+
+    #[test]
+    fn slow_client_test() {
+        let listener = bind(0).unwrap();
+        let address = listener.local_addr().unwrap();
+        let mut server = sample();
+        server.timeout = Duration::from_millis(200);
+        std::thread::spawn(move || server.serve(listener));
+
+        // Sends a byte at a time, each well within the timeout
+        let mut slow = TcpStream::connect(address).unwrap();
+        let trickle = std::thread::spawn(move || {
+            for _ in 0..40 {
+                if slow.write_all(b"G").is_err() {
+                    break;
+                }
+                std::thread::sleep(Duration::from_millis(50));
+            }
+        });
+        std::thread::sleep(Duration::from_millis(50));
+
+        let start = Instant::now();
+        let mut stream = TcpStream::connect(address).unwrap();
+        write!(stream, "GET /vertices/b HTTP/1.1\r\n\r\n").unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(start.elapsed() < Duration::from_secs(1));
+
+        trickle.join().unwrap();
+    }
+}
@@ -0,0 +1,432 @@
+//! Interactive shell that edits and queries a graph in memory.
+//! Vertex keys and values are strings, so a graph of any file can be opened.
+
+use std::fs;
+
+use graph_rs::{
+    edge::Edge,
+    graph::Graph,
+    marker::{Directed, GraphType, Undirected},
+};
+use rustyline::{
+    completion::Completer, error::ReadlineError, highlight::Highlighter, hint::Hinter,
+    history::DefaultHistory, validate::Validator, Context, Editor, Helper,
+};
+
+use crate::{
+    find_path,
+    formats::{self, Format},
+};
+
+const COMMANDS: [&str; 13] = [
+    "add-vertex",
+    "add-edge",
+    "rm",
+    "neighbors",
+    "bfs",
+    "dfs",
+    "path",
+    "print",
+    "save",
+    "history",
+    "help",
+    "exit",
+    "quit",
+];
+
+const HELP: &str = "\
+add-vertex KEY [VALUE]      add a vertex or change its value
+add-edge FROM TO [WEIGHT]   add an edge or change its weight
+rm KEY                      remove a vertex with its edges
+rm FROM TO                  remove an edge
+neighbors KEY               print adjacent vertices
+bfs KEY, dfs KEY            print vertices in breadth-first or depth-first order
+path FROM TO                print the shortest path
+print                       print the graph
+save [FILE]                 save the graph, the format is chosen by the extension
+history                     print previous commands
+exit, quit                  leave the shell";
+
+/// Editing of edges, which is implemented separately for directed and undirected graphs.
+pub trait EdgeEditing {
+    fn add_edge(
+        &mut self,
+        from: String,
+        to: String,
+        weight: i64,
+    ) -> Result<Option<Edge<String>>, &'static str>;
+
+    fn delete_edge(&mut self, from: &str, to: &str) -> Result<Option<Edge<String>>, &'static str>;
+}
+
+impl EdgeEditing for Graph<String, String, Directed> {
+    fn add_edge(
+        &mut self,
+        from: String,
+        to: String,
+        weight: i64,
+    ) -> Result<Option<Edge<String>>, &'static str> {
+        self.insert_edge(from, to, weight)
+    }
+
+    fn delete_edge(&mut self, from: &str, to: &str) -> Result<Option<Edge<String>>, &'static str> {
+        self.remove_edge(&from.to_string(), &to.to_string())
+    }
+}
+
+impl EdgeEditing for Graph<String, String, Undirected> {
+    fn add_edge(
+        &mut self,
+        from: String,
+        to: String,
+        weight: i64,
+    ) -> Result<Option<Edge<String>>, &'static str> {
+        self.insert_edge(from, to, weight)
+    }
+
+    fn delete_edge(&mut self, from: &str, to: &str) -> Result<Option<Edge<String>>, &'static str> {
+        self.remove_edge(&from.to_string(), &to.to_string())
+    }
+}
+
+/// Result of a command that doesn't fail.
+pub enum Outcome {
+    Print(String),
+    History,
+    Exit,
+}
+
+/// The graph being edited and the file it was opened from.
+pub struct Session<Type: GraphType> {
+    graph: Graph<String, String, Type>,
+    file: Option<String>,
+}
+
+impl<Type> Session<Type>
+where
+    Type: GraphType,
+    Graph<String, String, Type>: EdgeEditing,
+{
+    pub fn new(graph: Graph<String, String, Type>, file: Option<String>) -> Self {
+        Session { graph, file }
+    }
+
+    /// Keys of the graph, sorted, for completion.
+    pub fn keys(&self) -> Vec<String> {
+        let mut keys: Vec<String> = self.graph.vertecies().map(|v| v.key().clone()).collect();
+        keys.sort_unstable();
+        keys
+    }
+
+    /// Executes one line of input.
+    /// Returns Err with a message if the command can't be executed.
+    pub fn execute(&mut self, line: &str) -> Result<Outcome, String> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let Some((&command, args)) = words.split_first() else {
+            return Ok(Outcome::Print(String::new()));
+        };
+
+        let output = match (command, args) {
+            ("add-vertex", [key, value @ ..]) => {
+                match self.graph.insert(key.to_string(), value.join(" ")) {
+                    Some(_) => format!("updated vertex {}", key),
+                    None => format!("added vertex {}", key),
+                }
+            }
+            ("add-edge", [from, to, weight @ ..]) if weight.len() <= 1 => {
+                let weight = match weight.first() {
+                    Some(weight) => weight
+                        .parse()
+                        .map_err(|_| format!("weight {} is not an integer", weight))?,
+                    None => 0,
+                };
+                self.check(&[from, to])?;
+                match self
+                    .graph
+                    .add_edge(from.to_string(), to.to_string(), weight)?
+                {
+                    Some(_) => format!("updated edge {} {}", from, to),
+                    None => format!("added edge {} {}", from, to),
+                }
+            }
+            ("rm", [key]) => match self.graph.remove_vertex(&key.to_string()) {
+                Some(_) => format!("removed vertex {}", key),
+                None => return Err(format!("vertex {} is not in the graph", key)),
+            },
+            ("rm", [from, to]) => {
+                self.check(&[from, to])?;
+                match self.graph.delete_edge(from, to)? {
+                    Some(_) => format!("removed edge {} {}", from, to),
+                    None => return Err(format!("there is no edge from {} to {}", from, to)),
+                }
+            }
+            ("neighbors", [key]) => {
+                self.check(&[key])?;
+                let vertex = self
+                    .graph
+                    .get_vertex(&key.to_string())
+                    .expect("vertex is checked");
+                vertex
+                    .adjancency_list()
+                    .iter()
+                    .map(|e| match e.weight {
+                        0 => e.to().clone(),
+                        weight => format!("{} (w={})", e.to(), weight),
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            }
+            ("bfs", [key]) => {
+                self.check(&[key])?;
+                let key = key.to_string();
+                let order: Vec<&String> = self.graph.bfs(&key).map(|v| v.key()).collect();
+                join(&order, "\n")
+            }
+            ("dfs", [key]) => {
+                self.check(&[key])?;
+                let key = key.to_string();
+                let order: Vec<&String> = self.graph.dfs(&key).map(|v| v.key()).collect();
+                join(&order, "\n")
+            }
+            ("path", [from, to]) => {
+                self.check(&[from, to])?;
+                self.path(&from.to_string(), &to.to_string())?
+            }
+            ("print", []) => self.graph.to_string().trim_end().to_string(),
+            ("save", []) => match self.file.clone() {
+                Some(file) => self.save(&file)?,
+                None => return Err(String::from("no file was opened, use save FILE")),
+            },
+            ("save", [file]) => self.save(file)?,
+            ("history", []) => return Ok(Outcome::History),
+            ("help", []) => String::from(HELP),
+            ("exit" | "quit", []) => return Ok(Outcome::Exit),
+            _ if COMMANDS.contains(&command) => {
+                return Err(format!("wrong arguments of {}, see help", command))
+            }
+            _ => return Err(format!("unknown command {}, see help", command)),
+        };
+
+        Ok(Outcome::Print(output))
+    }
+
+    /// Returns Err if one of the keys is not in the graph.
+    fn check(&self, keys: &[&&str]) -> Result<(), String> {
+        match keys.iter().find(|k| !self.graph.contains(&k.to_string())) {
+            Some(key) => Err(format!("vertex {} is not in the graph", key)),
+            None => Ok(()),
+        }
+    }
+
+    fn path(&self, from: &String, to: &String) -> Result<String, String> {
+        let (path, length) = find_path(&self.graph, from, to, false)?
+            .ok_or_else(|| format!("there is no path from {} to {}", from, to))?;
+        Ok(format!("{}\n{}", join(&path, " -> "), length))
+    }
+
+    /// Writes the graph in the format of the file extension, tgf by default.
+    fn save(&mut self, file: &str) -> Result<String, String> {
+        let format = Format::from_extension(file).unwrap_or(Format::Tgf);
+        fs::write(file, formats::write(format, &self.graph))
+            .map_err(|e| format!("couldn't write '{}': {}", file, e))?;
+        self.file = Some(file.to_string());
+        Ok(format!("saved {} as {}", file, format))
+    }
+}
+
+fn join(keys: &[&String], separator: &str) -> String {
+    keys.iter()
+        .map(|k| k.as_str())
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+/// Completes command names at the start of the line and vertex keys after them.
+struct ShellHelper {
+    keys: Vec<String>,
+}
+
+impl Completer for ShellHelper {
+    type Candidate = String;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<String>)> {
+        let start = line[..pos].rfind(' ').map_or(0, |i| i + 1);
+        let prefix = &line[start..pos];
+        let candidates: Vec<&str> = if start == 0 {
+            COMMANDS.to_vec()
+        } else {
+            self.keys.iter().map(String::as_str).collect()
+        };
+
+        Ok((
+            start,
+            candidates
+                .into_iter()
+                .filter(|c| c.starts_with(prefix))
+                .map(String::from)
+                .collect(),
+        ))
+    }
+}
+
+impl Hinter for ShellHelper {
+    type Hint = String;
+}
+
+impl Highlighter for ShellHelper {}
+
+impl Validator for ShellHelper {}
+
+impl Helper for ShellHelper {}
+
+/// Reads commands until exit or the end of input.
+pub fn run<Type>(mut session: Session<Type>) -> Result<(), String>
+where
+    Type: GraphType,
+    Graph<String, String, Type>: EdgeEditing,
+{
+    let mut editor: Editor<ShellHelper, DefaultHistory> =
+        Editor::new().map_err(|e| format!("couldn't start the shell: {}", e))?;
+    editor.set_helper(Some(ShellHelper {
+        keys: session.keys(),
+    }));
+
+    loop {
+        let line = match editor.readline("graph> ") {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted) => continue,
+            Err(ReadlineError::Eof) => return Ok(()),
+            Err(e) => return Err(format!("couldn't read the command: {}", e)),
+        };
+        if !line.trim().is_empty() {
+            // Failing to remember the command is not worth stopping the shell
+            let _ = editor.add_history_entry(line.as_str());
+        }
+
+        match session.execute(&line) {
+            Ok(Outcome::Print(output)) if output.is_empty() => {}
+            Ok(Outcome::Print(output)) => println!("{}", output),
+            Ok(Outcome::History) => {
+                for (i, entry) in editor.history().iter().enumerate() {
+                    println!("{:>4}  {}", i + 1, entry);
+                }
+            }
+            Ok(Outcome::Exit) => return Ok(()),
+            Err(message) => eprintln!("error: {}", message),
+        }
+
+        if let Some(helper) = editor.helper_mut() {
+            helper.keys = session.keys();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn output<Type>(session: &mut Session<Type>, line: &str) -> String
+    where
+        Type: GraphType,
+        Graph<String, String, Type>: EdgeEditing,
+    {
+        match session.execute(line) {
+            Ok(Outcome::Print(output)) => output,
+            Ok(_) => panic!("{} doesn't print", line),
+            Err(message) => format!("error: {}", message),
+        }
+    }
+
+    #[test]
+    fn execute_test() {
+        let mut session: Session<Directed> = Session::new(Graph::new(), None);
+
+        assert_eq!(output(&mut session, "add-vertex a first"), "added vertex a");
+        assert_eq!(output(&mut session, "add-vertex b"), "added vertex b");
+        assert_eq!(output(&mut session, "add-vertex c"), "added vertex c");
+        assert_eq!(output(&mut session, "add-vertex a"), "updated vertex a");
+        assert_eq!(output(&mut session, "add-edge a b"), "added edge a b");
+        assert_eq!(output(&mut session, "add-edge b c"), "added edge b c");
+        assert_eq!(
+            output(&mut session, "add-edge a z"),
+            "error: vertex z is not in the graph"
+        );
+        assert_eq!(
+            output(&mut session, "add-edge a c x"),
+            "error: weight x is not an integer"
+        );
+
+        assert_eq!(output(&mut session, "neighbors a"), "b");
+        assert_eq!(output(&mut session, "bfs a"), "a\nb\nc");
+        assert_eq!(output(&mut session, "path a c"), "a -> b -> c\nedges: 2");
+
+        assert_eq!(output(&mut session, "add-edge a c 5"), "added edge a c");
+        assert_eq!(output(&mut session, "neighbors a"), "b\nc (w=5)");
+        assert_eq!(output(&mut session, "path a c"), "a -> b -> c\nweight: 0");
+
+        assert_eq!(output(&mut session, "rm a c"), "removed edge a c");
+        assert_eq!(output(&mut session, "rm b"), "removed vertex b");
+        assert_eq!(
+            output(&mut session, "path a c"),
+            "error: there is no path from a to c"
+        );
+        assert_eq!(session.keys(), vec!["a", "c"]);
+
+        assert_eq!(
+            output(&mut session, "save"),
+            "error: no file was opened, use save FILE"
+        );
+        assert_eq!(
+            output(&mut session, "rm"),
+            "error: wrong arguments of rm, see help"
+        );
+        assert_eq!(
+            output(&mut session, "frobnicate"),
+            "error: unknown command frobnicate, see help"
+        );
+        assert!(matches!(session.execute("quit"), Ok(Outcome::Exit)));
+    }
+
+    #[test]
+    fn execute_undirected_test() {
+        let mut session: Session<Undirected> = Session::new(Graph::new(), None);
+        output(&mut session, "add-vertex a");
+        output(&mut session, "add-vertex b");
+
+        assert_eq!(output(&mut session, "add-edge a a"), "added edge a a");
+        assert_eq!(output(&mut session, "add-edge a b"), "added edge a b");
+        assert_eq!(output(&mut session, "neighbors b"), "a");
+        assert_eq!(output(&mut session, "rm b a"), "removed edge b a");
+        assert_eq!(output(&mut session, "neighbors a"), "a");
+        assert_eq!(output(&mut session, "rm a a"), "removed edge a a");
+        assert_eq!(output(&mut session, "neighbors a"), "");
+    }
+
+    #[test]
+    fn complete_test() {
+        let helper = ShellHelper {
+            keys: vec![String::from("alpha"), String::from("beta")],
+        };
+        let history = DefaultHistory::new();
+        let context = Context::new(&history);
+
+        assert_eq!(
+            helper.complete("ad", 2, &context).unwrap(),
+            (
+                0,
+                vec![String::from("add-vertex"), String::from("add-edge")]
+            )
+        );
+        assert_eq!(
+            helper.complete("bfs al", 6, &context).unwrap(),
+            (4, vec![String::from("alpha")])
+        );
+    }
+}
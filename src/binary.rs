@@ -0,0 +1,481 @@
+//! Compact binary snapshot of a graph, meant for loading large graphs fast.
+//!
+//! All numbers are little-endian. The snapshot consists of:
+//!
+//! 1. header: magic `GRSB`, format version (u16), flags (u16, bit 0 is set
+//!    for directed graphs), number of vertices n (u64) and number of
+//!    adjacency entries m (u64);
+//! 2. vertex table: key and value of every vertex, written by BinaryCodec;
+//! 3. edges in compressed sparse row form: n + 1 offsets (u64) into the
+//!    following m targets (u64 indices into the vertex table) and m weights (i64);
+//! 4. CRC-32 checksum (u32) of everything above.
+//!
+//! Undirected edges are stored in the adjacency lists of both of their ends,
+//! the same way the graph keeps them in memory.
+
+use std::{collections::HashMap, fs, hash::Hash, path::Path};
+
+use crate::{edge::Edge, graph::Graph, marker::GraphType, vertex::Vertex};
+
+const MAGIC: &[u8; 4] = b"GRSB";
+
+/// Version of the snapshot format written by this build.
+pub const VERSION: u16 = 1;
+
+const DIRECTED_FLAG: u16 = 1;
+
+const HEADER_LEN: usize = 4 + 2 + 2 + 8 + 8;
+
+const CHECKSUM_LEN: usize = 4;
+
+/// BinaryCodec encodes keys and values of vertices in binary snapshots.
+/// Implement it for your own types to store them in snapshots.
+pub trait BinaryCodec: Sized {
+    /// Appends the encoded value to the buffer.
+    fn encode(&self, buffer: &mut Vec<u8>);
+
+    /// Decodes the value from the beginning of the input and advances the input past it.
+    fn decode(input: &mut &[u8]) -> Result<Self, String>;
+}
+
+/// Takes the next len bytes of the input.
+pub fn take<'a>(input: &mut &'a [u8], len: usize) -> Result<&'a [u8], String> {
+    if input.len() < len {
+        return Err(String::from("unexpected end of data"));
+    }
+    let (head, tail) = input.split_at(len);
+    *input = tail;
+    Ok(head)
+}
+
+macro_rules! impl_binary_codec_for_numbers {
+    ($($type:ty),*) => {
+        $(
+            impl BinaryCodec for $type {
+                fn encode(&self, buffer: &mut Vec<u8>) {
+                    buffer.extend_from_slice(&self.to_le_bytes());
+                }
+
+                fn decode(input: &mut &[u8]) -> Result<Self, String> {
+                    let bytes = take(input, std::mem::size_of::<$type>())?;
+                    Ok(<$type>::from_le_bytes(bytes.try_into().expect("length is checked")))
+                }
+            }
+        )*
+    };
+}
+
+impl_binary_codec_for_numbers!(u8, u16, u32, u64, i8, i16, i32, i64, f32, f64);
+
+impl BinaryCodec for usize {
+    fn encode(&self, buffer: &mut Vec<u8>) {
+        (*self as u64).encode(buffer);
+    }
+
+    fn decode(input: &mut &[u8]) -> Result<Self, String> {
+        usize::try_from(u64::decode(input)?).map_err(|_| String::from("number is too large"))
+    }
+}
+
+impl BinaryCodec for isize {
+    fn encode(&self, buffer: &mut Vec<u8>) {
+        (*self as i64).encode(buffer);
+    }
+
+    fn decode(input: &mut &[u8]) -> Result<Self, String> {
+        isize::try_from(i64::decode(input)?).map_err(|_| String::from("number is too large"))
+    }
+}
+
+impl BinaryCodec for () {
+    fn encode(&self, _: &mut Vec<u8>) {}
+
+    fn decode(_: &mut &[u8]) -> Result<Self, String> {
+        Ok(())
+    }
+}
+
+impl BinaryCodec for bool {
+    fn encode(&self, buffer: &mut Vec<u8>) {
+        buffer.push(*self as u8);
+    }
+
+    fn decode(input: &mut &[u8]) -> Result<Self, String> {
+        match u8::decode(input)? {
+            0 => Ok(false),
+            1 => Ok(true),
+            other => Err(format!("invalid bool {}", other)),
+        }
+    }
+}
+
+impl BinaryCodec for char {
+    fn encode(&self, buffer: &mut Vec<u8>) {
+        (*self as u32).encode(buffer);
+    }
+
+    fn decode(input: &mut &[u8]) -> Result<Self, String> {
+        let code = u32::decode(input)?;
+        char::from_u32(code).ok_or_else(|| format!("invalid char {}", code))
+    }
+}
+
+/// Strings are written as their length in bytes (u64) followed by UTF-8 bytes.
+impl BinaryCodec for String {
+    fn encode(&self, buffer: &mut Vec<u8>) {
+        self.len().encode(buffer);
+        buffer.extend_from_slice(self.as_bytes());
+    }
+
+    fn decode(input: &mut &[u8]) -> Result<Self, String> {
+        let len = usize::decode(input)?;
+        let bytes = take(input, len)?;
+        String::from_utf8(bytes.to_vec()).map_err(|_| String::from("string is not valid UTF-8"))
+    }
+}
+
+/// Table of the CRC-32 (IEEE 802.3) polynomial.
+const CRC_TABLE: [u32; 256] = {
+    let mut table = [0; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xEDB88320
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+fn crc32(data: &[u8]) -> u32 {
+    !data.iter().fold(!0, |crc, byte| {
+        CRC_TABLE[((crc ^ *byte as u32) & 0xff) as usize] ^ (crc >> 8)
+    })
+}
+
+impl<Key, Value, Type> Graph<Key, Value, Type>
+where
+    Key: Hash + Eq + Clone + BinaryCodec,
+    Value: BinaryCodec,
+    Type: GraphType,
+{
+    /// Serializes the graph into a binary snapshot.
+    pub fn to_binary(&self) -> Vec<u8> {
+        let indices: HashMap<&Key, u64> = self
+            .vertecies()
+            .enumerate()
+            .map(|(i, v)| (v.key(), i as u64))
+            .collect();
+        let entries: usize = self.vertecies().map(|v| v.adjancency_list().len()).sum();
+
+        let mut buffer = Vec::with_capacity(HEADER_LEN + (self.len() + 1) * 8 + entries * 16);
+        buffer.extend_from_slice(MAGIC);
+        VERSION.encode(&mut buffer);
+        let flags = if Type::is_directed() {
+            DIRECTED_FLAG
+        } else {
+            0
+        };
+        flags.encode(&mut buffer);
+        self.len().encode(&mut buffer);
+        entries.encode(&mut buffer);
+
+        for vertex in self.vertecies() {
+            vertex.key().encode(&mut buffer);
+            vertex.value.encode(&mut buffer);
+        }
+
+        let mut offset = 0u64;
+        offset.encode(&mut buffer);
+        for vertex in self.vertecies() {
+            offset += vertex.adjancency_list().len() as u64;
+            offset.encode(&mut buffer);
+        }
+        for vertex in self.vertecies() {
+            for edge in vertex.adjancency_list() {
+                indices[edge.to()].encode(&mut buffer);
+            }
+        }
+        for vertex in self.vertecies() {
+            for edge in vertex.adjancency_list() {
+                edge.weight.encode(&mut buffer);
+            }
+        }
+
+        let checksum = crc32(&buffer);
+        checksum.encode(&mut buffer);
+
+        buffer
+    }
+
+    /// Deserializes the graph from a binary snapshot.
+    /// Returns Err if the snapshot has another version or type of the graph,
+    /// its checksum doesn't match or its content is malformed.
+    pub fn from_binary(bytes: &[u8]) -> Result<Self, String> {
+        if bytes.len() < HEADER_LEN + CHECKSUM_LEN || &bytes[..4] != MAGIC {
+            return Err(String::from("data is not a graph-rs binary snapshot"));
+        }
+
+        let mut input = &bytes[4..];
+        let version = u16::decode(&mut input)?;
+        if version != VERSION {
+            return Err(format!(
+                "binary snapshot has format version {}, but only version {} is supported",
+                version, VERSION
+            ));
+        }
+
+        let (content, mut checksum) = bytes.split_at(bytes.len() - CHECKSUM_LEN);
+        if crc32(content) != u32::decode(&mut checksum)? {
+            return Err(String::from(
+                "checksum mismatch, binary snapshot is corrupted",
+            ));
+        }
+        let mut input = &content[6..];
+
+        let directed = u16::decode(&mut input)? & DIRECTED_FLAG != 0;
+        if directed != Type::is_directed() {
+            return Err(format!(
+                "graph is declared as {}, but {} graph was requested",
+                if directed { "directed" } else { "undirected" },
+                Type::type_name()
+            ));
+        }
+        let n = usize::decode(&mut input)?;
+        let m = usize::decode(&mut input)?;
+
+        let mut keys = Vec::with_capacity(n.min(input.len()));
+        let mut values = Vec::with_capacity(n.min(input.len()));
+        for _ in 0..n {
+            keys.push(Key::decode(&mut input)?);
+            values.push(Value::decode(&mut input)?);
+        }
+
+        let too_large = || String::from("number of edges is too large");
+        let offsets_len = n
+            .checked_add(1)
+            .and_then(|count| count.checked_mul(8))
+            .ok_or_else(too_large)?;
+        let targets_len = m.checked_mul(8).ok_or_else(too_large)?;
+        let expected = targets_len
+            .checked_mul(2)
+            .and_then(|len| len.checked_add(offsets_len))
+            .ok_or_else(too_large)?;
+        if input.len() != expected {
+            return Err(String::from("edge arrays have unexpected length"));
+        }
+        let (mut offsets, rest) = input.split_at(offsets_len);
+        let (mut targets, mut weights) = rest.split_at(targets_len);
+
+        let mut graph: Self = Graph::with_capacity(n);
+        let mut start = usize::decode(&mut offsets)?;
+        let mut seen = vec![usize::MAX; n];
+
+        for (i, (key, value)) in keys.iter().zip(values).enumerate() {
+            let end = usize::decode(&mut offsets)?;
+            if start > end || end > m {
+                return Err(String::from("edge offsets are not ascending"));
+            }
+
+            let mut adj = Vec::with_capacity(end - start);
+            for _ in start..end {
+                let target = usize::decode(&mut targets)?;
+                let weight = i64::decode(&mut weights)?;
+                if target >= n {
+                    return Err(format!("edge leads to unknown vertex {}", target));
+                }
+                if seen[target] == i {
+                    return Err(String::from("vertex contains duplicated edges"));
+                }
+                seen[target] = i;
+                adj.push(Edge::new(key.clone(), keys[target].clone(), weight));
+            }
+            start = end;
+
+            if graph
+                .insert_vertex(Vertex::from_parts(key.clone(), value, adj))
+                .is_some()
+            {
+                return Err(String::from("vertex is declared twice"));
+            }
+        }
+
+        if !Type::is_directed() {
+            for edge in graph.edges() {
+                let reverse = graph
+                    .get_vertex(edge.to())
+                    .and_then(|destination| destination.get_edge(edge.from()));
+                if reverse.is_none_or(|reverse| reverse.weight != edge.weight) {
+                    return Err(String::from(
+                        "undirected graph must contain edges in both directions",
+                    ));
+                }
+            }
+        }
+
+        Ok(graph)
+    }
+
+    /// Saves the binary snapshot of the graph into the file.
+    pub fn save_binary(&self, path: impl AsRef<Path>) -> Result<(), String> {
+        let path = path.as_ref();
+        fs::write(path, self.to_binary())
+            .map_err(|e| format!("couldn't write '{}': {}", path.display(), e))
+    }
+
+    /// Loads the graph from the binary snapshot in the file.
+    pub fn load_binary(path: impl AsRef<Path>) -> Result<Self, String> {
+        let path = path.as_ref();
+        let bytes =
+            fs::read(path).map_err(|e| format!("couldn't read '{}': {}", path.display(), e))?;
+        Graph::from_binary(&bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::marker::{Directed, Undirected};
+    use pretty_assertions::assert_eq;
+
+    fn sample() -> Graph<String, i32, Undirected> {
+        let mut graph: Graph<String, i32, Undirected> = Graph::new();
+        graph.insert(String::from("a"), 1);
+        graph.insert(String::from("b"), -2);
+        graph.insert(String::from("c"), 3);
+        graph
+            .insert_edge(String::from("a"), String::from("b"), 5)
+            .unwrap();
+        graph
+            .insert_edge_unweighted(String::from("b"), String::from("c"))
+            .unwrap();
+        graph
+    }
+
+    #[test]
+    fn crc32_test() {
+        assert_eq!(crc32(b"123456789"), 0xCBF43926);
+    }
+
+    #[test]
+    fn round_trip_test() {
+        let graph = sample();
+        let restored: Graph<String, i32, Undirected> =
+            Graph::from_binary(&graph.to_binary()).expect("snapshot is valid");
+
+        let (a, b, c) = (String::from("a"), String::from("b"), String::from("c"));
+        assert_eq!(restored.len(), 3);
+        assert_eq!(restored.edges_count(), 2);
+        assert_eq!(restored.get(&b), Some(&-2));
+        assert_eq!(
+            restored
+                .get_vertex(&b)
+                .unwrap()
+                .get_edge(&a)
+                .unwrap()
+                .weight,
+            5
+        );
+        assert!(restored.get_vertex(&c).unwrap().get_edge(&b).is_some());
+
+        let mut directed: Graph<char, ()> = Graph::new();
+        directed.insert('x', ());
+        directed.insert_edge('x', 'x', 7).unwrap();
+        let restored: Graph<char, ()> =
+            Graph::from_binary(&directed.to_binary()).expect("snapshot is valid");
+        assert_eq!(
+            restored
+                .get_vertex(&'x')
+                .unwrap()
+                .get_edge(&'x')
+                .unwrap()
+                .weight,
+            7
+        );
+    }
+
+    #[test]
+    fn save_load_test() {
+        let path = std::env::temp_dir().join(format!("graph-rs-{}.bin", std::process::id()));
+        sample().save_binary(&path).expect("file is writable");
+        let restored = Graph::<String, i32, Undirected>::load_binary(&path);
+        fs::remove_file(&path).unwrap();
+        assert_eq!(restored.expect("snapshot is valid").len(), 3);
+    }
+
+    #[test]
+    fn errors_test() {
+        let bytes = sample().to_binary();
+
+        assert!(Graph::<String, i32, Undirected>::from_binary(b"not a snapshot").is_err());
+
+        let mut newer = bytes.clone();
+        newer[4] = 2;
+        let err = Graph::<String, i32, Undirected>::from_binary(&newer).err();
+        assert_eq!(
+            err,
+            Some(String::from(
+                "binary snapshot has format version 2, but only version 1 is supported"
+            ))
+        );
+
+        let mut corrupted = bytes.clone();
+        corrupted[HEADER_LEN + 2] ^= 0xff;
+        let err = Graph::<String, i32, Undirected>::from_binary(&corrupted).err();
+        assert_eq!(
+            err,
+            Some(String::from(
+                "checksum mismatch, binary snapshot is corrupted"
+            ))
+        );
+
+        assert!(Graph::<String, i32, Directed>::from_binary(&bytes).is_err());
+        assert!(Graph::<String, i32, Undirected>::from_binary(&bytes[..bytes.len() - 1]).is_err());
+    }
+
+    /// Seals the snapshot content with its checksum.
+    fn seal(mut content: Vec<u8>) -> Vec<u8> {
+        let checksum = crc32(&content);
+        checksum.encode(&mut content);
+        content
+    }
+
+    #[test]
+    fn malformed_test() {
+        // Header of an empty graph that declares 2^62 edges
+        let mut content = MAGIC.to_vec();
+        VERSION.encode(&mut content);
+        DIRECTED_FLAG.encode(&mut content);
+        0usize.encode(&mut content);
+        (1usize << 62).encode(&mut content);
+        let err = Graph::<String, i32, Directed>::from_binary(&seal(content)).err();
+        assert_eq!(err, Some(String::from("number of edges is too large")));
+
+        // Directed edge a -> b relabeled as undirected
+        let mut directed: Graph<String, i32, Directed> = Graph::new();
+        directed.insert(String::from("a"), 1);
+        directed.insert(String::from("b"), 2);
+        directed
+            .insert_edge(String::from("a"), String::from("b"), 5)
+            .unwrap();
+        let mut content = directed.to_binary();
+        content.truncate(content.len() - CHECKSUM_LEN);
+        content[6..8].copy_from_slice(&0u16.to_le_bytes());
+        let err = Graph::<String, i32, Undirected>::from_binary(&seal(content)).err();
+        assert_eq!(
+            err,
+            Some(String::from(
+                "undirected graph must contain edges in both directions"
+            ))
+        );
+    }
+}
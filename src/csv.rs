@@ -0,0 +1,532 @@
+//! Readers and writers for graphs stored as CSV edge lists
+//! (`source,target,weight` per line) and as square adjacency matrices.
+
+use std::{collections::HashMap, fmt::Display, hash::Hash, str::FromStr};
+
+use crate::{edge::parse_weight, graph::Graph, marker::GraphType};
+
+/// CsvColumn identifies a column either by its zero-based index
+/// or by its name in the header row.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CsvColumn {
+    Index(usize),
+    Name(String),
+}
+
+impl CsvColumn {
+    /// Returns the index of the column in a record.
+    fn resolve(&self, header: Option<&Vec<String>>) -> Result<usize, String> {
+        match self {
+            CsvColumn::Index(i) => Ok(*i),
+            CsvColumn::Name(name) => header
+                .ok_or_else(|| {
+                    format!(
+                        "column '{}' is referenced by name, but there is no header",
+                        name
+                    )
+                })?
+                .iter()
+                .position(|column| column == name)
+                .ok_or_else(|| format!("header does not contain column '{}'", name)),
+        }
+    }
+}
+
+/// EdgeListOptions describes the layout of an edge list.
+#[derive(Debug, Clone)]
+pub struct EdgeListOptions {
+    /// Separates fields of a record.
+    pub delimiter: char,
+
+    /// Whether the first record is a header that contains column names.
+    pub has_header: bool,
+
+    /// Column with the keys of source vertices.
+    pub source: CsvColumn,
+
+    /// Column with the keys of destination vertices.
+    pub target: CsvColumn,
+
+    /// Column with the weights of edges. Edges are unweighted if it is None
+    /// or the cell is empty.
+    pub weight: Option<CsvColumn>,
+}
+
+impl Default for EdgeListOptions {
+    /// Comma separated `source,target,weight` records with a header.
+    fn default() -> Self {
+        Self {
+            delimiter: ',',
+            has_header: true,
+            source: CsvColumn::Index(0),
+            target: CsvColumn::Index(1),
+            weight: Some(CsvColumn::Index(2)),
+        }
+    }
+}
+
+/// MatrixOptions describes the layout of an adjacency matrix.
+#[derive(Debug, Clone)]
+pub struct MatrixOptions {
+    /// Separates fields of a record.
+    pub delimiter: char,
+
+    /// Whether the first row and the first column contain vertex keys.
+    /// Without a header vertices are keyed by their zero-based index.
+    pub has_header: bool,
+
+    /// Whether cells contain edge weights. In a weighted matrix only empty cells
+    /// mean there is no edge. In an unweighted one zero cells mean it too and
+    /// any other number is an unweighted edge.
+    pub weighted: bool,
+}
+
+impl Default for MatrixOptions {
+    /// Comma separated unweighted matrix of zeros and ones with a header.
+    fn default() -> Self {
+        Self {
+            delimiter: ',',
+            has_header: true,
+            weighted: false,
+        }
+    }
+}
+
+/// Splits the record into fields. Fields may be quoted with '"',
+/// a quote inside of a quoted field is escaped by doubling it.
+fn split_record(line: &str, delimiter: char) -> Result<Vec<String>, String> {
+    let mut fields = Vec::new();
+    let mut chars = line.chars().peekable();
+
+    loop {
+        while chars
+            .next_if(|c| *c != delimiter && c.is_whitespace())
+            .is_some()
+        {}
+
+        let mut field = String::new();
+        if chars.next_if_eq(&'"').is_some() {
+            loop {
+                match chars.next() {
+                    Some('"') if chars.next_if_eq(&'"').is_some() => field.push('"'),
+                    Some('"') => break,
+                    Some(c) => field.push(c),
+                    None => return Err(format!("unterminated quoted field in '{}'", line)),
+                }
+            }
+            while chars
+                .next_if(|c| *c != delimiter && c.is_whitespace())
+                .is_some()
+            {}
+            if chars.peek().is_some_and(|c| *c != delimiter) {
+                return Err(format!(
+                    "unexpected character after quoted field in '{}'",
+                    line
+                ));
+            }
+        } else {
+            while let Some(c) = chars.next_if(|c| *c != delimiter) {
+                field.push(c);
+            }
+            field = String::from(field.trim_end());
+        }
+        fields.push(field);
+
+        if chars.next().is_none() {
+            return Ok(fields);
+        }
+    }
+}
+
+/// Quotes the field if it contains the delimiter, quotes or line breaks.
+fn quote_field(field: &str, delimiter: char) -> String {
+    if field.contains([delimiter, '"', '\n', '\r']) || field.trim() != field {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        String::from(field)
+    }
+}
+
+fn join_record(fields: &[String], delimiter: char) -> String {
+    let fields: Vec<String> = fields.iter().map(|f| quote_field(f, delimiter)).collect();
+    fields.join(&delimiter.to_string()) + "\n"
+}
+
+/// Returns non-empty records of the input.
+fn records(input: &str, delimiter: char) -> impl Iterator<Item = Result<Vec<String>, String>> + '_ {
+    input
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(move |line| split_record(line, delimiter))
+}
+
+fn parse_key<Key: FromStr>(raw: &str) -> Result<Key, String> {
+    raw.parse()
+        .map_err(|_| format!("couldn't parse vertex '{}'", raw))
+}
+
+/// Reads the graph from the edge list. Vertices are created for every key
+/// mentioned in the list and get the default value.
+pub fn read_edge_list<Key, Value, Type>(
+    input: &str,
+    options: &EdgeListOptions,
+) -> Result<Graph<Key, Value, Type>, String>
+where
+    Key: Hash + Eq + Clone + FromStr,
+    Value: Default,
+    Type: GraphType,
+{
+    let mut records = records(input, options.delimiter);
+
+    let header = if options.has_header {
+        records.next().transpose()?
+    } else {
+        None
+    };
+
+    let source = options.source.resolve(header.as_ref())?;
+    let target = options.target.resolve(header.as_ref())?;
+    let weight = match &options.weight {
+        Some(column) => Some(column.resolve(header.as_ref())?),
+        None => None,
+    };
+
+    let mut graph = Graph::new();
+
+    for (i, record) in records.enumerate() {
+        let record = record?;
+        let cell = |column: usize| {
+            record
+                .get(column)
+                .ok_or_else(|| format!("record {} does not have column {}", i + 1, column))
+        };
+
+        let from: Key = parse_key(cell(source)?)?;
+        let to: Key = parse_key(cell(target)?)?;
+        let weight = match weight.and_then(|column| record.get(column)) {
+            Some(raw) if !raw.is_empty() => parse_weight(raw)?,
+            _ => 0,
+        };
+
+        for key in [&from, &to] {
+            if !graph.contains(key) {
+                graph.insert(key.clone(), Value::default());
+            }
+        }
+        graph
+            .link(from, to, weight)
+            .expect("graph must contain src and dst vertecies");
+    }
+
+    Ok(graph)
+}
+
+/// Writes the edge list of the graph as `source,target,weight` records.
+/// Column mapping of the options is not used, only the delimiter and the header flag.
+/// Isolated vertices are not written, because edge list can't represent them.
+/// Edges are sorted by keys, so the output is stable.
+pub fn write_edge_list<Key, Value, Type>(
+    graph: &Graph<Key, Value, Type>,
+    options: &EdgeListOptions,
+) -> String
+where
+    Key: Hash + Eq + Clone + Display + Ord,
+    Type: GraphType,
+{
+    let mut buffer = String::new();
+
+    if options.has_header {
+        let header = ["source", "target", "weight"].map(String::from);
+        buffer += &join_record(&header, options.delimiter);
+    }
+
+    for edge in graph.sorted_edges() {
+        buffer += &join_record(
+            &[
+                edge.from().to_string(),
+                edge.to().to_string(),
+                edge.weight.to_string(),
+            ],
+            options.delimiter,
+        );
+    }
+
+    buffer
+}
+
+/// Reads the graph from the square adjacency matrix where the cell in row `i`
+/// and column `j` describes the edge from vertex `i` to vertex `j`.
+/// The matrix of an undirected graph must be symmetric.
+pub fn read_adjacency_matrix<Key, Value, Type>(
+    input: &str,
+    options: &MatrixOptions,
+) -> Result<Graph<Key, Value, Type>, String>
+where
+    Key: Hash + Eq + Clone + FromStr,
+    Value: Default,
+    Type: GraphType,
+{
+    let mut rows: Vec<Vec<String>> = records(input, options.delimiter).collect::<Result<_, _>>()?;
+
+    let keys: Vec<Key> = if options.has_header {
+        if rows.is_empty() {
+            return Ok(Graph::new());
+        }
+        let header = rows.remove(0);
+        let keys: Vec<Key> = header
+            .iter()
+            .skip(1)
+            .map(|raw| parse_key(raw))
+            .collect::<Result<_, _>>()?;
+
+        for (row, key) in rows.iter_mut().zip(keys.iter()) {
+            let label: Key = parse_key(&row.remove(0))?;
+            if label != *key {
+                return Err(String::from("row labels must match the header"));
+            }
+        }
+        keys
+    } else {
+        (0..rows.len())
+            .map(|i| parse_key(&i.to_string()))
+            .collect::<Result<_, _>>()?
+    };
+
+    if rows.len() != keys.len() || rows.iter().any(|row| row.len() != keys.len()) {
+        return Err(format!(
+            "adjacency matrix must be square, expected {} rows and columns",
+            keys.len()
+        ));
+    }
+
+    let mut cells: Vec<Vec<Option<i64>>> = Vec::with_capacity(rows.len());
+    for row in &rows {
+        let mut parsed = Vec::with_capacity(row.len());
+        for raw in row {
+            let cell = if options.weighted {
+                match raw.as_str() {
+                    "" => None,
+                    raw => Some(parse_weight(raw)?),
+                }
+            } else {
+                match raw.as_str() {
+                    "" => None,
+                    raw => (parse_weight(raw)? != 0).then_some(0),
+                }
+            };
+            parsed.push(cell);
+        }
+        cells.push(parsed);
+    }
+
+    let mut graph = Graph::with_capacity(keys.len());
+    for key in &keys {
+        if graph.insert(key.clone(), Value::default()).is_some() {
+            return Err(String::from("adjacency matrix contains duplicated keys"));
+        }
+    }
+
+    for (i, row) in cells.iter().enumerate() {
+        for (j, cell) in row.iter().enumerate() {
+            if !Type::is_directed() && *cell != cells[j][i] {
+                return Err(String::from(
+                    "adjacency matrix of undirected graph must be symmetric",
+                ));
+            }
+            if let Some(weight) = cell {
+                graph
+                    .link(keys[i].clone(), keys[j].clone(), *weight)
+                    .expect("graph must contain src and dst vertecies");
+            }
+        }
+    }
+
+    Ok(graph)
+}
+
+/// Writes the adjacency matrix of the graph.
+/// Rows and columns are sorted by keys, so the output is stable.
+pub fn write_adjacency_matrix<Key, Value, Type>(
+    graph: &Graph<Key, Value, Type>,
+    options: &MatrixOptions,
+) -> String
+where
+    Key: Hash + Eq + Clone + Display + Ord,
+    Type: GraphType,
+{
+    let keys: Vec<&Key> = graph.sorted_vertices().iter().map(|v| v.key()).collect();
+    let index: HashMap<&Key, usize> = keys.iter().enumerate().map(|(i, k)| (*k, i)).collect();
+
+    let mut buffer = String::new();
+
+    if options.has_header {
+        let header: Vec<String> = std::iter::once(String::new())
+            .chain(keys.iter().map(|k| k.to_string()))
+            .collect();
+        buffer += &join_record(&header, options.delimiter);
+    }
+
+    for key in &keys {
+        let mut row = vec![
+            if options.weighted {
+                String::new()
+            } else {
+                String::from("0")
+            };
+            keys.len()
+        ];
+        for edge in graph
+            .get_vertex(key)
+            .expect("key must exist")
+            .adjancency_list()
+        {
+            row[index[edge.to()]] = if options.weighted {
+                edge.weight.to_string()
+            } else {
+                String::from("1")
+            };
+        }
+        if options.has_header {
+            row.insert(0, key.to_string());
+        }
+        buffer += &join_record(&row, options.delimiter);
+    }
+
+    buffer
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::marker::{Directed, Undirected};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn split_record_test() {
+        assert_eq!(
+            split_record(r#"a, "b,c" ,"say ""hi""",,d "#, ','),
+            Ok(vec!["a", "b,c", "say \"hi\"", "", "d"]
+                .into_iter()
+                .map(String::from)
+                .collect())
+        );
+        assert_eq!(
+            split_record("1\t2", '\t'),
+            Ok(vec![String::from("1"), String::from("2")])
+        );
+        assert!(split_record("\"a", ',').is_err());
+        assert!(split_record("\"a\"b", ',').is_err());
+    }
+
+    #[test]
+    fn edge_list_test() {
+        let input = "weight;from;to\n5;a;b\n;b;c\n\n-1;c;a\n";
+        let options = EdgeListOptions {
+            delimiter: ';',
+            has_header: true,
+            source: CsvColumn::Name(String::from("from")),
+            target: CsvColumn::Index(2),
+            weight: Some(CsvColumn::Name(String::from("weight"))),
+        };
+
+        let graph: Graph<String, i32, Directed> =
+            read_edge_list(input, &options).expect("csv is not valid");
+
+        let (a, b, c) = (String::from("a"), String::from("b"), String::from("c"));
+        assert_eq!(graph.len(), 3);
+        assert_eq!(graph.get(&a), Some(&0));
+        assert_eq!(
+            graph.get_vertex(&a).unwrap().get_edge(&b).unwrap().weight,
+            5
+        );
+        assert_eq!(
+            graph.get_vertex(&b).unwrap().get_edge(&c).unwrap().weight,
+            0
+        );
+        assert_eq!(
+            graph.get_vertex(&c).unwrap().get_edge(&a).unwrap().weight,
+            -1
+        );
+
+        let output = write_edge_list(&graph, &EdgeListOptions::default());
+        assert_eq!(output, "source,target,weight\na,b,5\nb,c,0\nc,a,-1\n");
+        let restored: Graph<String, i32, Directed> =
+            read_edge_list(&output, &EdgeListOptions::default()).expect("csv is not valid");
+        assert_eq!(restored.edges_count(), 3);
+        assert_eq!(
+            restored
+                .get_vertex(&c)
+                .unwrap()
+                .get_edge(&a)
+                .unwrap()
+                .weight,
+            -1
+        );
+    }
+
+    #[test]
+    fn edge_list_errors_test() {
+        let options = EdgeListOptions::default();
+        assert!(read_edge_list::<i32, (), Directed>("s,t\nx,1", &options).is_err());
+        assert!(read_edge_list::<i32, (), Directed>("s,t\n1", &options).is_err());
+        assert!(read_edge_list::<i32, (), Directed>("s,t\n1,2,w", &options).is_err());
+        assert!(read_edge_list::<i32, (), Directed>("s,t\n1,2,1e30", &options).is_err());
+
+        // Weights are parsed like in the other formats
+        let graph: Graph<i32, (), Directed> =
+            read_edge_list("s,t\n1,2,2.0", &options).expect("csv is valid");
+        assert_eq!(
+            graph.get_vertex(&1).unwrap().get_edge(&2).unwrap().weight,
+            2
+        );
+
+        let by_name = EdgeListOptions {
+            has_header: false,
+            source: CsvColumn::Name(String::from("s")),
+            ..EdgeListOptions::default()
+        };
+        assert!(read_edge_list::<i32, (), Directed>("1,2", &by_name).is_err());
+    }
+
+    #[test]
+    fn adjacency_matrix_test() {
+        let input = "0,1,0\n1,0,1\n0,1,0\n";
+        let options = MatrixOptions {
+            has_header: false,
+            ..MatrixOptions::default()
+        };
+
+        let graph: Graph<usize, (), Undirected> =
+            read_adjacency_matrix(input, &options).expect("matrix is not valid");
+        assert_eq!(graph.len(), 3);
+        assert_eq!(graph.edges_count(), 2);
+        assert!(graph.get_vertex(&2).unwrap().get_edge(&1).is_some());
+
+        let weighted = MatrixOptions {
+            weighted: true,
+            ..MatrixOptions::default()
+        };
+        let output = write_adjacency_matrix(&graph, &weighted);
+        assert_eq!(output, ",0,1,2\n0,,0,\n1,0,,0\n2,,0,\n");
+        let restored: Graph<usize, (), Undirected> =
+            read_adjacency_matrix(&output, &weighted).expect("matrix is not valid");
+        assert_eq!(restored.edges_count(), 2);
+        assert!(restored.get_vertex(&0).unwrap().get_edge(&1).is_some());
+    }
+
+    #[test]
+    fn adjacency_matrix_errors_test() {
+        let options = MatrixOptions::default();
+        assert!(read_adjacency_matrix::<i32, (), Directed>(",1,2\n1,0,1\n", &options).is_err());
+        assert!(
+            read_adjacency_matrix::<i32, (), Directed>(",1,2\n1,0,1\n3,0,0\n", &options).is_err()
+        );
+        assert!(
+            read_adjacency_matrix::<i32, (), Undirected>(",1,2\n1,0,1\n2,0,0\n", &options).is_err()
+        );
+        assert!(
+            read_adjacency_matrix::<i32, (), Directed>(",1,2\n1,0,1\n2,0,0\n", &options).is_ok()
+        );
+    }
+}
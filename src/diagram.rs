@@ -0,0 +1,340 @@
+//! Export of graphs as Mermaid flowcharts and PlantUML diagrams,
+//! e.g. for embedding dependency graphs in Markdown documents.
+//!
+//! Vertices are labeled with their values and edges with their weights.
+//! Vertices are sorted by key, so the same graph always produces the same diagram.
+
+use std::{collections::HashMap, fmt::Display, hash::Hash};
+
+use crate::{graph::Graph, marker::GraphType};
+
+/// Direction in which the diagram is laid out.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Direction {
+    /// From top to bottom.
+    #[default]
+    TopDown,
+    /// From left to right.
+    LeftRight,
+}
+
+/// DiagramOptions configures the layout direction and highlighting of a diagram.
+pub struct DiagramOptions<Key> {
+    pub direction: Direction,
+
+    /// Vertices to highlight. Keys that are not in the graph are ignored.
+    pub highlighted: Vec<Key>,
+
+    /// Path to highlight, given as a sequence of vertices.
+    /// Both the vertices and the edges between consecutive vertices are highlighted.
+    pub path: Vec<Key>,
+}
+
+impl<Key> Default for DiagramOptions<Key> {
+    fn default() -> Self {
+        DiagramOptions {
+            direction: Direction::default(),
+            highlighted: Vec::new(),
+            path: Vec::new(),
+        }
+    }
+}
+
+/// Vertices and edges of the graph in the order they are drawn.
+struct Layout<'a, Value> {
+    /// Labels of vertices and whether they are highlighted.
+    vertices: Vec<(&'a Value, bool)>,
+
+    /// Edges between vertex indices, their weights and whether they are highlighted.
+    edges: Vec<(usize, usize, i64, bool)>,
+}
+
+fn layout<'a, Key, Value, Type>(
+    graph: &'a Graph<Key, Value, Type>,
+    options: &DiagramOptions<Key>,
+) -> Layout<'a, Value>
+where
+    Key: Hash + Eq + Clone + Display + Ord,
+    Type: GraphType,
+{
+    let vertices = graph.sorted_vertices();
+    let indices: HashMap<&Key, usize> = vertices
+        .iter()
+        .enumerate()
+        .map(|(i, v)| (v.key(), i))
+        .collect();
+
+    let normalize = |from: usize, to: usize| {
+        if Type::is_directed() {
+            (from, to)
+        } else {
+            (from.min(to), from.max(to))
+        }
+    };
+
+    let path_edges: Vec<(usize, usize)> = options
+        .path
+        .windows(2)
+        .filter_map(|pair| Some(normalize(*indices.get(&pair[0])?, *indices.get(&pair[1])?)))
+        .collect();
+
+    let edges: Vec<(usize, usize, i64, bool)> = graph
+        .sorted_edges()
+        .into_iter()
+        .map(|e| {
+            let (from, to) = (indices[e.from()], indices[e.to()]);
+            (from, to, e.weight, path_edges.contains(&(from, to)))
+        })
+        .collect();
+
+    Layout {
+        vertices: vertices
+            .iter()
+            .map(|v| {
+                let highlighted =
+                    options.highlighted.contains(v.key()) || options.path.contains(v.key());
+                (&v.value, highlighted)
+            })
+            .collect(),
+        edges,
+    }
+}
+
+/// Escapes quotes and line breaks, which can't appear in quoted Mermaid labels.
+fn mermaid_label(value: &impl Display) -> String {
+    value
+        .to_string()
+        .replace('"', "#quot;")
+        .replace("\r\n", "<br>")
+        .replace(['\n', '\r'], "<br>")
+}
+
+/// Escapes quotes and line breaks, which can't appear in quoted PlantUML labels.
+/// PlantUML breaks lines at `\n` and reads `<U+0022>` as a quote.
+fn plantuml_label(value: &impl Display) -> String {
+    value
+        .to_string()
+        .replace('"', "<U+0022>")
+        .replace("\r\n", "\\n")
+        .replace(['\n', '\r'], "\\n")
+}
+
+impl<Key, Value, Type> Graph<Key, Value, Type>
+where
+    Key: Hash + Eq + Clone + Display + Ord,
+    Value: Display,
+    Type: GraphType,
+{
+    /// Exports the graph as a top-down Mermaid flowchart.
+    pub fn to_mermaid(&self) -> String {
+        self.to_mermaid_with(&DiagramOptions::default())
+    }
+
+    /// Exports the graph as a Mermaid flowchart.
+    /// Directed edges are drawn as `-->`, undirected as `---`.
+    pub fn to_mermaid_with(&self, options: &DiagramOptions<Key>) -> String {
+        let layout = layout(self, options);
+
+        let mut buffer = format!(
+            "flowchart {}\n",
+            match options.direction {
+                Direction::TopDown => "TD",
+                Direction::LeftRight => "LR",
+            }
+        );
+
+        for (i, (value, _)) in layout.vertices.iter().enumerate() {
+            buffer += &format!("    v{}[\"{}\"]\n", i, mermaid_label(value));
+        }
+
+        let arrow = if Type::is_directed() { "-->" } else { "---" };
+        for (from, to, weight, _) in &layout.edges {
+            if *weight != 0 {
+                buffer += &format!("    v{} {}|{}| v{}\n", from, arrow, weight, to);
+            } else {
+                buffer += &format!("    v{} {} v{}\n", from, arrow, to);
+            }
+        }
+
+        let highlighted: Vec<String> = layout
+            .vertices
+            .iter()
+            .enumerate()
+            .filter(|(_, (_, highlighted))| *highlighted)
+            .map(|(i, _)| format!("v{}", i))
+            .collect();
+        if !highlighted.is_empty() {
+            buffer += "    classDef highlighted fill:#f96,stroke:#333,stroke-width:2px\n";
+            buffer += &format!("    class {} highlighted\n", highlighted.join(","));
+        }
+
+        let path: Vec<String> = layout
+            .edges
+            .iter()
+            .enumerate()
+            .filter(|(_, edge)| edge.3)
+            .map(|(i, _)| i.to_string())
+            .collect();
+        if !path.is_empty() {
+            buffer += &format!(
+                "    linkStyle {} stroke:#f00,stroke-width:3px\n",
+                path.join(",")
+            );
+        }
+
+        buffer
+    }
+
+    /// Exports the graph as a top-down PlantUML diagram.
+    pub fn to_plantuml(&self) -> String {
+        self.to_plantuml_with(&DiagramOptions::default())
+    }
+
+    /// Exports the graph as a PlantUML diagram with vertices drawn as rectangles.
+    /// Directed edges are drawn as `-->`, undirected as `--`.
+    pub fn to_plantuml_with(&self, options: &DiagramOptions<Key>) -> String {
+        let layout = layout(self, options);
+
+        let mut buffer = String::from("@startuml\n");
+        buffer += match options.direction {
+            Direction::TopDown => "top to bottom direction\n",
+            Direction::LeftRight => "left to right direction\n",
+        };
+
+        for (i, (value, highlighted)) in layout.vertices.iter().enumerate() {
+            buffer += &format!(
+                "rectangle \"{}\" as v{}{}\n",
+                plantuml_label(value),
+                i,
+                if *highlighted { " #FF9966" } else { "" }
+            );
+        }
+
+        for (from, to, weight, highlighted) in &layout.edges {
+            let style = if *highlighted { "[#red,bold]" } else { "" };
+            let arrow = if Type::is_directed() {
+                format!("-{}->", style)
+            } else {
+                format!("-{}-", style)
+            };
+            buffer += &format!("v{} {} v{}", from, arrow, to);
+            if *weight != 0 {
+                buffer += &format!(" : {}", weight);
+            }
+            buffer.push('\n');
+        }
+
+        buffer += "@enduml\n";
+
+        buffer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::marker::{Directed, Undirected};
+    use pretty_assertions::assert_eq;
+
+    fn sample() -> Graph<i32, String, Directed> {
+        let mut graph: Graph<i32, String, Directed> = Graph::new();
+        graph.insert(1, String::from("core"));
+        graph.insert(2, String::from("app \"main\""));
+        graph.insert(3, String::from("utils"));
+        graph.insert_edge(2, 1, 0).unwrap();
+        graph.insert_edge(2, 3, 4).unwrap();
+        graph.insert_edge(3, 1, 0).unwrap();
+        graph
+    }
+
+    #[test]
+    fn mermaid_test() {
+        assert_eq!(
+            sample().to_mermaid(),
+            r#"flowchart TD
+    v0["core"]
+    v1["app #quot;main#quot;"]
+    v2["utils"]
+    v1 --> v0
+    v1 -->|4| v2
+    v2 --> v0
+"#
+        );
+
+        let options = DiagramOptions {
+            direction: Direction::LeftRight,
+            highlighted: vec![1],
+            path: vec![2, 3, 1],
+        };
+        assert_eq!(
+            sample().to_mermaid_with(&options),
+            r#"flowchart LR
+    v0["core"]
+    v1["app #quot;main#quot;"]
+    v2["utils"]
+    v1 --> v0
+    v1 -->|4| v2
+    v2 --> v0
+    classDef highlighted fill:#f96,stroke:#333,stroke-width:2px
+    class v0,v1,v2 highlighted
+    linkStyle 1,2 stroke:#f00,stroke-width:3px
+"#
+        );
+
+        let mut undirected: Graph<char, i32, Undirected> = Graph::new();
+        undirected.insert('a', 1);
+        undirected.insert('b', 2);
+        undirected.insert_edge('b', 'a', 0).unwrap();
+        assert_eq!(
+            undirected.to_mermaid(),
+            "flowchart TD\n    v0[\"1\"]\n    v1[\"2\"]\n    v0 --- v1\n"
+        );
+    }
+
+    #[test]
+    fn plantuml_test() {
+        let options = DiagramOptions {
+            direction: Direction::LeftRight,
+            highlighted: vec![],
+            path: vec![2, 1],
+        };
+        assert_eq!(
+            sample().to_plantuml_with(&options),
+            r#"@startuml
+left to right direction
+rectangle "core" as v0 #FF9966
+rectangle "app <U+0022>main<U+0022>" as v1 #FF9966
+rectangle "utils" as v2
+v1 -[#red,bold]-> v0
+v1 --> v2 : 4
+v2 --> v0
+@enduml
+"#
+        );
+
+        let mut undirected: Graph<char, i32, Undirected> = Graph::new();
+        undirected.insert('a', 1);
+        undirected.insert('b', 2);
+        undirected.insert_edge('a', 'b', 3).unwrap();
+        assert_eq!(
+            undirected.to_plantuml(),
+            "@startuml\ntop to bottom direction\nrectangle \"1\" as v0\nrectangle \"2\" as v1\nv0 -- v1 : 3\n@enduml\n"
+        );
+    }
+
+    #[test]
+    fn multiline_label_test() {
+        let mut graph: Graph<i32, String, Directed> = Graph::new();
+        graph.insert(1, String::from("two\nlines\r\n\"quoted\""));
+
+        assert_eq!(
+            graph.to_mermaid(),
+            "flowchart TD\n    v0[\"two<br>lines<br>#quot;quoted#quot;\"]\n"
+        );
+        assert_eq!(
+            graph.to_plantuml(),
+            "@startuml\ntop to bottom direction\nrectangle \"two\\nlines\\n<U+0022>quoted<U+0022>\" as v0\n@enduml\n"
+        );
+    }
+}
@@ -0,0 +1,312 @@
+//! Readers and writers for the DIMACS shortest path (`p sp`) and maximum flow
+//! (`p max`) formats. See <http://www.diag.uniroma1.it/challenge9/format.shtml>
+//! and <https://lpsolve.sourceforge.net/5.5/DIMACS_maxf.htm>.
+//!
+//! Vertices of DIMACS graphs are numbered from 1 to n, so they are read into
+//! `Graph<usize, Value, Directed>` with values set to default and arc lengths
+//! or capacities stored in `Edge::weight`.
+
+use std::str::{FromStr, SplitWhitespace};
+
+use crate::{graph::Graph, marker::Directed};
+
+/// FlowNetwork is a graph with capacities in edge weights and
+/// designated source and sink vertices.
+pub struct FlowNetwork<Value> {
+    pub graph: Graph<usize, Value, Directed>,
+    pub source: usize,
+    pub sink: usize,
+}
+
+/// Parses the next token of the line.
+pub(crate) fn field<T: FromStr>(
+    tokens: &mut SplitWhitespace,
+    line: usize,
+    what: &str,
+) -> Result<T, String> {
+    let token = tokens
+        .next()
+        .ok_or_else(|| format!("line {}: missing {}", line, what))?;
+    token
+        .parse()
+        .map_err(|_| format!("line {}: couldn't parse {} '{}'", line, what, token))
+}
+
+/// Checks that the graph is keyed by 1..=n and returns n.
+pub(crate) fn check_keys<Value>(graph: &Graph<usize, Value, Directed>) -> Result<usize, String> {
+    let n = graph.len();
+    if (1..=n).any(|key| !graph.contains(&key)) {
+        return Err(format!("vertices must be numbered from 1 to {}", n));
+    }
+    Ok(n)
+}
+
+/// Problem line and arcs of a DIMACS file.
+struct Problem<'a> {
+    n: usize,
+    arcs: Vec<(usize, usize, i64)>,
+    descriptors: Vec<(usize, SplitWhitespace<'a>)>,
+}
+
+fn parse_problem<'a>(input: &'a str, kind: &str) -> Result<Problem<'a>, String> {
+    let mut problem: Option<(usize, usize)> = None;
+    let mut arcs = Vec::new();
+    let mut descriptors = Vec::new();
+
+    for (i, line) in input.lines().enumerate() {
+        let line_no = i + 1;
+        let mut tokens = line.split_whitespace();
+
+        match tokens.next() {
+            None | Some("c") => {}
+            Some("p") => {
+                if problem.is_some() {
+                    return Err(format!("line {}: duplicated problem line", line_no));
+                }
+                let found: String = field(&mut tokens, line_no, "problem type")?;
+                if found != kind {
+                    return Err(format!(
+                        "line {}: expected '{}' problem, found '{}'",
+                        line_no, kind, found
+                    ));
+                }
+                let n = field(&mut tokens, line_no, "number of vertices")?;
+                let m = field(&mut tokens, line_no, "number of arcs")?;
+                problem = Some((n, m));
+            }
+            Some("a") => {
+                let (n, _) =
+                    problem.ok_or_else(|| format!("line {}: arc before problem line", line_no))?;
+                let from: usize = field(&mut tokens, line_no, "arc source")?;
+                let to: usize = field(&mut tokens, line_no, "arc destination")?;
+                let weight: i64 = field(&mut tokens, line_no, "arc weight")?;
+                if !(1..=n).contains(&from) || !(1..=n).contains(&to) {
+                    return Err(format!("line {}: arc references unknown vertex", line_no));
+                }
+                arcs.push((from, to, weight));
+            }
+            Some("n") => {
+                if problem.is_none() {
+                    return Err(format!("line {}: descriptor before problem line", line_no));
+                }
+                descriptors.push((line_no, tokens));
+            }
+            Some(other) => {
+                return Err(format!("line {}: unknown line type '{}'", line_no, other));
+            }
+        }
+    }
+
+    let (n, m) = problem.ok_or("problem line is missing")?;
+    if arcs.len() != m {
+        return Err(format!(
+            "problem line declares {} arcs, but {} were found",
+            m,
+            arcs.len()
+        ));
+    }
+
+    Ok(Problem {
+        n,
+        arcs,
+        descriptors,
+    })
+}
+
+/// Builds the graph of vertices 1..=n. Parallel arcs are merged by the function,
+/// which returns None if the merged weight overflows.
+/// Returns Err if there is no memory for n vertices.
+fn build<Value: Default>(
+    n: usize,
+    arcs: Vec<(usize, usize, i64)>,
+    merge: impl Fn(i64, i64) -> Option<i64>,
+) -> Result<Graph<usize, Value, Directed>, String> {
+    let mut graph: Graph<usize, Value, Directed> = Graph::try_with_capacity(n)
+        .map_err(|_| format!("graph with {} vertices is too large", n))?;
+    for key in 1..=n {
+        graph.insert(key, Value::default());
+    }
+
+    for (from, to, weight) in arcs {
+        let weight = match graph.get_vertex(&from).and_then(|v| v.get_edge(&to)) {
+            Some(existing) => merge(existing.weight, weight)
+                .ok_or_else(|| format!("weight of parallel arcs {} -> {} overflows", from, to))?,
+            None => weight,
+        };
+        graph
+            .insert_edge(from, to, weight)
+            .expect("graph must contain src and dst vertecies");
+    }
+
+    Ok(graph)
+}
+
+fn write_arcs<Value>(buffer: &mut String, graph: &Graph<usize, Value, Directed>, n: usize) {
+    for key in 1..=n {
+        let vertex = graph.get_vertex(&key).expect("key must exist");
+        for edge in vertex.adjancency_list() {
+            *buffer += &format!("a {} {} {}\n", edge.from(), edge.to(), edge.weight);
+        }
+    }
+}
+
+/// Reads a shortest path problem. Of parallel arcs the shortest one is kept.
+pub fn read_shortest_path<Value: Default>(
+    input: &str,
+) -> Result<Graph<usize, Value, Directed>, String> {
+    let problem = parse_problem(input, "sp")?;
+    if let Some((line_no, _)) = problem.descriptors.first() {
+        return Err(format!(
+            "line {}: node descriptors are not allowed in 'sp' problem",
+            line_no
+        ));
+    }
+
+    build(problem.n, problem.arcs, |a, b| Some(a.min(b)))
+}
+
+/// Writes a shortest path problem.
+/// Returns Err if the graph is not keyed by 1..=n.
+pub fn write_shortest_path<Value>(graph: &Graph<usize, Value, Directed>) -> Result<String, String> {
+    let n = check_keys(graph)?;
+
+    let mut buffer = format!("p sp {} {}\n", n, graph.edges_count());
+    write_arcs(&mut buffer, graph, n);
+
+    Ok(buffer)
+}
+
+/// Reads a maximum flow problem. Capacities of parallel arcs are summed up.
+pub fn read_max_flow<Value: Default>(input: &str) -> Result<FlowNetwork<Value>, String> {
+    let problem = parse_problem(input, "max")?;
+
+    let mut source = None;
+    let mut sink = None;
+    for (line_no, mut tokens) in problem.descriptors {
+        let id: usize = field(&mut tokens, line_no, "vertex")?;
+        if !(1..=problem.n).contains(&id) {
+            return Err(format!(
+                "line {}: descriptor references unknown vertex",
+                line_no
+            ));
+        }
+        let slot = match tokens.next() {
+            Some("s") => &mut source,
+            Some("t") => &mut sink,
+            _ => return Err(format!("line {}: descriptor must be 's' or 't'", line_no)),
+        };
+        if slot.replace(id).is_some() {
+            return Err(format!("line {}: duplicated descriptor", line_no));
+        }
+    }
+
+    Ok(FlowNetwork {
+        graph: build(problem.n, problem.arcs, i64::checked_add)?,
+        source: source.ok_or("source descriptor is missing")?,
+        sink: sink.ok_or("sink descriptor is missing")?,
+    })
+}
+
+/// Writes a maximum flow problem.
+/// Returns Err if the graph is not keyed by 1..=n or source or sink don't exist.
+pub fn write_max_flow<Value>(network: &FlowNetwork<Value>) -> Result<String, String> {
+    let n = check_keys(&network.graph)?;
+    if !network.graph.contains(&network.source) || !network.graph.contains(&network.sink) {
+        return Err(String::from(
+            "source and sink must be vertices of the graph",
+        ));
+    }
+
+    let mut buffer = format!("p max {} {}\n", n, network.graph.edges_count());
+    buffer += &format!("n {} s\nn {} t\n", network.source, network.sink);
+    write_arcs(&mut buffer, &network.graph, n);
+
+    Ok(buffer)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn shortest_path_test() {
+        let input = "c sample\np sp 3 4\na 1 2 7\na 2 3 -1\n\na 1 2 5\nc end\na 3 1 2\n";
+
+        let graph: Graph<usize, ()> = read_shortest_path(input).expect("file is not valid");
+        assert_eq!(graph.len(), 3);
+        assert_eq!(graph.edges_count(), 3);
+        assert_eq!(
+            graph.get_vertex(&1).unwrap().get_edge(&2).unwrap().weight,
+            5
+        );
+
+        let output = write_shortest_path(&graph).expect("graph is keyed by 1..=n");
+        assert!(output.starts_with("p sp 3 3\n"));
+        let restored: Graph<usize, ()> = read_shortest_path(&output).expect("file is not valid");
+        assert_eq!(
+            restored
+                .get_vertex(&2)
+                .unwrap()
+                .get_edge(&3)
+                .unwrap()
+                .weight,
+            -1
+        );
+    }
+
+    #[test]
+    fn max_flow_test() {
+        let input = "p max 4 5\nn 1 s\nn 4 t\na 1 2 3\na 1 3 2\na 2 4 2\na 3 4 3\na 1 2 1\n";
+
+        let network: FlowNetwork<()> = read_max_flow(input).expect("file is not valid");
+        assert_eq!((network.source, network.sink), (1, 4));
+        assert_eq!(
+            network
+                .graph
+                .get_vertex(&1)
+                .unwrap()
+                .get_edge(&2)
+                .unwrap()
+                .weight,
+            4
+        );
+
+        let output = write_max_flow(&network).expect("network is valid");
+        let restored: FlowNetwork<()> = read_max_flow(&output).expect("file is not valid");
+        assert_eq!((restored.source, restored.sink), (1, 4));
+        assert_eq!(restored.graph.edges_count(), 4);
+    }
+
+    #[test]
+    fn errors_test() {
+        let invalid_sp = [
+            "a 1 2 3\np sp 2 1\n",
+            "p sp 2 1\na 1 3 1\n",
+            "p sp 2 2\na 1 2 1\n",
+            "p max 2 0\n",
+            "p sp 2 0\nn 1 s\n",
+            "p sp 2 1\na 1 2 x\n",
+            "p sp 18446744073709551615 0\n",
+            "x\n",
+        ];
+        for input in invalid_sp {
+            assert!(
+                read_shortest_path::<()>(input).is_err(),
+                "'{}' must be rejected",
+                input
+            );
+        }
+
+        assert!(read_max_flow::<()>("p max 2 0\nn 1 s\n").is_err());
+        assert!(read_max_flow::<()>("p max 2 0\nn 1 s\nn 2 s\n").is_err());
+        assert!(read_max_flow::<()>("p max 2 0\nn 1 s\nn 3 t\n").is_err());
+        let overflow = "p max 2 2\nn 1 s\nn 2 t\na 1 2 9223372036854775807\na 1 2 1\n";
+        assert!(read_max_flow::<()>(overflow).is_err());
+
+        let mut graph: Graph<usize, ()> = Graph::new();
+        graph.insert(2, ());
+        assert!(write_shortest_path(&graph).is_err());
+    }
+}
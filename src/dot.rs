@@ -0,0 +1,96 @@
+//! Export of graphs in the DOT language of Graphviz.
+//! See <https://graphviz.org/doc/info/lang.html>.
+//!
+//! Vertex keys become node ids and vertex values become node labels.
+//! Weights are written both as the `weight` attribute, which Graphviz uses
+//! for layout, and as edge labels.
+
+use std::{fmt::Display, hash::Hash};
+
+use crate::{graph::Graph, marker::GraphType};
+
+/// Writes the text as a DOT double-quoted string.
+fn quote(text: &impl Display) -> String {
+    format!(
+        "\"{}\"",
+        text.to_string().replace('\\', "\\\\").replace('"', "\\\"")
+    )
+}
+
+impl<Key, Value, Type> Graph<Key, Value, Type>
+where
+    Key: Hash + Eq + Clone + Display + Ord,
+    Value: Display,
+    Type: GraphType,
+{
+    /// Exports the graph as a DOT `digraph` or `graph`.
+    /// Vertices and edges are sorted by keys, so the output is stable.
+    pub fn to_dot(&self) -> String {
+        let (keyword, arrow) = if Type::is_directed() {
+            ("digraph", "->")
+        } else {
+            ("graph", "--")
+        };
+
+        let mut buffer = format!("{} {{\n", keyword);
+        for vertex in self.sorted_vertices() {
+            buffer += &format!(
+                "  {} [label={}];\n",
+                quote(vertex.key()),
+                quote(&vertex.value)
+            );
+        }
+        for edge in self.sorted_edges() {
+            let (from, to) = (quote(edge.from()), quote(edge.to()));
+            if edge.weight != 0 {
+                buffer += &format!(
+                    "  {} {} {} [weight={}, label=\"{}\"];\n",
+                    from, arrow, to, edge.weight, edge.weight
+                );
+            } else {
+                buffer += &format!("  {} {} {};\n", from, arrow, to);
+            }
+        }
+        buffer += "}\n";
+
+        buffer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::marker::{Directed, Undirected};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn to_dot_test() {
+        let mut graph: Graph<i32, String, Directed> = Graph::new();
+        graph.insert(1, String::from("say \"hi\""));
+        graph.insert(2, String::from("B"));
+        graph.insert_edge(1, 2, 5).unwrap();
+        graph.insert_edge(2, 2, 0).unwrap();
+
+        assert_eq!(
+            graph.to_dot(),
+            r#"digraph {
+  "1" [label="say \"hi\""];
+  "2" [label="B"];
+  "1" -> "2" [weight=5, label="5"];
+  "2" -> "2";
+}
+"#
+        );
+
+        let mut undirected: Graph<char, i32, Undirected> = Graph::new();
+        undirected.insert('a', 1);
+        undirected.insert('b', 2);
+        undirected.insert_edge('b', 'a', 0).unwrap();
+        let dot = undirected.to_dot();
+        assert!(dot.starts_with("graph {\n"));
+        assert_eq!(dot.matches(" -- ").count(), 1);
+        // Undirected edges start at the smaller key, whichever way they were inserted
+        assert!(dot.contains("  \"a\" -- \"b\";\n"));
+    }
+}
@@ -1,5 +1,6 @@
 /// Edge is a directed connection between two vertices in a graph that has a weight.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Edge<Key>
 where
     Key: Eq,
@@ -35,6 +36,26 @@ where
     }
 }
 
+/// Parses an edge weight written either as an integer or as
+/// a floating point number without fractional part.
+/// Returns Err if the weight doesn't fit into i64.
+pub(crate) fn parse_weight(raw: &str) -> Result<i64, String> {
+    let raw = raw.trim();
+    if let Ok(weight) = raw.parse() {
+        return Ok(weight);
+    }
+
+    match raw.parse::<f64>() {
+        Ok(weight) if weight.fract() != 0.0 => {
+            Err(format!("edge weight '{}' is not an integer", raw))
+        }
+        // i64::MIN is exactly -2^63, while i64::MAX rounds up to 2^63
+        Ok(weight) if weight >= i64::MIN as f64 && weight < -(i64::MIN as f64) => Ok(weight as i64),
+        Ok(_) => Err(format!("edge weight '{}' is out of range", raw)),
+        Err(_) => Err(format!("couldn't parse edge weight '{}'", raw)),
+    }
+}
+
 impl<Key> PartialEq for Edge<Key>
 where
     Key: Eq,
@@ -96,4 +117,29 @@ mod tests {
             "edge must not be equal because its destination is not the same"
         );
     }
+
+    #[test]
+    fn parse_weight_test() {
+        assert_eq!(parse_weight(" -42 "), Ok(-42));
+        assert_eq!(parse_weight("1e3"), Ok(1000));
+        assert_eq!(parse_weight("-9.223372036854775808e18"), Ok(i64::MIN));
+        assert_eq!(
+            parse_weight("9.223372036854775808e18"),
+            Err(String::from(
+                "edge weight '9.223372036854775808e18' is out of range"
+            ))
+        );
+        assert_eq!(
+            parse_weight("1e30"),
+            Err(String::from("edge weight '1e30' is out of range"))
+        );
+        assert_eq!(
+            parse_weight("1.5"),
+            Err(String::from("edge weight '1.5' is not an integer"))
+        );
+        assert_eq!(
+            parse_weight("x"),
+            Err(String::from("couldn't parse edge weight 'x'"))
+        );
+    }
 }
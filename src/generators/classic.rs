@@ -0,0 +1,369 @@
+//! Constructors of classic graphs: complete graphs, paths, cycles, grids, trees and others.
+//!
+//! Every constructor takes a closure that gives the value of a vertex by its key,
+//! and a closure that gives the weight of an edge by its ends.
+//! In directed graphs edges lead from lower keys to higher ones unless stated otherwise.
+//! Constructors return Err if their arguments describe no graph or a graph whose
+//! vertices don't fit in memory, as the random generators do.
+//!
+//! ```
+//! use graph_rs::{generators::classic, graph::Graph, marker::Undirected};
+//!
+//! let cycle: Graph<usize, (), Undirected> = classic::cycle(5, |_| (), |_, _| 0).unwrap();
+//! assert_eq!(cycle.edges_count(), 5);
+//! ```
+
+use crate::{graph::Graph, marker::GraphType};
+
+/// Builds the graph with n vertices and the given edges.
+/// Returns Err if the vertices don't fit in memory.
+fn build<Type, Value>(
+    n: usize,
+    mut value: impl FnMut(usize) -> Value,
+    edges: impl IntoIterator<Item = (usize, usize)>,
+    mut weight: impl FnMut(usize, usize) -> i64,
+) -> Result<Graph<usize, Value, Type>, String>
+where
+    Type: GraphType,
+{
+    let mut graph = Graph::try_with_capacity(n)
+        .map_err(|_| format!("graph with {} vertices is too large", n))?;
+    for key in 0..n {
+        graph.insert(key, value(key));
+    }
+    for (from, to) in edges {
+        graph
+            .link(from, to, weight(from, to))
+            .expect("edges join existing vertices");
+    }
+    Ok(graph)
+}
+
+/// Complete graph with n vertices, where every pair of vertices is joined.
+/// Directed complete graphs have edges in both directions.
+pub fn complete<Type: GraphType, Value>(
+    n: usize,
+    value: impl FnMut(usize) -> Value,
+    weight: impl FnMut(usize, usize) -> i64,
+) -> Result<Graph<usize, Value, Type>, String> {
+    let edges = (0..n).flat_map(|from| {
+        (0..n)
+            .filter(move |&to| {
+                if Type::is_directed() {
+                    from != to
+                } else {
+                    from < to
+                }
+            })
+            .map(move |to| (from, to))
+    });
+    build(n, value, edges, weight)
+}
+
+/// Path 0 - 1 - ... - (n - 1).
+pub fn path<Type: GraphType, Value>(
+    n: usize,
+    value: impl FnMut(usize) -> Value,
+    weight: impl FnMut(usize, usize) -> i64,
+) -> Result<Graph<usize, Value, Type>, String> {
+    build(n, value, (1..n).map(|to| (to - 1, to)), weight)
+}
+
+/// Cycle 0 - 1 - ... - (n - 1) - 0. The last edge leads from n - 1 to 0.
+/// Cycles need at least 3 vertices, so smaller ones are paths.
+pub fn cycle<Type: GraphType, Value>(
+    n: usize,
+    value: impl FnMut(usize) -> Value,
+    weight: impl FnMut(usize, usize) -> i64,
+) -> Result<Graph<usize, Value, Type>, String> {
+    let closing = (n >= 3).then(|| (n - 1, 0));
+    build(
+        n,
+        value,
+        (1..n).map(|to| (to - 1, to)).chain(closing),
+        weight,
+    )
+}
+
+/// Star with n vertices: the center 0 joined to the leaves 1..n.
+pub fn star<Type: GraphType, Value>(
+    n: usize,
+    value: impl FnMut(usize) -> Value,
+    weight: impl FnMut(usize, usize) -> i64,
+) -> Result<Graph<usize, Value, Type>, String> {
+    build(n, value, (1..n).map(|leaf| (0, leaf)), weight)
+}
+
+/// Wheel with n vertices: the hub 0 joined to every vertex of the cycle 1..n.
+pub fn wheel<Type: GraphType, Value>(
+    n: usize,
+    value: impl FnMut(usize) -> Value,
+    weight: impl FnMut(usize, usize) -> i64,
+) -> Result<Graph<usize, Value, Type>, String> {
+    let spokes = (1..n).map(|rim| (0, rim));
+    let rim = (2..n).map(|to| (to - 1, to));
+    let closing = (n >= 4).then(|| (n - 1, 1));
+    build(n, value, spokes.chain(rim).chain(closing), weight)
+}
+
+/// Grid of rows x columns vertices, where every vertex is joined to its right
+/// and lower neighbours. The vertex in the row r and the column c has the key r * columns + c.
+/// Returns Err if the number of vertices overflows.
+pub fn grid_2d<Type: GraphType, Value>(
+    rows: usize,
+    columns: usize,
+    value: impl FnMut(usize) -> Value,
+    weight: impl FnMut(usize, usize) -> i64,
+) -> Result<Graph<usize, Value, Type>, String> {
+    grid_3d(1, rows, columns, value, weight)
+}
+
+/// Grid of x * y * z vertices, where every vertex is joined to its next neighbours
+/// along every axis. The vertex (i, j, k) has the key (i * y + j) * z + k.
+/// Returns Err if the number of vertices overflows.
+pub fn grid_3d<Type: GraphType, Value>(
+    x: usize,
+    y: usize,
+    z: usize,
+    value: impl FnMut(usize) -> Value,
+    weight: impl FnMut(usize, usize) -> i64,
+) -> Result<Graph<usize, Value, Type>, String> {
+    let n = x
+        .checked_mul(y)
+        .and_then(|n| n.checked_mul(z))
+        .ok_or_else(|| format!("grid of {} x {} x {} vertices is too large", x, y, z))?;
+    // Edges are generated lazily, so a grid too large for memory fails in build
+    let edges = (0..n).flat_map(move |key| {
+        let (i, j, k) = (key / (y * z), key / z % y, key % z);
+        [
+            (i + 1 < x).then_some(key + y * z),
+            (j + 1 < y).then_some(key + z),
+            (k + 1 < z).then_some(key + 1),
+        ]
+        .into_iter()
+        .flatten()
+        .map(move |to| (key, to))
+    });
+    build(n, value, edges, weight)
+}
+
+/// Hypercube of the dimension d: 2^d vertices, joined if their keys differ in one bit.
+/// Returns Err if 2^d doesn't fit into usize.
+pub fn hypercube<Type: GraphType, Value>(
+    d: u32,
+    value: impl FnMut(usize) -> Value,
+    weight: impl FnMut(usize, usize) -> i64,
+) -> Result<Graph<usize, Value, Type>, String> {
+    let n = 1usize
+        .checked_shl(d)
+        .ok_or_else(|| format!("hypercube of dimension {} is too large", d))?;
+    let edges = (0..n).flat_map(|from| {
+        (0..d)
+            .map(move |bit| from | 1 << bit)
+            .filter(move |&to| to != from)
+            .map(move |to| (from, to))
+    });
+    build(n, value, edges, weight)
+}
+
+/// Complete bipartite graph: every vertex of the part 0..m is joined to every vertex
+/// of the part m..m + n.
+/// Returns Err if the number of vertices overflows.
+pub fn complete_bipartite<Type: GraphType, Value>(
+    m: usize,
+    n: usize,
+    value: impl FnMut(usize) -> Value,
+    weight: impl FnMut(usize, usize) -> i64,
+) -> Result<Graph<usize, Value, Type>, String> {
+    let total = m
+        .checked_add(n)
+        .ok_or_else(|| format!("bipartite graph of {} and {} vertices is too large", m, n))?;
+    let edges = (0..m).flat_map(|from| (m..total).map(move |to| (from, to)));
+    build(total, value, edges, weight)
+}
+
+/// Tree with n vertices where every vertex has up to k children, filled level by level.
+/// The root is 0 and the parent of the vertex i is (i - 1) / k.
+/// Edges lead from parents to children. Returns Err if k is 0.
+pub fn k_ary_tree<Type: GraphType, Value>(
+    k: usize,
+    n: usize,
+    value: impl FnMut(usize) -> Value,
+    weight: impl FnMut(usize, usize) -> i64,
+) -> Result<Graph<usize, Value, Type>, String> {
+    if k == 0 {
+        return Err(String::from(
+            "vertices of a tree must be allowed to have children",
+        ));
+    }
+    build(
+        n,
+        value,
+        (1..n).map(|child| ((child - 1) / k, child)),
+        weight,
+    )
+}
+
+/// Binary tree with n vertices, see [`k_ary_tree`].
+pub fn binary_tree<Type: GraphType, Value>(
+    n: usize,
+    value: impl FnMut(usize) -> Value,
+    weight: impl FnMut(usize, usize) -> i64,
+) -> Result<Graph<usize, Value, Type>, String> {
+    k_ary_tree(2, n, value, weight)
+}
+
+/// Petersen graph: the outer cycle 0..5, the inner pentagram 5..10,
+/// and spokes from i to i + 5.
+/// See <https://en.wikipedia.org/wiki/Petersen_graph>.
+pub fn petersen<Type: GraphType, Value>(
+    value: impl FnMut(usize) -> Value,
+    weight: impl FnMut(usize, usize) -> i64,
+) -> Graph<usize, Value, Type> {
+    let outer = (0..5).map(|i| (i, (i + 1) % 5));
+    let spokes = (0..5).map(|i| (i, i + 5));
+    let inner = (0..5).map(|i| (i + 5, (i + 2) % 5 + 5));
+    build(10, value, outer.chain(spokes).chain(inner), weight).expect("10 vertices fit in memory")
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::marker::{Directed, Undirected};
+    use pretty_assertions::assert_eq;
+
+    fn degrees<Value>(graph: &Graph<usize, Value, Undirected>) -> Vec<usize> {
+        let mut degrees = vec![0; graph.len()];
+        for vertex in graph.vertecies() {
+            degrees[*vertex.key()] = graph
+                .degree(vertex.key())
+                .expect("the vertex is in the graph");
+        }
+        degrees
+    }
+
+    fn unit(_: usize) {}
+
+    fn unweighted(_: usize, _: usize) -> i64 {
+        0
+    }
+
+    #[test]
+    fn complete_test() {
+        let graph: Graph<usize, (), Undirected> = complete(5, unit, unweighted).unwrap();
+        assert_eq!(graph.edges_count(), 10);
+        assert_eq!(degrees(&graph), vec![4; 5]);
+
+        let directed: Graph<usize, (), Directed> = complete(5, unit, unweighted).unwrap();
+        assert_eq!(directed.edges_count(), 20);
+    }
+
+    #[test]
+    fn path_cycle_star_wheel_test() {
+        let path: Graph<usize, String, Directed> =
+            path(4, |i| i.to_string(), |from, to| (from * 10 + to) as i64).unwrap();
+        assert_eq!(path.get(&3), Some(&String::from("3")));
+        assert_eq!(
+            path.get_vertex(&1).unwrap().get_edge(&2).unwrap().weight,
+            12
+        );
+        assert_eq!(path.edges_count(), 3);
+
+        let cycle: Graph<usize, (), Undirected> = cycle(6, unit, unweighted).unwrap();
+        assert_eq!(degrees(&cycle), vec![2; 6]);
+        let short: Graph<usize, (), Undirected> = super::cycle(2, unit, unweighted).unwrap();
+        assert_eq!(short.edges_count(), 1);
+
+        let star: Graph<usize, (), Undirected> = star(5, unit, unweighted).unwrap();
+        assert_eq!(degrees(&star), vec![4, 1, 1, 1, 1]);
+
+        let wheel: Graph<usize, (), Undirected> = wheel(6, unit, unweighted).unwrap();
+        assert_eq!(degrees(&wheel), vec![5, 3, 3, 3, 3, 3]);
+        assert_eq!(wheel.edges_count(), 10);
+    }
+
+    #[test]
+    fn grid_test() {
+        let grid: Graph<usize, (), Undirected> = grid_2d(3, 4, unit, unweighted).unwrap();
+        assert_eq!(grid.len(), 12);
+        assert_eq!(grid.edges_count(), 3 * 3 + 2 * 4);
+        assert!(grid.get_vertex(&5).unwrap().get_edge(&9).is_some());
+        assert!(grid.get_vertex(&3).unwrap().get_edge(&4).is_none());
+
+        let cube: Graph<usize, (), Undirected> = grid_3d(2, 3, 4, unit, unweighted).unwrap();
+        assert_eq!(cube.len(), 24);
+        assert_eq!(cube.edges_count(), 3 * 4 + 2 * 2 * 4 + 2 * 3 * 3);
+
+        assert!(grid_2d::<Undirected, ()>(usize::MAX, 2, unit, unweighted).is_err());
+    }
+
+    #[test]
+    fn hypercube_test() {
+        let graph: Graph<usize, (), Undirected> = hypercube(4, unit, unweighted).unwrap();
+        assert_eq!(graph.len(), 16);
+        assert_eq!(degrees(&graph), vec![4; 16]);
+
+        let directed: Graph<usize, (), Directed> = hypercube(3, unit, unweighted).unwrap();
+        assert_eq!(directed.edges_count(), 12);
+        assert!(directed.edges().all(|e| e.from() < e.to()));
+
+        assert_eq!(
+            hypercube::<Undirected, ()>(usize::BITS, unit, unweighted).err(),
+            Some(format!(
+                "hypercube of dimension {} is too large",
+                usize::BITS
+            ))
+        );
+        assert_eq!(
+            hypercube::<Undirected, ()>(usize::BITS - 2, unit, unweighted).err(),
+            Some(format!(
+                "graph with {} vertices is too large",
+                1usize << (usize::BITS - 2)
+            ))
+        );
+    }
+
+    #[test]
+    fn complete_bipartite_test() {
+        let graph: Graph<usize, (), Undirected> =
+            complete_bipartite(2, 3, unit, unweighted).unwrap();
+        assert_eq!(graph.edges_count(), 6);
+        assert_eq!(degrees(&graph), vec![3, 3, 2, 2, 2]);
+
+        assert!(complete_bipartite::<Undirected, ()>(usize::MAX, 1, unit, unweighted).is_err());
+    }
+
+    #[test]
+    fn tree_test() {
+        let binary: Graph<usize, (), Directed> = binary_tree(7, unit, unweighted).unwrap();
+        assert_eq!(binary.edges_count(), 6);
+        let children = |key: usize| -> Vec<usize> {
+            binary
+                .get_vertex(&key)
+                .unwrap()
+                .adjancency_list()
+                .iter()
+                .map(|e| *e.to())
+                .collect()
+        };
+        assert_eq!(children(0), vec![1, 2]);
+        assert_eq!(children(2), vec![5, 6]);
+
+        let ternary: Graph<usize, (), Undirected> = k_ary_tree(3, 13, unit, unweighted).unwrap();
+        assert_eq!(degrees(&ternary)[..5], [3, 4, 4, 4, 1]);
+        assert_eq!(ternary.connected_components().len(), 1);
+
+        assert!(k_ary_tree::<Directed, ()>(0, 3, unit, unweighted).is_err());
+    }
+
+    #[test]
+    fn petersen_test() {
+        let graph: Graph<usize, (), Undirected> = petersen(unit, unweighted);
+        assert_eq!(graph.len(), 10);
+        assert_eq!(graph.edges_count(), 15);
+        assert_eq!(degrees(&graph), vec![3; 10]);
+        // The girth of the Petersen graph is 5
+        assert_eq!(graph.fewest_edges_path(&5, &6).unwrap().unwrap().len(), 3);
+    }
+}
@@ -0,0 +1,5 @@
+//! Generators of graphs for tests and benchmarks.
+//! Vertices are numbered from 0 to n - 1.
+
+pub mod classic;
+pub mod random;
@@ -0,0 +1,568 @@
+//! Random graph generators. The same seed and parameters always produce the same graph,
+//! also across platforms, so generated graphs can be used in tests.
+//!
+//! Vertices have no values. Generators don't create self-loops or parallel edges.
+
+use std::{
+    collections::{BTreeMap, HashSet},
+    ops::RangeInclusive,
+};
+
+use crate::{
+    graph::Graph,
+    marker::{Directed, GraphType, Undirected},
+};
+
+/// RandomOptions configures the seed and the weights of generated edges.
+#[derive(Default)]
+pub struct RandomOptions {
+    /// Seed of the pseudo-random number generator.
+    pub seed: u64,
+
+    /// Range of random edge weights. Edges are unweighted (weight 0) if None.
+    pub weights: Option<RangeInclusive<i64>>,
+}
+
+/// SplitMix64 pseudo-random number generator.
+/// See <https://prng.di.unimi.it/splitmix64.c>.
+struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a number in 0..n without modulo bias. n must not be 0.
+    fn below(&mut self, n: u64) -> u64 {
+        // Numbers under the threshold would make the lowest remainders more likely
+        let threshold = n.wrapping_neg() % n;
+        loop {
+            let x = self.next_u64();
+            if x >= threshold {
+                return x % n;
+            }
+        }
+    }
+
+    fn index(&mut self, n: usize) -> usize {
+        self.below(n as u64) as usize
+    }
+
+    /// Returns true with the probability p.
+    fn chance(&mut self, p: f64) -> bool {
+        ((self.next_u64() >> 11) as f64 / (1u64 << 53) as f64) < p
+    }
+
+    fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.index(i + 1));
+        }
+    }
+}
+
+/// The graph being generated with the random source of its weights.
+struct Generator<'a, Type: GraphType> {
+    graph: Graph<usize, (), Type>,
+    rng: Rng,
+    weights: &'a Option<RangeInclusive<i64>>,
+}
+
+impl<'a, Type: GraphType> Generator<'a, Type> {
+    /// Creates the generator of a graph with n vertices.
+    /// Returns Err if the range of weights is empty or the vertices don't fit in memory.
+    fn new(n: usize, options: &'a RandomOptions) -> Result<Self, String> {
+        if options.weights.as_ref().is_some_and(|w| w.is_empty()) {
+            return Err(String::from("range of weights is empty"));
+        }
+
+        let mut graph = Graph::try_with_capacity(n)
+            .map_err(|_| format!("graph with {} vertices is too large", n))?;
+        for key in 0..n {
+            graph.insert(key, ());
+        }
+        Ok(Generator {
+            graph,
+            rng: Rng {
+                state: options.seed,
+            },
+            weights: &options.weights,
+        })
+    }
+
+    fn connect(&mut self, from: usize, to: usize) {
+        let weight = match self.weights {
+            Some(range) => {
+                let span = (*range.end() as i128 - *range.start() as i128 + 1) as u128;
+                let offset = if span > u64::MAX as u128 {
+                    self.rng.next_u64() as i128
+                } else {
+                    self.rng.below(span as u64) as i128
+                };
+                (*range.start() as i128 + offset) as i64
+            }
+            None => 0,
+        };
+        self.graph
+            .link(from, to, weight)
+            .expect("generated vertices exist");
+    }
+}
+
+/// Returns the pair as it is stored in the set of edges:
+/// ordered for undirected graphs, so both directions are the same edge.
+fn pair<Type: GraphType>(from: usize, to: usize) -> (usize, usize) {
+    if Type::is_directed() {
+        (from, to)
+    } else {
+        (from.min(to), from.max(to))
+    }
+}
+
+fn check_probability(p: f64) -> Result<(), String> {
+    if (0.0..=1.0).contains(&p) {
+        Ok(())
+    } else {
+        Err(format!(
+            "probability must be between 0 and 1, but it is {}",
+            p
+        ))
+    }
+}
+
+/// Generates an Erdős–Rényi G(n, p) graph: every possible edge exists with the probability p.
+/// See <https://en.wikipedia.org/wiki/Erd%C5%91s%E2%80%93R%C3%A9nyi_model>.
+pub fn gnp<Type: GraphType>(
+    n: usize,
+    p: f64,
+    options: &RandomOptions,
+) -> Result<Graph<usize, (), Type>, String> {
+    check_probability(p)?;
+    let mut generator = Generator::new(n, options)?;
+
+    for from in 0..n {
+        let first = if Type::is_directed() { 0 } else { from + 1 };
+        for to in first..n {
+            if from != to && generator.rng.chance(p) {
+                generator.connect(from, to);
+            }
+        }
+    }
+
+    Ok(generator.graph)
+}
+
+/// Generates an Erdős–Rényi G(n, m) graph: m edges chosen uniformly among all possible ones.
+/// Returns Err if there are less than m possible edges.
+pub fn gnm<Type: GraphType>(
+    n: usize,
+    m: usize,
+    options: &RandomOptions,
+) -> Result<Graph<usize, (), Type>, String> {
+    // Halving the even factor first keeps n * (n - 1) / 2 from overflowing
+    let (a, b) = (n, n.saturating_sub(1));
+    let possible = if Type::is_directed() {
+        a.checked_mul(b)
+    } else if a % 2 == 0 {
+        (a / 2).checked_mul(b)
+    } else {
+        a.checked_mul(b / 2)
+    }
+    .ok_or_else(|| format!("graph with {} vertices is too large", n))?;
+    if m > possible {
+        return Err(format!(
+            "graph with {} vertices has at most {} edges, but {} are requested",
+            n, possible, m
+        ));
+    }
+    let mut generator = Generator::new(n, options)?;
+
+    if m > possible / 2 {
+        // Dense graphs are faster to pick from the list of all edges
+        let mut pairs: Vec<(usize, usize)> = (0..n)
+            .flat_map(|from| (0..n).map(move |to| (from, to)))
+            .filter(|&(from, to)| from != to && pair::<Type>(from, to) == (from, to))
+            .collect();
+        generator.rng.shuffle(&mut pairs);
+        for &(from, to) in &pairs[..m] {
+            generator.connect(from, to);
+        }
+    } else {
+        let mut edges = HashSet::with_capacity(m);
+        while edges.len() < m {
+            let (from, to) = (generator.rng.index(n), generator.rng.index(n));
+            if from != to && edges.insert(pair::<Type>(from, to)) {
+                generator.connect(from, to);
+            }
+        }
+    }
+
+    Ok(generator.graph)
+}
+
+/// Generates a Barabási–Albert graph by preferential attachment: every new vertex is
+/// connected to m existing vertices chosen with probabilities proportional to their degrees.
+/// Edges lead from new vertices to existing ones.
+/// See <https://en.wikipedia.org/wiki/Barab%C3%A1si%E2%80%93Albert_model>.
+/// Returns Err unless 1 <= m < n.
+pub fn barabasi_albert<Type: GraphType>(
+    n: usize,
+    m: usize,
+    options: &RandomOptions,
+) -> Result<Graph<usize, (), Type>, String> {
+    if m == 0 || m >= n {
+        return Err(format!(
+            "number of edges of a new vertex must be between 1 and {}, but it is {}",
+            n.saturating_sub(1),
+            m
+        ));
+    }
+    let degrees = (n - m)
+        .checked_mul(m)
+        .and_then(|edges| edges.checked_mul(2))
+        .ok_or_else(|| {
+            format!(
+                "graph with {} vertices and {} edges each is too large",
+                n, m
+            )
+        })?;
+    let mut generator = Generator::new(n, options)?;
+
+    // Every vertex is repeated as many times as its degree
+    let mut repeated: Vec<usize> = Vec::with_capacity(degrees);
+    let mut targets: Vec<usize> = (0..m).collect();
+    for source in m..n {
+        for &target in &targets {
+            generator.connect(source, target);
+        }
+        repeated.extend(&targets);
+        repeated.extend(std::iter::repeat_n(source, m));
+
+        let mut chosen = HashSet::with_capacity(m);
+        targets.clear();
+        while targets.len() < m {
+            let target = repeated[generator.rng.index(repeated.len())];
+            if chosen.insert(target) {
+                targets.push(target);
+            }
+        }
+    }
+
+    Ok(generator.graph)
+}
+
+/// Generates a Watts–Strogatz small-world graph: a ring where every vertex is connected
+/// to its k nearest neighbours, and then every edge is rewired to a random vertex
+/// with the probability beta.
+/// See <https://en.wikipedia.org/wiki/Watts%E2%80%93Strogatz_model>.
+/// Returns Err unless k is even and less than n.
+pub fn watts_strogatz<Type: GraphType>(
+    n: usize,
+    k: usize,
+    beta: f64,
+    options: &RandomOptions,
+) -> Result<Graph<usize, (), Type>, String> {
+    if !k.is_multiple_of(2) || k >= n.max(1) {
+        return Err(format!(
+            "number of neighbours must be even and less than {}, but it is {}",
+            n, k
+        ));
+    }
+    check_probability(beta)?;
+    let mut generator = Generator::new(n, options)?;
+
+    let mut edges: Vec<(usize, usize)> = (1..=k / 2)
+        .flat_map(|distance| (0..n).map(move |from| (from, (from + distance) % n)))
+        .collect();
+    let mut existing: HashSet<(usize, usize)> = edges
+        .iter()
+        .map(|&(from, to)| pair::<Type>(from, to))
+        .collect();
+    // Rewiring changes only edges leaving the vertex, so directed graphs count only them
+    let mut degrees = vec![if Type::is_directed() { k / 2 } else { k }; n];
+
+    for edge in edges.iter_mut() {
+        let (from, to) = *edge;
+        if !generator.rng.chance(beta) || degrees[from] >= n - 1 {
+            continue;
+        }
+        let new_to = loop {
+            let candidate = generator.rng.index(n);
+            if candidate != from && !existing.contains(&pair::<Type>(from, candidate)) {
+                break candidate;
+            }
+        };
+        existing.remove(&pair::<Type>(from, to));
+        existing.insert(pair::<Type>(from, new_to));
+        if !Type::is_directed() {
+            degrees[to] -= 1;
+            degrees[new_to] += 1;
+        }
+        *edge = (from, new_to);
+    }
+
+    for (from, to) in edges {
+        generator.connect(from, to);
+    }
+    Ok(generator.graph)
+}
+
+/// Generates a random d-regular graph, where every vertex has d neighbours,
+/// by randomly pairing d copies of every vertex and retrying the pairs that
+/// would make loops or parallel edges.
+/// Returns Err if n * d is odd or d >= n, when such a graph doesn't exist.
+pub fn random_regular(
+    n: usize,
+    d: usize,
+    options: &RandomOptions,
+) -> Result<Graph<usize, (), Undirected>, String> {
+    let stubs = n
+        .checked_mul(d)
+        .ok_or_else(|| format!("{}-regular graph with {} vertices is too large", d, n))?;
+    if !stubs.is_multiple_of(2) || (d >= n && d > 0) {
+        return Err(format!(
+            "{}-regular graph with {} vertices doesn't exist",
+            d, n
+        ));
+    }
+    let mut generator: Generator<Undirected> = Generator::new(n, options)?;
+
+    // Pairing rarely gets stuck, but it is possible, so it starts over a few times
+    const ATTEMPTS: usize = 100;
+    for _ in 0..ATTEMPTS {
+        if let Some(edges) = pair_stubs(n, d, &mut generator.rng) {
+            for (from, to) in edges {
+                generator.connect(from, to);
+            }
+            return Ok(generator.graph);
+        }
+    }
+
+    Err(format!(
+        "couldn't generate a {}-regular graph with {} vertices in {} attempts",
+        d, n, ATTEMPTS
+    ))
+}
+
+/// Pairs d copies of every vertex into edges. Returns None if the remaining copies
+/// can't be paired without loops or parallel edges.
+fn pair_stubs(n: usize, d: usize, rng: &mut Rng) -> Option<Vec<(usize, usize)>> {
+    let mut edges = Vec::with_capacity(n * d / 2);
+    let mut existing = HashSet::with_capacity(n * d / 2);
+    let mut stubs: Vec<usize> = (0..n).flat_map(|v| std::iter::repeat_n(v, d)).collect();
+
+    while !stubs.is_empty() {
+        rng.shuffle(&mut stubs);
+        let mut rejected: BTreeMap<usize, usize> = BTreeMap::new();
+        for chunk in stubs.chunks(2) {
+            let (from, to) = (chunk[0].min(chunk[1]), chunk[0].max(chunk[1]));
+            if from != to && existing.insert((from, to)) {
+                edges.push((from, to));
+            } else {
+                *rejected.entry(from).or_default() += 1;
+                *rejected.entry(to).or_default() += 1;
+            }
+        }
+
+        let suitable = rejected.keys().any(|&from| {
+            rejected
+                .keys()
+                .any(|&to| from < to && !existing.contains(&(from, to)))
+        });
+        if !rejected.is_empty() && !suitable {
+            return None;
+        }
+        stubs = rejected
+            .into_iter()
+            .flat_map(|(v, count)| std::iter::repeat_n(v, count))
+            .collect();
+    }
+
+    Some(edges)
+}
+
+/// Generates a random directed acyclic graph: the vertices are shuffled, and every edge
+/// leading from an earlier vertex to a later one exists with the probability p.
+pub fn random_dag(
+    n: usize,
+    p: f64,
+    options: &RandomOptions,
+) -> Result<Graph<usize, (), Directed>, String> {
+    check_probability(p)?;
+    let mut generator: Generator<Directed> = Generator::new(n, options)?;
+
+    let mut order: Vec<usize> = (0..n).collect();
+    generator.rng.shuffle(&mut order);
+    for i in 0..n {
+        for j in i + 1..n {
+            if generator.rng.chance(p) {
+                generator.connect(order[i], order[j]);
+            }
+        }
+    }
+
+    Ok(generator.graph)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn edges<Type: GraphType>(graph: &Graph<usize, (), Type>) -> Vec<(usize, usize, i64)> {
+        let mut edges: Vec<(usize, usize, i64)> = graph
+            .edges()
+            .map(|e| {
+                let (from, to) = pair::<Type>(*e.from(), *e.to());
+                (from, to, e.weight)
+            })
+            .collect();
+        edges.sort_unstable();
+        edges
+    }
+
+    fn seeded(seed: u64) -> RandomOptions {
+        RandomOptions {
+            seed,
+            weights: None,
+        }
+    }
+
+    #[test]
+    fn rng_test() {
+        // The first outputs of the reference implementation for the seed 1234567
+        let mut rng = Rng { state: 1234567 };
+        assert_eq!(rng.next_u64(), 6457827717110365317);
+        assert_eq!(rng.next_u64(), 3203168211198807973);
+
+        let mut counts = [0; 3];
+        for _ in 0..3000 {
+            counts[rng.index(3)] += 1;
+        }
+        assert!(counts.iter().all(|c| (900..1100).contains(c)));
+    }
+
+    #[test]
+    fn gnp_test() {
+        let graph: Graph<usize, (), Undirected> = gnp(30, 0.2, &seeded(7)).unwrap();
+        let same: Graph<usize, (), Undirected> = gnp(30, 0.2, &seeded(7)).unwrap();
+        let other: Graph<usize, (), Undirected> = gnp(30, 0.2, &seeded(8)).unwrap();
+        assert_eq!(edges(&graph), edges(&same));
+        assert_ne!(edges(&graph), edges(&other));
+
+        let empty: Graph<usize, (), Directed> = gnp(10, 0.0, &seeded(1)).unwrap();
+        let complete: Graph<usize, (), Directed> = gnp(10, 1.0, &seeded(1)).unwrap();
+        assert_eq!(empty.edges_count(), 0);
+        assert_eq!(complete.edges_count(), 90);
+        assert!(gnp::<Directed>(10, 1.5, &seeded(1)).is_err());
+        assert_eq!(
+            gnp::<Directed>(usize::MAX, 0.0, &seeded(1)).err(),
+            Some(format!("graph with {} vertices is too large", usize::MAX))
+        );
+    }
+
+    #[test]
+    fn gnm_test() {
+        let sparse: Graph<usize, (), Undirected> = gnm(50, 40, &seeded(3)).unwrap();
+        let dense: Graph<usize, (), Directed> = gnm(10, 80, &seeded(3)).unwrap();
+        assert_eq!(sparse.edges_count(), 40);
+        assert_eq!(dense.edges_count(), 80);
+        assert!(dense.vertecies().all(|v| v.get_edge(v.key()).is_none()));
+        assert_eq!(
+            gnm::<Undirected>(4, 7, &seeded(3)).err(),
+            Some(String::from(
+                "graph with 4 vertices has at most 6 edges, but 7 are requested"
+            ))
+        );
+        assert_eq!(
+            gnm::<Directed>(usize::MAX, 1, &seeded(3)).err(),
+            Some(format!("graph with {} vertices is too large", usize::MAX))
+        );
+        assert_eq!(
+            gnm::<Undirected>(5, 11, &seeded(3)).err(),
+            Some(String::from(
+                "graph with 5 vertices has at most 10 edges, but 11 are requested"
+            ))
+        );
+    }
+
+    #[test]
+    fn barabasi_albert_test() {
+        let graph: Graph<usize, (), Undirected> = barabasi_albert(100, 3, &seeded(5)).unwrap();
+        assert_eq!(graph.edges_count(), (100 - 3) * 3);
+        assert_eq!(graph.connected_components().len(), 1);
+
+        let directed: Graph<usize, (), Directed> = barabasi_albert(20, 2, &seeded(5)).unwrap();
+        assert!(directed.edges().all(|e| e.from() > e.to()));
+        assert!(barabasi_albert::<Undirected>(3, 3, &seeded(5)).is_err());
+        assert!(barabasi_albert::<Undirected>(usize::MAX, usize::MAX / 2, &seeded(5)).is_err());
+    }
+
+    #[test]
+    fn watts_strogatz_test() {
+        let ring: Graph<usize, (), Undirected> = watts_strogatz(10, 4, 0.0, &seeded(9)).unwrap();
+        assert_eq!(ring.edges_count(), 20);
+        assert!(ring.vertecies().all(|v| ring.degree(v.key()) == Some(4)));
+
+        let rewired: Graph<usize, (), Undirected> = watts_strogatz(50, 4, 0.3, &seeded(9)).unwrap();
+        assert_eq!(rewired.edges_count(), 100);
+        assert_ne!(
+            edges(&rewired),
+            edges(&watts_strogatz::<Undirected>(50, 4, 0.0, &seeded(9)).unwrap())
+        );
+        assert!(watts_strogatz::<Undirected>(10, 3, 0.1, &seeded(9)).is_err());
+    }
+
+    #[test]
+    fn random_regular_test() {
+        for (n, d) in [(10, 3), (7, 6), (20, 0), (12, 5)] {
+            let graph = random_regular(n, d, &seeded(11)).unwrap();
+            assert_eq!(graph.edges_count(), n * d / 2);
+            assert!(graph.vertecies().all(|v| graph.degree(v.key()) == Some(d)));
+        }
+        assert!(random_regular(5, 3, &seeded(11)).is_err());
+        assert!(random_regular(4, 4, &seeded(11)).is_err());
+        assert!(random_regular(usize::MAX, 3, &seeded(11)).is_err());
+    }
+
+    #[test]
+    fn random_dag_test() {
+        let graph = random_dag(30, 0.3, &seeded(13)).unwrap();
+        assert!(graph.edges_count() > 0);
+        assert!(graph.topological_sort().is_some());
+    }
+
+    #[test]
+    fn weights_test() {
+        let options = RandomOptions {
+            seed: 17,
+            weights: Some(-5..=5),
+        };
+        let graph: Graph<usize, (), Directed> = gnp(20, 0.5, &options).unwrap();
+        assert!(graph.edges().all(|e| (-5..=5).contains(&e.weight)));
+        assert!(graph
+            .edges()
+            .any(|e| e.weight != graph.edges().next().unwrap().weight));
+
+        let undirected: Graph<usize, (), Undirected> = gnm(10, 20, &options).unwrap();
+        for edge in undirected.edges() {
+            let back = undirected
+                .get_vertex(edge.to())
+                .unwrap()
+                .get_edge(edge.from());
+            assert_eq!(back.map(|e| e.weight), Some(edge.weight));
+        }
+
+        let empty = RandomOptions {
+            seed: 0,
+            weights: Some(RangeInclusive::new(1, 0)),
+        };
+        assert!(gnp::<Directed>(5, 0.5, &empty).is_err());
+    }
+}
@@ -0,0 +1,283 @@
+use std::{fmt::Display, hash::Hash, str::FromStr};
+
+use crate::{edge::parse_weight, graph::Graph, marker::GraphType, xml};
+
+/// Gexf is the Graph Exchange XML Format used by Gephi.
+/// See <https://gexf.net/>.
+///
+/// Vertex keys are stored in node ids and vertex values in node labels.
+/// Edge weights are stored in the `weight` attribute of edges.
+pub struct Gexf(String);
+
+impl From<String> for Gexf {
+    fn from(value: String) -> Self {
+        Gexf(value)
+    }
+}
+
+impl<'a> From<&'a str> for Gexf {
+    fn from(value: &'a str) -> Gexf {
+        Gexf(String::from(value))
+    }
+}
+
+impl From<Gexf> for String {
+    fn from(value: Gexf) -> Self {
+        value.0
+    }
+}
+
+impl Display for Gexf {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Gexf {
+    /// Returns true if the default edge type of the graph is directed.
+    /// Allows choosing the type of the graph before converting.
+    pub fn is_directed(&self) -> Result<bool, String> {
+        let root = xml::parse(&self.0)?;
+        is_directed(graph_element(&root)?)
+    }
+}
+
+/// GexfConvertible is a trait that allows converting from and to Gexf.
+pub trait GexfConvertible {
+    fn to_gexf(&self) -> Gexf;
+
+    fn from_gexf(gexf: Gexf) -> Result<Self, String>
+    where
+        Self: Sized;
+}
+
+fn graph_element(root: &xml::Element) -> Result<&xml::Element, String> {
+    if root.local_name() != "gexf" {
+        return Err(format!(
+            "expected <gexf> root element, found <{}>",
+            root.name
+        ));
+    }
+    root.child("graph")
+        .ok_or_else(|| String::from("document does not contain a graph"))
+}
+
+fn edge_type_directed(edge_type: &str) -> Result<bool, String> {
+    match edge_type {
+        "directed" => Ok(true),
+        "undirected" => Ok(false),
+        "mutual" => Err(String::from("mutual edges are not supported")),
+        other => Err(format!("unknown edge type '{}'", other)),
+    }
+}
+
+fn is_directed(graph: &xml::Element) -> Result<bool, String> {
+    // GEXF declares undirected as the default edge type
+    edge_type_directed(graph.attr("defaultedgetype").unwrap_or("undirected"))
+}
+
+impl<Key, Value, Type> GexfConvertible for Graph<Key, Value, Type>
+where
+    Key: Hash + Eq + Clone + Display + FromStr + Ord,
+    Value: Display + FromStr,
+    Type: GraphType,
+{
+    /// Deserializes Gexf into Graph
+    fn from_gexf(gexf: Gexf) -> Result<Self, String> {
+        let raw: String = gexf.into();
+        let root = xml::parse(&raw)?;
+        let element = graph_element(&root)?;
+
+        let directed = is_directed(element)?;
+        if directed != Type::is_directed() {
+            return Err(format!(
+                "graph is declared as {}, but {} graph was requested",
+                if directed { "directed" } else { "undirected" },
+                Type::type_name()
+            ));
+        }
+
+        let mut graph: Self = Graph::new();
+
+        let nodes = element
+            .children_named("nodes")
+            .flat_map(|n| n.children_named("node"));
+        for node in nodes {
+            if node.child("nodes").is_some() {
+                return Err(String::from("hierarchical graphs are not supported"));
+            }
+
+            let id = node.required_attr("id")?;
+            let key: Key = id
+                .parse()
+                .map_err(|_| format!("couldn't parse vertex '{}'", id))?;
+            let label = node.attr("label").unwrap_or("");
+            let value = label
+                .parse()
+                .map_err(|_| format!("couldn't parse value '{}' of vertex '{}'", label, id))?;
+
+            if graph.insert(key, value).is_some() {
+                return Err(format!("vertex '{}' is declared twice", id));
+            }
+        }
+
+        let edges = element
+            .children_named("edges")
+            .flat_map(|e| e.children_named("edge"));
+        for edge in edges {
+            if let Some(edge_type) = edge.attr("type") {
+                if edge_type_directed(edge_type)? != directed {
+                    return Err(format!(
+                        "edge of type '{}' in {} graph",
+                        edge_type,
+                        Type::type_name()
+                    ));
+                }
+            }
+
+            let source = edge.required_attr("source")?;
+            let target = edge.required_attr("target")?;
+            let from: Key = source
+                .parse()
+                .map_err(|_| format!("couldn't parse vertex '{}'", source))?;
+            let to: Key = target
+                .parse()
+                .map_err(|_| format!("couldn't parse vertex '{}'", target))?;
+            let weight = match edge.attr("weight") {
+                Some(raw) => parse_weight(raw)?,
+                None => 0,
+            };
+
+            graph.link(from, to, weight).map_err(|_| {
+                format!("edge {} -> {} references an unknown vertex", source, target)
+            })?;
+        }
+
+        Ok(graph)
+    }
+
+    /// Serializes Graph into Gexf.
+    /// Nodes and edges are sorted by keys, so the output is stable.
+    fn to_gexf(&self) -> Gexf {
+        let mut buffer = String::from(concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+            "<gexf xmlns=\"http://gexf.net/1.3\" version=\"1.3\">\n",
+        ));
+
+        buffer += &format!(
+            "  <graph mode=\"static\" defaultedgetype=\"{}\">\n    <nodes>\n",
+            if Type::is_directed() {
+                "directed"
+            } else {
+                "undirected"
+            }
+        );
+
+        for vertex in self.sorted_vertices() {
+            buffer += &format!(
+                "      <node id=\"{}\" label=\"{}\"/>\n",
+                xml::escape(&vertex.key().to_string()),
+                xml::escape(&vertex.value.to_string())
+            );
+        }
+
+        buffer += "    </nodes>\n    <edges>\n";
+
+        for (i, edge) in self.sorted_edges().into_iter().enumerate() {
+            buffer += &format!(
+                "      <edge id=\"{}\" source=\"{}\" target=\"{}\" weight=\"{}\"/>\n",
+                i,
+                xml::escape(&edge.from().to_string()),
+                xml::escape(&edge.to().to_string()),
+                edge.weight
+            );
+        }
+
+        buffer += "    </edges>\n  </graph>\n</gexf>\n";
+
+        buffer.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::marker::{Directed, Undirected};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn round_trip_test() {
+        let mut graph: Graph<i32, String, Directed> = Graph::new();
+        graph.insert(1, String::from("<A>"));
+        graph.insert(2, String::from("B"));
+        graph.insert_edge(1, 2, 4).unwrap();
+        graph.insert_edge(2, 1, 6).unwrap();
+
+        let gexf = graph.to_gexf();
+        assert_eq!(gexf.is_directed(), Ok(true));
+
+        let restored: Graph<i32, String, Directed> =
+            Graph::from_gexf(gexf).expect("gexf is not valid");
+        assert_eq!(restored.get(&1), Some(&String::from("<A>")));
+        assert_eq!(restored.edges_count(), 2);
+        assert_eq!(
+            restored
+                .get_vertex(&2)
+                .unwrap()
+                .get_edge(&1)
+                .unwrap()
+                .weight,
+            6
+        );
+    }
+
+    #[test]
+    fn from_gexf_test() {
+        let gexf: Gexf = r#"<?xml version="1.0" encoding="UTF-8"?>
+<gexf xmlns="http://www.gexf.net/1.2draft" version="1.2">
+  <meta><creator>Gephi</creator></meta>
+  <graph mode="static">
+    <nodes>
+      <node id="a" label="Hello"/>
+      <node id="b" label="World"/>
+    </nodes>
+    <edges>
+      <edge id="0" source="a" target="b" weight="3.0"/>
+    </edges>
+  </graph>
+</gexf>"#
+            .into();
+        assert_eq!(gexf.is_directed(), Ok(false));
+
+        let graph: Graph<String, String, Undirected> =
+            Graph::from_gexf(gexf).expect("gexf is not valid");
+        let (a, b) = (String::from("a"), String::from("b"));
+        assert_eq!(graph.get(&b), Some(&String::from("World")));
+        assert_eq!(
+            graph.get_vertex(&b).unwrap().get_edge(&a).unwrap().weight,
+            3
+        );
+    }
+
+    #[test]
+    fn errors_test() {
+        let undirected = r#"<gexf><graph><nodes/><edges/></graph></gexf>"#;
+        assert!(Graph::<i32, String, Directed>::from_gexf(undirected.into()).is_err());
+
+        let mutual = r#"<gexf><graph defaultedgetype="mutual"/></gexf>"#;
+        assert!(Graph::<i32, String, Undirected>::from_gexf(mutual.into()).is_err());
+
+        let edge_type = r#"<gexf><graph defaultedgetype="directed">
+  <nodes><node id="1"/><node id="2"/></nodes>
+  <edges><edge source="1" target="2" type="undirected"/></edges>
+</graph></gexf>"#;
+        assert!(Graph::<i32, String, Directed>::from_gexf(edge_type.into()).is_err());
+
+        let dangling = r#"<gexf><graph defaultedgetype="directed">
+  <nodes><node id="1"/></nodes>
+  <edges><edge source="1" target="2"/></edges>
+</graph></gexf>"#;
+        assert!(Graph::<i32, String, Directed>::from_gexf(dangling.into()).is_err());
+    }
+}
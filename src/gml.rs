@@ -0,0 +1,388 @@
+use std::{fmt::Display, hash::Hash, str::FromStr};
+
+use crate::{edge::parse_weight, graph::Graph, marker::GraphType, xml, MAX_DEPTH};
+
+/// GraphModellingLanguage is a hierarchical key-value format for graphs.
+/// See <https://en.wikipedia.org/wiki/Graph_Modelling_Language>.
+///
+/// Vertex keys are stored in `id` and vertex values in `label`, the same way
+/// TGF stores them. Edge weights are stored in `weight`. GML requires integer
+/// ids, so keys that are not integers are written as strings.
+pub struct GraphModellingLanguage(String);
+
+impl From<String> for GraphModellingLanguage {
+    fn from(value: String) -> Self {
+        GraphModellingLanguage(value)
+    }
+}
+
+impl<'a> From<&'a str> for GraphModellingLanguage {
+    fn from(value: &'a str) -> GraphModellingLanguage {
+        GraphModellingLanguage(String::from(value))
+    }
+}
+
+impl From<GraphModellingLanguage> for String {
+    fn from(value: GraphModellingLanguage) -> Self {
+        value.0
+    }
+}
+
+impl Display for GraphModellingLanguage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl GraphModellingLanguage {
+    /// Returns true if the graph is declared as directed.
+    /// Allows choosing the type of the graph before converting.
+    pub fn is_directed(&self) -> Result<bool, String> {
+        let document = parse(&self.0)?;
+        is_directed(graph_list(&document)?)
+    }
+}
+
+/// GmlConvertible is a trait that allows converting from and to GraphModellingLanguage.
+pub trait GmlConvertible {
+    fn to_gml(&self) -> GraphModellingLanguage;
+
+    fn from_gml(gml: GraphModellingLanguage) -> Result<Self, String>
+    where
+        Self: Sized;
+}
+
+/// GmlValue is a value of a GML key.
+#[derive(Debug, PartialEq)]
+enum GmlValue {
+    /// Integers and reals are kept in their textual form.
+    Number(String),
+    String(String),
+    List(Vec<(String, GmlValue)>),
+}
+
+impl GmlValue {
+    fn get(&self, key: &str) -> Option<&GmlValue> {
+        match self {
+            GmlValue::List(items) => items.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    /// Returns the textual form of a number or a string.
+    fn as_scalar(&self) -> Option<&str> {
+        match self {
+            GmlValue::Number(raw) | GmlValue::String(raw) => Some(raw),
+            GmlValue::List(_) => None,
+        }
+    }
+}
+
+enum Token {
+    Open,
+    Close,
+    Word(String),
+    String(String),
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {}
+            '#' => while chars.next_if(|c| *c != '\n').is_some() {},
+            '[' => tokens.push(Token::Open),
+            ']' => tokens.push(Token::Close),
+            '"' => {
+                let mut raw = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some(c) => raw.push(c),
+                        None => return Err(String::from("unterminated string")),
+                    }
+                }
+                tokens.push(Token::String(xml::unescape(&raw)?));
+            }
+            c => {
+                let mut word = String::from(c);
+                while let Some(c) = chars.next_if(|c| !c.is_whitespace() && *c != '[' && *c != ']')
+                {
+                    word.push(c);
+                }
+                tokens.push(Token::Word(word));
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// Parses the document into a list of its top-level keys.
+fn parse(input: &str) -> Result<GmlValue, String> {
+    let mut tokens = tokenize(input)?.into_iter();
+    let list = parse_list(&mut tokens, 0)?;
+    Ok(list)
+}
+
+/// Parses the list at the depth, the top level is 0.
+fn parse_list(tokens: &mut impl Iterator<Item = Token>, depth: usize) -> Result<GmlValue, String> {
+    if depth > MAX_DEPTH {
+        return Err(format!("lists are nested deeper than {}", MAX_DEPTH));
+    }
+    let nested = depth > 0;
+    let mut items = Vec::new();
+
+    loop {
+        let key = match tokens.next() {
+            Some(Token::Word(key)) => key,
+            Some(Token::Close) if nested => return Ok(GmlValue::List(items)),
+            None if !nested => return Ok(GmlValue::List(items)),
+            None => return Err(String::from("unexpected end of input, expected ']'")),
+            Some(_) => return Err(String::from("expected a key")),
+        };
+
+        let value = match tokens.next() {
+            Some(Token::Open) => parse_list(tokens, depth + 1)?,
+            Some(Token::String(s)) => GmlValue::String(s),
+            Some(Token::Word(word)) => {
+                if word.parse::<f64>().is_err() {
+                    return Err(format!(
+                        "value of key '{}' is not a number: '{}'",
+                        key, word
+                    ));
+                }
+                GmlValue::Number(word)
+            }
+            Some(Token::Close) | None => return Err(format!("key '{}' has no value", key)),
+        };
+
+        items.push((key, value));
+    }
+}
+
+fn graph_list(document: &GmlValue) -> Result<&GmlValue, String> {
+    match document.get("graph") {
+        Some(graph @ GmlValue::List(_)) => Ok(graph),
+        _ => Err(String::from("document does not contain a graph")),
+    }
+}
+
+fn is_directed(graph: &GmlValue) -> Result<bool, String> {
+    match graph.get("directed").and_then(GmlValue::as_scalar) {
+        None | Some("0") => Ok(false),
+        Some("1") => Ok(true),
+        Some(other) => Err(format!("'directed' must be 0 or 1, found '{}'", other)),
+    }
+}
+
+/// Writes the text as a GML string. Quotes and ampersands are written as entities.
+fn quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('&', "&amp;").replace('"', "&quot;"))
+}
+
+/// Writes the key as an integer if it is one, otherwise as a string.
+fn id(key: &impl Display) -> String {
+    let raw = key.to_string();
+    if raw.parse::<i64>().is_ok() {
+        raw
+    } else {
+        quote(&raw)
+    }
+}
+
+impl<Key, Value, Type> GmlConvertible for Graph<Key, Value, Type>
+where
+    Key: Hash + Eq + Clone + Display + FromStr + Ord,
+    Value: Display + FromStr,
+    Type: GraphType,
+{
+    /// Deserializes GraphModellingLanguage into Graph
+    fn from_gml(gml: GraphModellingLanguage) -> Result<Self, String> {
+        let raw: String = gml.into();
+        let document = parse(&raw)?;
+        let graph_list = graph_list(&document)?;
+
+        let directed = is_directed(graph_list)?;
+        if directed != Type::is_directed() {
+            return Err(format!(
+                "graph is declared as {}, but {} graph was requested",
+                if directed { "directed" } else { "undirected" },
+                Type::type_name()
+            ));
+        }
+
+        let items = match graph_list {
+            GmlValue::List(items) => items,
+            _ => unreachable!("graph_list returns only lists"),
+        };
+
+        let mut graph: Self = Graph::new();
+
+        for (_, node) in items.iter().filter(|(k, _)| k == "node") {
+            let id = node
+                .get("id")
+                .and_then(GmlValue::as_scalar)
+                .ok_or("node is missing 'id'")?;
+            let key: Key = id
+                .parse()
+                .map_err(|_| format!("couldn't parse vertex '{}'", id))?;
+            let label = node
+                .get("label")
+                .and_then(GmlValue::as_scalar)
+                .unwrap_or("");
+            let value = label
+                .parse()
+                .map_err(|_| format!("couldn't parse value '{}' of vertex '{}'", label, id))?;
+
+            if graph.insert(key, value).is_some() {
+                return Err(format!("vertex '{}' is declared twice", id));
+            }
+        }
+
+        for (_, edge) in items.iter().filter(|(k, _)| k == "edge") {
+            let source = edge
+                .get("source")
+                .and_then(GmlValue::as_scalar)
+                .ok_or("edge is missing 'source'")?;
+            let target = edge
+                .get("target")
+                .and_then(GmlValue::as_scalar)
+                .ok_or("edge is missing 'target'")?;
+            let from: Key = source
+                .parse()
+                .map_err(|_| format!("couldn't parse vertex '{}'", source))?;
+            let to: Key = target
+                .parse()
+                .map_err(|_| format!("couldn't parse vertex '{}'", target))?;
+            let weight = match edge.get("weight").and_then(GmlValue::as_scalar) {
+                Some(raw) => parse_weight(raw)?,
+                None => 0,
+            };
+
+            graph.link(from, to, weight).map_err(|_| {
+                format!("edge {} -> {} references an unknown vertex", source, target)
+            })?;
+        }
+
+        Ok(graph)
+    }
+
+    /// Serializes Graph into GraphModellingLanguage.
+    /// Nodes and edges are sorted by keys, so the output is stable.
+    fn to_gml(&self) -> GraphModellingLanguage {
+        let mut buffer = format!(
+            "graph [\n  directed {}\n",
+            if Type::is_directed() { 1 } else { 0 }
+        );
+
+        for vertex in self.sorted_vertices() {
+            buffer += &format!(
+                "  node [\n    id {}\n    label {}\n  ]\n",
+                id(vertex.key()),
+                quote(&vertex.value.to_string())
+            );
+        }
+
+        for edge in self.sorted_edges() {
+            buffer += &format!(
+                "  edge [\n    source {}\n    target {}\n    weight {}\n  ]\n",
+                id(edge.from()),
+                id(edge.to()),
+                edge.weight
+            );
+        }
+
+        buffer += "]\n";
+
+        buffer.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::{
+        assert_depth_limit,
+        marker::{Directed, Undirected},
+    };
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parse_test() {
+        let document = parse(
+            "# comment\nCreator \"me\"\ngraph [ directed 1 node [ id 1 label \"a &quot;b&quot;\" x -1.5 ] ]",
+        )
+        .expect("gml is valid");
+
+        let graph = graph_list(&document).unwrap();
+        assert_eq!(is_directed(graph), Ok(true));
+        let node = graph.get("node").unwrap();
+        assert_eq!(
+            node.get("label"),
+            Some(&GmlValue::String(String::from("a \"b\"")))
+        );
+        assert_eq!(node.get("x"), Some(&GmlValue::Number(String::from("-1.5"))));
+
+        assert!(parse("graph [ node [ id 1 ]").is_err());
+        assert!(parse("graph [ id ]").is_err());
+        assert!(parse("graph [ id x ]").is_err());
+        assert!(parse("label \"unterminated").is_err());
+
+        assert_depth_limit(|depth| parse(&("a [ ".repeat(depth) + &"] ".repeat(depth))));
+    }
+
+    #[test]
+    fn round_trip_test() {
+        let mut graph: Graph<String, String, Undirected> = Graph::new();
+        graph.insert(String::from("1"), String::from("one"));
+        graph.insert(String::from("b"), String::from("say \"hi\" & bye"));
+        graph
+            .insert_edge(String::from("1"), String::from("b"), 3)
+            .unwrap();
+
+        let gml = graph.to_gml();
+        assert_eq!(gml.is_directed(), Ok(false));
+
+        let restored: Graph<String, String, Undirected> =
+            Graph::from_gml(gml).expect("gml is not valid");
+        let b = String::from("b");
+        assert_eq!(restored.get(&b), Some(&String::from("say \"hi\" & bye")));
+        assert_eq!(
+            restored
+                .get_vertex(&b)
+                .unwrap()
+                .get_edge(&String::from("1"))
+                .unwrap()
+                .weight,
+            3
+        );
+    }
+
+    #[test]
+    fn from_gml_test() {
+        let gml: GraphModellingLanguage = r#"graph [
+  directed 1
+  node [ id 1 label "A" ]
+  node [ id 2 ]
+  edge [ source 1 target 2 weight 2.0 ]
+]"#
+        .into();
+
+        let graph: Graph<i32, String, Directed> = Graph::from_gml(gml).expect("gml is not valid");
+        assert_eq!(graph.get(&1), Some(&String::from("A")));
+        assert_eq!(graph.get(&2), Some(&String::new()));
+        assert_eq!(
+            graph.get_vertex(&1).unwrap().get_edge(&2).unwrap().weight,
+            2
+        );
+
+        let undirected = "graph [ node [ id 1 ] ]";
+        assert!(Graph::<i32, String, Directed>::from_gml(undirected.into()).is_err());
+        let dangling = "graph [ directed 1 node [ id 1 ] edge [ source 1 target 2 ] ]";
+        assert!(Graph::<i32, String, Directed>::from_gml(dangling.into()).is_err());
+    }
+}
@@ -0,0 +1,217 @@
+use std::hash::Hash;
+
+use crate::{graph::Graph, marker::Undirected};
+
+/// Result of the low-link depth-first search, in vertex indices.
+struct LowLink {
+    articulation_points: Vec<usize>,
+    bridges: Vec<(usize, usize)>,
+    components: Vec<Vec<(usize, usize)>>,
+}
+
+/// Runs Tarjan's depth-first search that finds for every vertex the lowest order
+/// reachable from its subtree with at most one back edge.
+/// A child whose subtree can't reach above its parent separates them:
+/// the parent is an articulation point, unless it is a root with a single child,
+/// and the edges of the subtree pushed since the child form a biconnected component.
+/// If the subtree can't reach the parent itself, the edge to the child is a bridge.
+fn low_link(neighbours: &[Vec<usize>]) -> LowLink {
+    let n = neighbours.len();
+    let mut order = vec![usize::MAX; n];
+    let mut low = vec![0; n];
+    let mut counter = 0;
+    let mut is_articulation = vec![false; n];
+    let mut edges = Vec::new();
+    let mut result = LowLink {
+        articulation_points: Vec::new(),
+        bridges: Vec::new(),
+        components: Vec::new(),
+    };
+
+    for root in 0..n {
+        if order[root] != usize::MAX {
+            continue;
+        }
+        order[root] = counter;
+        low[root] = counter;
+        counter += 1;
+        let mut root_children = 0;
+
+        // Every frame is a vertex, its parent and the position in its adjacency list
+        let mut calls = vec![(root, usize::MAX, 0)];
+        while let Some(&(v, parent, next)) = calls.last() {
+            if let Some(&w) = neighbours[v].get(next) {
+                calls.last_mut().expect("frame exists").2 += 1;
+                if w == parent {
+                    continue;
+                }
+                if order[w] == usize::MAX {
+                    order[w] = counter;
+                    low[w] = counter;
+                    counter += 1;
+                    edges.push((v, w));
+                    calls.push((w, v, 0));
+                } else if order[w] < order[v] {
+                    low[v] = low[v].min(order[w]);
+                    edges.push((v, w));
+                }
+                continue;
+            }
+
+            calls.pop();
+            if parent == usize::MAX {
+                continue;
+            }
+            low[parent] = low[parent].min(low[v]);
+            if low[v] > order[parent] {
+                result.bridges.push((parent, v));
+            }
+            if low[v] >= order[parent] {
+                if parent == root {
+                    root_children += 1;
+                } else {
+                    is_articulation[parent] = true;
+                }
+                let mut component = Vec::new();
+                while let Some(edge) = edges.pop() {
+                    component.push(edge);
+                    if edge == (parent, v) {
+                        break;
+                    }
+                }
+                result.components.push(component);
+            }
+        }
+
+        if root_children > 1 {
+            is_articulation[root] = true;
+        }
+    }
+
+    result.articulation_points = (0..n).filter(|&v| is_articulation[v]).collect();
+    result
+}
+
+impl<Key, Value> Graph<Key, Value, Undirected>
+where
+    Key: Hash + Eq + Clone,
+{
+    /// Returns the vertices whose removal increases the number of connected components.
+    /// See <https://en.wikipedia.org/wiki/Biconnected_component>.
+    pub fn articulation_points(&self) -> Vec<&Key> {
+        let (keys, neighbours) = self.neighbour_indices();
+        low_link(&neighbours)
+            .articulation_points
+            .into_iter()
+            .map(|v| keys[v])
+            .collect()
+    }
+
+    /// Returns the edges whose removal increases the number of connected components.
+    /// See <https://en.wikipedia.org/wiki/Bridge_(graph_theory)>.
+    pub fn bridges(&self) -> Vec<(&Key, &Key)> {
+        let (keys, neighbours) = self.neighbour_indices();
+        low_link(&neighbours)
+            .bridges
+            .into_iter()
+            .map(|(v, w)| (keys[v], keys[w]))
+            .collect()
+    }
+
+    /// Returns the biconnected components of the graph as lists of their edges:
+    /// maximal subgraphs that stay connected after the removal of any vertex.
+    /// Every edge belongs to exactly one component, a bridge forms a component alone.
+    /// Isolated vertices belong to no component.
+    /// See <https://en.wikipedia.org/wiki/Biconnected_component>.
+    pub fn biconnected_components(&self) -> Vec<Vec<(&Key, &Key)>> {
+        let (keys, neighbours) = self.neighbour_indices();
+        low_link(&neighbours)
+            .components
+            .into_iter()
+            .map(|component| {
+                component
+                    .into_iter()
+                    .map(|(v, w)| (keys[v], keys[w]))
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::{
+        generators::classic,
+        graph::{
+            algorithms::testing::{graph, sorted, sorted_pairs},
+            Graph,
+        },
+        marker::Undirected,
+    };
+    use pretty_assertions::assert_eq;
+
+    /// Two triangles 0-1-2 and 2-3-4 sharing the vertex 2,
+    /// a bridge 4-5 and a path 5-6-7, and the separate edge 8-9.
+    fn example() -> Graph<i32, (), Undirected> {
+        graph(
+            11,
+            &[
+                (0, 1),
+                (1, 2),
+                (2, 0),
+                (2, 3),
+                (3, 4),
+                (4, 2),
+                (4, 5),
+                (5, 6),
+                (6, 7),
+                (8, 9),
+            ],
+        )
+    }
+
+    #[test]
+    fn articulation_points_test() {
+        assert_eq!(sorted(&example().articulation_points()), vec![2, 4, 5, 6]);
+
+        let cycle: Graph<usize, (), Undirected> = classic::cycle(6, |_| (), |_, _| 0).unwrap();
+        assert!(cycle.articulation_points().is_empty());
+
+        let star: Graph<usize, (), Undirected> = classic::star(4, |_| (), |_, _| 0).unwrap();
+        assert_eq!(star.articulation_points(), vec![&0]);
+    }
+
+    #[test]
+    fn bridges_test() {
+        assert_eq!(
+            sorted_pairs(&example().bridges()),
+            vec![(4, 5), (5, 6), (6, 7), (8, 9)]
+        );
+
+        let petersen: Graph<usize, (), Undirected> = classic::petersen(|_| (), |_, _| 0);
+        assert!(petersen.bridges().is_empty());
+    }
+
+    #[test]
+    fn biconnected_components_test() {
+        let mut components: Vec<Vec<(i32, i32)>> = example()
+            .biconnected_components()
+            .iter()
+            .map(|component| sorted_pairs(component))
+            .collect();
+        components.sort_unstable();
+
+        assert_eq!(
+            components,
+            vec![
+                vec![(0, 1), (0, 2), (1, 2)],
+                vec![(2, 3), (2, 4), (3, 4)],
+                vec![(4, 5)],
+                vec![(5, 6)],
+                vec![(6, 7)],
+                vec![(8, 9)],
+            ]
+        );
+    }
+}
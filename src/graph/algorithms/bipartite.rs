@@ -0,0 +1,540 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    hash::Hash,
+};
+
+use crate::{
+    graph::Graph,
+    marker::{GraphType, Undirected},
+};
+
+/// Bipartiteness is the answer of Graph::is_bipartite together with its proof.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Bipartiteness<'a, Key> {
+    /// The graph is bipartite: every edge joins a vertex of the first part
+    /// with a vertex of the second one.
+    Bipartite(Vec<&'a Key>, Vec<&'a Key>),
+    /// The graph is not bipartite: the vertices of a cycle of odd length in order.
+    /// The first vertex is not repeated at the end.
+    OddCycle(Vec<&'a Key>),
+}
+
+impl<Key> Bipartiteness<'_, Key> {
+    pub fn is_bipartite(&self) -> bool {
+        matches!(self, Bipartiteness::Bipartite(..))
+    }
+}
+
+/// Matched pairs of vertices.
+pub(super) type Matching<'a, Key> = Vec<(&'a Key, &'a Key)>;
+
+/// Coloring of vertex indices into two colors, or an odd cycle if there is none.
+enum Coloring {
+    Colors(Vec<bool>),
+    OddCycle(Vec<usize>),
+}
+
+/// Colors every connected component with breadth-first search.
+/// An edge between two vertices of the same color closes an odd cycle:
+/// both ends are at the same depth of the search tree, so the paths
+/// from them to their lowest common ancestor have the same length.
+fn two_coloring(neighbours: &[Vec<usize>]) -> Coloring {
+    let n = neighbours.len();
+    let mut depth = vec![usize::MAX; n];
+    let mut parent = vec![usize::MAX; n];
+
+    for root in 0..n {
+        if depth[root] != usize::MAX {
+            continue;
+        }
+        depth[root] = 0;
+        let mut queue = VecDeque::from([root]);
+        while let Some(v) = queue.pop_front() {
+            for &w in &neighbours[v] {
+                if depth[w] == usize::MAX {
+                    depth[w] = depth[v] + 1;
+                    parent[w] = v;
+                    queue.push_back(w);
+                } else if depth[w] % 2 == depth[v] % 2 {
+                    let (mut a, mut b) = (v, w);
+                    let (mut to_v, mut to_w) = (vec![a], vec![b]);
+                    while a != b {
+                        a = parent[a];
+                        b = parent[b];
+                        to_v.push(a);
+                        to_w.push(b);
+                    }
+                    // The cycle goes from the ancestor down to v, then from w back up
+                    to_v.reverse();
+                    to_w.pop();
+                    let mut cycle = to_v;
+                    cycle.append(&mut to_w);
+                    return Coloring::OddCycle(cycle);
+                }
+            }
+        }
+    }
+
+    Coloring::Colors(depth.into_iter().map(|d| d % 2 == 1).collect())
+}
+
+/// Solves the assignment problem for the square matrix of costs, where None means
+/// that the row can't be assigned to the column. Returns the column of every row,
+/// or None if there is no assignment.
+/// Follows the O(n^3) version with potentials of rows and columns.
+fn hungarian(costs: &[Vec<Option<i64>>]) -> Option<Vec<usize>> {
+    let n = costs.len();
+    // A missing edge costs more than any assignment of edges, so the cheapest
+    // assignment uses one only if there is no other
+    let largest = costs
+        .iter()
+        .flatten()
+        .flatten()
+        .map(|c| i128::from(*c).abs())
+        .max();
+    let missing = 2 * n as i128 * largest.unwrap_or(0) + 1;
+    let cost = |i: usize, j: usize| costs[i][j].map_or(missing, i128::from);
+
+    // Rows and columns are numbered from 1, column 0 holds the row being assigned
+    let mut row_potential = vec![0i128; n + 1];
+    let mut column_potential = vec![0i128; n + 1];
+    let mut row_of = vec![0; n + 1];
+    let mut way = vec![0; n + 1];
+    for i in 1..=n {
+        row_of[0] = i;
+        let mut column = 0;
+        let mut slack = vec![i128::MAX; n + 1];
+        let mut used = vec![false; n + 1];
+        loop {
+            used[column] = true;
+            let row = row_of[column];
+            let (mut delta, mut next) = (i128::MAX, 0);
+            for j in 1..=n {
+                if used[j] {
+                    continue;
+                }
+                let reduced = cost(row - 1, j - 1) - row_potential[row] - column_potential[j];
+                if reduced < slack[j] {
+                    slack[j] = reduced;
+                    way[j] = column;
+                }
+                if slack[j] < delta {
+                    delta = slack[j];
+                    next = j;
+                }
+            }
+            for j in 0..=n {
+                if used[j] {
+                    row_potential[row_of[j]] += delta;
+                    column_potential[j] -= delta;
+                } else {
+                    slack[j] -= delta;
+                }
+            }
+            column = next;
+            if row_of[column] == 0 {
+                break;
+            }
+        }
+        while column != 0 {
+            let previous = way[column];
+            row_of[column] = row_of[previous];
+            column = previous;
+        }
+    }
+
+    let mut assignment = vec![0; n];
+    for j in 1..=n {
+        assignment[row_of[j] - 1] = j - 1;
+    }
+    assignment
+        .iter()
+        .enumerate()
+        .all(|(i, &j)| costs[i][j].is_some())
+        .then_some(assignment)
+}
+
+impl<Key, Value, Type> Graph<Key, Value, Type>
+where
+    Key: Hash + Eq + Clone,
+    Type: GraphType,
+{
+    /// Returns the keys of the vertices and the lists of their neighbours
+    /// as indices in the keys. Directions of edges are ignored.
+    pub(super) fn neighbour_indices(&self) -> (Vec<&Key>, Vec<Vec<usize>>) {
+        let keys: Vec<&Key> = self.vertecies().map(|v| v.key()).collect();
+        let indices: HashMap<&Key, usize> = keys.iter().enumerate().map(|(i, k)| (*k, i)).collect();
+        let mut neighbours = vec![Vec::new(); keys.len()];
+        for edge in self.edges() {
+            let (from, to) = (indices[edge.from()], indices[edge.to()]);
+            neighbours[from].push(to);
+            if from != to {
+                neighbours[to].push(from);
+            }
+        }
+        (keys, neighbours)
+    }
+
+    /// Checks whether the vertices can be split into two parts,
+    /// so that every edge joins vertices of different parts.
+    /// Directions of edges are ignored.
+    /// Returns both parts, or a cycle of odd length that proves there are none.
+    /// See <https://en.wikipedia.org/wiki/Bipartite_graph>.
+    pub fn is_bipartite(&self) -> Bipartiteness<'_, Key> {
+        let (keys, neighbours) = self.neighbour_indices();
+        match two_coloring(&neighbours) {
+            Coloring::Colors(colors) => {
+                let (mut first, mut second) = (Vec::new(), Vec::new());
+                for (key, color) in keys.into_iter().zip(colors) {
+                    if color {
+                        second.push(key);
+                    } else {
+                        first.push(key);
+                    }
+                }
+                Bipartiteness::Bipartite(first, second)
+            }
+            Coloring::OddCycle(cycle) => {
+                Bipartiteness::OddCycle(cycle.into_iter().map(|i| keys[i]).collect())
+            }
+        }
+    }
+}
+
+impl<Key, Value> Graph<Key, Value, Undirected>
+where
+    Key: Hash + Eq + Clone,
+{
+    /// Finds a maximum cardinality matching of the bipartite graph
+    /// with the Hopcroft-Karp algorithm in O(E * sqrt(V)).
+    /// See <https://en.wikipedia.org/wiki/Hopcroft%E2%80%93Karp_algorithm>.
+    /// Returns the matched pairs, the first vertex of every pair belongs
+    /// to the first part returned by Graph::is_bipartite.
+    /// Returns Err if the graph is not bipartite.
+    pub fn maximum_bipartite_matching(&self) -> Result<Matching<'_, Key>, &'static str> {
+        let (keys, neighbours) = self.neighbour_indices();
+        let colors = match two_coloring(&neighbours) {
+            Coloring::Colors(colors) => colors,
+            Coloring::OddCycle(_) => return Err("graph is not bipartite"),
+        };
+
+        // Vertices of the first part are on the left, the rest on the right
+        let left: Vec<usize> = (0..keys.len()).filter(|&i| !colors[i]).collect();
+        let n = keys.len();
+        let mut mate = vec![usize::MAX; n];
+        let mut layer = vec![usize::MAX; n];
+        let mut next = vec![0; n];
+
+        loop {
+            // Layers the left vertices by the length of the shortest alternating path
+            // from a free one, and stops at the first layer that reaches a free right vertex.
+            // Only the shortest augmenting paths end in that layer.
+            let mut queue = VecDeque::new();
+            for &u in &left {
+                layer[u] = if mate[u] == usize::MAX {
+                    queue.push_back(u);
+                    0
+                } else {
+                    usize::MAX
+                };
+            }
+            let mut last = usize::MAX;
+            while let Some(u) = queue.pop_front() {
+                if layer[u] > last {
+                    break;
+                }
+                for &v in &neighbours[u] {
+                    match mate[v] {
+                        usize::MAX => last = layer[u],
+                        w if layer[w] == usize::MAX => {
+                            layer[w] = layer[u] + 1;
+                            queue.push_back(w);
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            if last == usize::MAX {
+                break;
+            }
+
+            // Augments along vertex-disjoint shortest paths found by depth-first search
+            // through the layers. Left vertices of dead ends leave the layers.
+            for &u in &left {
+                next[u] = 0;
+            }
+            for &root in &left {
+                if mate[root] != usize::MAX {
+                    continue;
+                }
+                let (mut stack, mut through) = (vec![root], Vec::new());
+                while let Some(&u) = stack.last() {
+                    let Some(&v) = neighbours[u].get(next[u]) else {
+                        layer[u] = usize::MAX;
+                        stack.pop();
+                        through.pop();
+                        continue;
+                    };
+                    next[u] += 1;
+                    match mate[v] {
+                        usize::MAX if layer[u] == last => {
+                            through.push(v);
+                            for (&u, &v) in stack.iter().zip(&through) {
+                                mate[u] = v;
+                                mate[v] = u;
+                            }
+                            break;
+                        }
+                        w if layer[u] < last && layer[w] == layer[u] + 1 => {
+                            through.push(v);
+                            stack.push(w);
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+
+        Ok(left
+            .into_iter()
+            .filter(|&u| mate[u] != usize::MAX)
+            .map(|u| (keys[u], keys[mate[u]]))
+            .collect())
+    }
+
+    /// Finds the perfect matching between the parts with the smallest total weight
+    /// of its edges with the Hungarian (Kuhn-Munkres) algorithm in O(V^3).
+    /// See <https://en.wikipedia.org/wiki/Hungarian_algorithm>.
+    /// Vertices outside the parts and edges inside a part are ignored.
+    /// Returns the pairs in the order of the first part and their total weight.
+    /// Returns None if there is no perfect matching.
+    /// Returns Err if a vertex doesn't exist, is listed twice or belongs to both parts,
+    /// or the parts have different sizes.
+    pub fn minimum_cost_assignment<'a>(
+        &'a self,
+        first: &[Key],
+        second: &[Key],
+    ) -> Result<Option<(Matching<'a, Key>, i64)>, &'static str> {
+        if first.len() != second.len() {
+            return Err("parts have different sizes");
+        }
+        let mut columns: HashMap<&Key, usize> = HashMap::with_capacity(second.len());
+        for (j, key) in second.iter().enumerate() {
+            let key = self
+                .get_vertex(key)
+                .ok_or("vertex is not in the graph")?
+                .key();
+            if columns.insert(key, j).is_some() {
+                return Err("vertex is listed twice");
+            }
+        }
+
+        let mut rows = Vec::with_capacity(first.len());
+        let mut costs = Vec::with_capacity(first.len());
+        for key in first {
+            let vertex = self.get_vertex(key).ok_or("vertex is not in the graph")?;
+            if columns.contains_key(vertex.key()) {
+                return Err("vertex belongs to both parts");
+            }
+            if rows.contains(&vertex.key()) {
+                return Err("vertex is listed twice");
+            }
+            let mut row = vec![None; second.len()];
+            for edge in vertex.adjancency_list() {
+                if let Some(&j) = columns.get(edge.to()) {
+                    row[j] = Some(edge.weight);
+                }
+            }
+            rows.push(vertex.key());
+            costs.push(row);
+        }
+
+        let Some(assignment) = hungarian(&costs) else {
+            return Ok(None);
+        };
+        let keys: Vec<&Key> = second
+            .iter()
+            .map(|key| self.get_vertex(key).expect("vertex is checked").key())
+            .collect();
+        let total = (0..rows.len())
+            .map(|i| costs[i][assignment[i]].expect("assignment uses edges"))
+            .sum();
+        let pairs = rows
+            .into_iter()
+            .zip(assignment)
+            .map(|(key, j)| (key, keys[j]))
+            .collect();
+        Ok(Some((pairs, total)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::Bipartiteness;
+    use crate::{
+        generators::{
+            classic,
+            random::{self, RandomOptions},
+        },
+        graph::{
+            algorithms::testing::{graph, sorted, weighted},
+            Graph,
+        },
+        marker::{Directed, GraphType, Undirected},
+    };
+    use pretty_assertions::assert_eq;
+
+    /// Checks that the pairs are edges of the graph and no vertex is matched twice.
+    fn assert_matching<Type: GraphType>(graph: &Graph<i32, (), Type>, pairs: &[(&i32, &i32)]) {
+        let mut matched: Vec<i32> = pairs.iter().flat_map(|(a, b)| [**a, **b]).collect();
+        matched.sort_unstable();
+        matched.dedup();
+        assert_eq!(matched.len(), pairs.len() * 2, "vertex is matched twice");
+        for (a, b) in pairs {
+            assert!(graph.get_vertex(a).unwrap().get_edge(b).is_some());
+        }
+    }
+
+    #[test]
+    fn is_bipartite_test() {
+        let even: Graph<i32, (), Undirected> = graph(7, &[(0, 1), (1, 2), (2, 3), (3, 0), (4, 5)]);
+        let Bipartiteness::Bipartite(first, second) = even.is_bipartite() else {
+            panic!("even cycle is bipartite");
+        };
+        let (first, second) = (sorted(&first), sorted(&second));
+        let parts = if first.contains(&0) {
+            (first, second)
+        } else {
+            (second, first)
+        };
+        assert!(parts.0.contains(&2) && parts.1.contains(&1) && parts.1.contains(&3));
+        assert_eq!(parts.0.len() + parts.1.len(), 7);
+
+        let odd: Graph<i32, (), Undirected> =
+            graph(7, &[(0, 1), (1, 2), (2, 3), (3, 4), (4, 0), (4, 5), (5, 6)]);
+        let proof = odd.is_bipartite();
+        assert!(!proof.is_bipartite());
+        let Bipartiteness::OddCycle(cycle) = proof else {
+            unreachable!()
+        };
+        assert_eq!(sorted(&cycle), vec![0, 1, 2, 3, 4]);
+        for (i, key) in cycle.iter().enumerate() {
+            let following = cycle[(i + 1) % cycle.len()];
+            assert!(odd.get_vertex(key).unwrap().get_edge(following).is_some());
+        }
+
+        let self_loop: Graph<i32, (), Directed> = graph(2, &[(0, 1), (1, 1)]);
+        assert_eq!(self_loop.is_bipartite(), Bipartiteness::OddCycle(vec![&1]));
+    }
+
+    #[test]
+    fn maximum_bipartite_matching_test() {
+        // Greedy matching of 0 with 4 leaves 1 without a pair
+        let shifts: Graph<i32, (), Undirected> =
+            graph(8, &[(0, 4), (0, 5), (1, 4), (2, 5), (2, 6), (3, 6), (3, 7)]);
+        let pairs = shifts.maximum_bipartite_matching().unwrap();
+        assert_eq!(pairs.len(), 4);
+        assert_matching(&shifts, &pairs);
+
+        let grid: Graph<usize, (), Undirected> = classic::grid_2d(5, 5, |_| (), |_, _| 0).unwrap();
+        assert_eq!(grid.maximum_bipartite_matching().unwrap().len(), 12);
+
+        let complete: Graph<usize, (), Undirected> =
+            classic::complete_bipartite(3, 6, |_| (), |_, _| 0).unwrap();
+        assert_eq!(complete.maximum_bipartite_matching().unwrap().len(), 3);
+
+        let triangle: Graph<i32, (), Undirected> = graph(3, &[(0, 1), (1, 2), (2, 0)]);
+        assert_eq!(
+            triangle.maximum_bipartite_matching(),
+            Err("graph is not bipartite")
+        );
+    }
+
+    #[test]
+    fn maximum_bipartite_matching_random_test() {
+        // Random edges between 0..10 and 10..20, compared with the general matching
+        for seed in 0..30 {
+            let options = RandomOptions {
+                seed,
+                weights: None,
+            };
+            let random: Graph<usize, (), Undirected> = random::gnp(20, 0.15, &options).unwrap();
+            let edges: Vec<(i32, i32)> = random
+                .edges()
+                .map(|e| (*e.from() as i32, *e.to() as i32))
+                .filter(|&(from, to)| (from < 10) != (to < 10))
+                .collect();
+            let bipartite: Graph<i32, (), Undirected> = graph(20, &edges);
+
+            let pairs = bipartite.maximum_bipartite_matching().unwrap();
+            assert_matching(&bipartite, &pairs);
+            assert_eq!(
+                pairs.len(),
+                bipartite.maximum_matching().len(),
+                "seed {}",
+                seed
+            );
+        }
+    }
+
+    #[test]
+    fn minimum_cost_assignment_test() {
+        // Workers 0, 1, 2 and shifts 3, 4, 5, the cheapest pairs can't all be taken
+        let mut shifts: Graph<i32, (), Undirected> = weighted(
+            6,
+            &[
+                (0, 3, 4),
+                (0, 4, 1),
+                (0, 5, 3),
+                (1, 3, 2),
+                (1, 4, 0),
+                (1, 5, 5),
+                (2, 3, 3),
+                (2, 4, 2),
+                (2, 5, 2),
+            ],
+        );
+        let (pairs, total) = shifts
+            .minimum_cost_assignment(&[0, 1, 2], &[3, 4, 5])
+            .unwrap()
+            .unwrap();
+        assert_eq!(pairs, vec![(&0, &4), (&1, &3), (&2, &5)]);
+        assert_eq!(total, 5);
+
+        // Negative weights are costs too
+        shifts.insert_edge(2, 3, -10).unwrap();
+        let (_, total) = shifts
+            .minimum_cost_assignment(&[0, 1, 2], &[3, 4, 5])
+            .unwrap()
+            .unwrap();
+        // 2 takes 3, then 0 takes 5 and 1 takes 4
+        assert_eq!(total, -10 + 3);
+
+        // 0 and 1 both can take only shift 3
+        let missing: Graph<i32, (), Undirected> = graph(4, &[(0, 2), (1, 2), (1, 3)]);
+        assert_eq!(
+            missing.minimum_cost_assignment(&[0, 1], &[2, 3]),
+            Ok(Some((vec![(&0, &2), (&1, &3)], 0)))
+        );
+        let missing: Graph<i32, (), Undirected> = graph(4, &[(0, 2), (1, 2)]);
+        assert_eq!(missing.minimum_cost_assignment(&[0, 1], &[2, 3]), Ok(None));
+
+        assert_eq!(
+            missing.minimum_cost_assignment(&[0, 1], &[2]),
+            Err("parts have different sizes")
+        );
+        assert_eq!(
+            missing.minimum_cost_assignment(&[0, 2], &[2, 3]),
+            Err("vertex belongs to both parts")
+        );
+        assert_eq!(
+            missing.minimum_cost_assignment(&[0, 9], &[2, 3]),
+            Err("vertex is not in the graph")
+        );
+        assert_eq!(
+            missing.minimum_cost_assignment(&[], &[]),
+            Ok(Some((vec![], 0)))
+        );
+    }
+}
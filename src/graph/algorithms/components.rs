@@ -0,0 +1,179 @@
+use std::{
+    collections::{HashMap, HashSet},
+    hash::Hash,
+};
+
+use crate::{
+    graph::Graph,
+    marker::{Directed, GraphType},
+};
+
+impl<Key, Value, Type> Graph<Key, Value, Type>
+where
+    Key: Hash + Eq + Clone,
+    Type: GraphType,
+{
+    /// Returns the connected components of the graph.
+    /// Directions of edges are ignored, so for directed graphs
+    /// the weakly connected components are returned.
+    pub fn connected_components(&self) -> Vec<Vec<&Key>> {
+        let mut neighbours: HashMap<&Key, Vec<&Key>> = HashMap::with_capacity(self.len());
+        for edge in self.edges() {
+            neighbours.entry(edge.from()).or_default().push(edge.to());
+            neighbours.entry(edge.to()).or_default().push(edge.from());
+        }
+
+        let mut visited: HashSet<&Key> = HashSet::with_capacity(self.len());
+        let mut components = Vec::new();
+
+        for vertex in self.vertecies() {
+            if !visited.insert(vertex.key()) {
+                continue;
+            }
+
+            let mut component = Vec::new();
+            let mut stack = vec![vertex.key()];
+            while let Some(key) = stack.pop() {
+                component.push(key);
+                for neighbour in neighbours.get(key).into_iter().flatten() {
+                    if visited.insert(neighbour) {
+                        stack.push(neighbour);
+                    }
+                }
+            }
+            components.push(component);
+        }
+
+        components
+    }
+}
+
+impl<Key, Value> Graph<Key, Value, Directed>
+where
+    Key: Hash + Eq + Clone,
+{
+    /// Returns the strongly connected components of the directed graph
+    /// with Tarjan's algorithm. Components are returned in reverse topological order:
+    /// no edge leads from a component to the components before it.
+    /// See <https://en.wikipedia.org/wiki/Tarjan%27s_strongly_connected_components_algorithm>.
+    pub fn strongly_connected_components(&self) -> Vec<Vec<&Key>> {
+        let keys: Vec<&Key> = self.vertecies().map(|v| v.key()).collect();
+        let indices: HashMap<&Key, usize> = keys.iter().enumerate().map(|(i, k)| (*k, i)).collect();
+        let adj: Vec<Vec<usize>> = self
+            .vertecies()
+            .map(|v| {
+                v.adjancency_list()
+                    .iter()
+                    .map(|e| indices[e.to()])
+                    .collect()
+            })
+            .collect();
+
+        let n = keys.len();
+        let mut order = vec![usize::MAX; n];
+        let mut low = vec![0; n];
+        let mut on_stack = vec![false; n];
+        let mut stack = Vec::new();
+        let mut counter = 0;
+        let mut components = Vec::new();
+
+        for root in 0..n {
+            if order[root] != usize::MAX {
+                continue;
+            }
+
+            // Every frame is a vertex and the position in its adjacency list
+            let mut calls = vec![(root, 0)];
+            order[root] = counter;
+            low[root] = counter;
+            counter += 1;
+            stack.push(root);
+            on_stack[root] = true;
+
+            while let Some(&(v, next)) = calls.last() {
+                if let Some(&w) = adj[v].get(next) {
+                    calls.last_mut().expect("frame exists").1 += 1;
+                    if order[w] == usize::MAX {
+                        order[w] = counter;
+                        low[w] = counter;
+                        counter += 1;
+                        stack.push(w);
+                        on_stack[w] = true;
+                        calls.push((w, 0));
+                    } else if on_stack[w] {
+                        low[v] = low[v].min(order[w]);
+                    }
+                    continue;
+                }
+
+                calls.pop();
+                if let Some(&(parent, _)) = calls.last() {
+                    low[parent] = low[parent].min(low[v]);
+                }
+                if low[v] == order[v] {
+                    let mut component = Vec::new();
+                    loop {
+                        let w = stack.pop().expect("vertex is on the stack");
+                        on_stack[w] = false;
+                        component.push(keys[w]);
+                        if w == v {
+                            break;
+                        }
+                    }
+                    components.push(component);
+                }
+            }
+        }
+
+        components
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::{
+        graph::algorithms::testing::{graph, sorted},
+        marker::{Directed, Undirected},
+    };
+    use pretty_assertions::assert_eq;
+
+    fn sorted_components(components: Vec<Vec<&i32>>) -> Vec<Vec<i32>> {
+        let mut components: Vec<Vec<i32>> = components
+            .iter()
+            .map(|component| sorted(component))
+            .collect();
+        components.sort_unstable();
+        components
+    }
+
+    #[test]
+    fn connected_components_test() {
+        let undirected = graph::<Undirected>(6, &[(0, 1), (1, 2), (3, 4)]);
+        assert_eq!(
+            sorted_components(undirected.connected_components()),
+            vec![vec![0, 1, 2], vec![3, 4], vec![5]]
+        );
+
+        let directed = graph::<Directed>(3, &[(0, 1), (2, 1)]);
+        assert_eq!(
+            sorted_components(directed.connected_components()),
+            vec![vec![0, 1, 2]]
+        );
+    }
+
+    #[test]
+    fn strongly_connected_components_test() {
+        let graph = graph::<Directed>(6, &[(0, 1), (1, 2), (2, 0), (2, 3), (3, 4), (4, 3), (5, 5)]);
+
+        let components = graph.strongly_connected_components();
+        assert_eq!(
+            sorted_components(components.clone()),
+            vec![vec![0, 1, 2], vec![3, 4], vec![5]]
+        );
+
+        // {3, 4} is reachable from {0, 1, 2}, so it comes first
+        let position = |key: i32| components.iter().position(|c| c.contains(&&key));
+        assert!(position(3) < position(0));
+    }
+}
@@ -0,0 +1,287 @@
+use std::{collections::HashMap, fmt::Display, hash::Hash};
+
+use crate::{edge::Edge, graph::Graph, marker::GraphType};
+
+/// NotEulerian explains why the graph has no Eulerian path or circuit.
+#[derive(Debug, Clone, PartialEq)]
+pub enum NotEulerian<'a, Key> {
+    /// Vertices of an undirected graph with an odd degree.
+    /// A circuit allows none of them, a path allows two.
+    OddDegree(Vec<&'a Key>),
+    /// Vertices of a directed graph whose out-degree differs from the in-degree,
+    /// with the difference. A circuit allows none of them, a path allows
+    /// one vertex with 1 to start at and one with -1 to end at.
+    Unbalanced(Vec<(&'a Key, i64)>),
+    /// Vertices with edges that can't be reached from the rest of the edges.
+    Disconnected(Vec<&'a Key>),
+}
+
+impl<Key: Display> Display for NotEulerian<'_, Key> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let join = |keys: Vec<String>| keys.join(", ");
+        match self {
+            NotEulerian::OddDegree(keys) => write!(
+                f,
+                "vertices of odd degree: {}",
+                join(keys.iter().map(|k| k.to_string()).collect())
+            ),
+            NotEulerian::Unbalanced(keys) => write!(
+                f,
+                "vertices with out-degree minus in-degree: {}",
+                join(
+                    keys.iter()
+                        .map(|(k, d)| format!("{} ({:+})", k, d))
+                        .collect()
+                )
+            ),
+            NotEulerian::Disconnected(keys) => write!(
+                f,
+                "edges of vertices {} are not connected with the rest",
+                join(keys.iter().map(|k| k.to_string()).collect())
+            ),
+        }
+    }
+}
+
+impl<Key, Value, Type> Graph<Key, Value, Type>
+where
+    Key: Hash + Eq + Clone,
+    Type: GraphType,
+{
+    /// Finds an Eulerian circuit with Hierholzer's algorithm:
+    /// a closed walk that uses every edge exactly once.
+    /// It exists if all edges are connected and every vertex has an even degree
+    /// in an undirected graph, or the same in-degree and out-degree in a directed one.
+    /// See <https://en.wikipedia.org/wiki/Eulerian_path>.
+    /// Returns the edges in the order of the walk, every edge starts where
+    /// the previous one ends. Returns the violating vertices if there is no circuit.
+    pub fn eulerian_circuit(&self) -> Result<Vec<&Edge<Key>>, NotEulerian<'_, Key>> {
+        self.eulerian(true)
+    }
+
+    /// Finds an Eulerian path with Hierholzer's algorithm:
+    /// a walk that uses every edge exactly once, not necessarily closed.
+    /// Besides the conditions of the circuit, it allows two vertices of odd degree
+    /// in an undirected graph, or a vertex with one more outgoing edge to start at
+    /// and a vertex with one more incoming edge to end at in a directed one.
+    /// Returns the edges in the order of the walk, or the violating vertices
+    /// if there is no path.
+    pub fn eulerian_path(&self) -> Result<Vec<&Edge<Key>>, NotEulerian<'_, Key>> {
+        self.eulerian(false)
+    }
+
+    fn eulerian(&self, closed: bool) -> Result<Vec<&Edge<Key>>, NotEulerian<'_, Key>> {
+        let keys: Vec<&Key> = self.vertecies().map(|v| v.key()).collect();
+        let indices: HashMap<&Key, usize> = keys.iter().enumerate().map(|(i, k)| (*k, i)).collect();
+
+        // Both copies of an undirected edge share the same id
+        let mut ids: HashMap<(usize, usize), usize> = HashMap::new();
+        let adj: Vec<Vec<(usize, usize, &Edge<Key>)>> = self
+            .vertecies()
+            .map(|v| {
+                v.adjancency_list()
+                    .iter()
+                    .map(|e| {
+                        let (from, to) = (indices[e.from()], indices[e.to()]);
+                        let pair = if Type::is_directed() {
+                            (from, to)
+                        } else {
+                            (from.min(to), from.max(to))
+                        };
+                        let next = ids.len();
+                        (to, *ids.entry(pair).or_insert(next), e)
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let start = if Type::is_directed() {
+            let mut balance = vec![0i64; keys.len()];
+            for (v, edges) in adj.iter().enumerate() {
+                for &(w, _, _) in edges {
+                    balance[v] += 1;
+                    balance[w] -= 1;
+                }
+            }
+            let unbalanced: Vec<usize> = (0..keys.len()).filter(|&v| balance[v] != 0).collect();
+            let starts: Vec<usize> = unbalanced
+                .iter()
+                .copied()
+                .filter(|&v| balance[v] == 1)
+                .collect();
+            match unbalanced.len() {
+                0 => None,
+                2 if !closed && starts.len() == 1 => Some(starts[0]),
+                _ => {
+                    return Err(NotEulerian::Unbalanced(
+                        unbalanced
+                            .into_iter()
+                            .map(|v| (keys[v], balance[v]))
+                            .collect(),
+                    ))
+                }
+            }
+        } else {
+            let odd: Vec<usize> = (0..keys.len())
+                .filter(|&v| self.degree(keys[v]).expect("keys are in the graph") % 2 == 1)
+                .collect();
+            match odd.len() {
+                0 => None,
+                2 if !closed => Some(odd[0]),
+                _ => {
+                    return Err(NotEulerian::OddDegree(
+                        odd.into_iter().map(|v| keys[v]).collect(),
+                    ))
+                }
+            }
+        };
+        let Some(start) = start.or_else(|| (0..keys.len()).find(|&v| !adj[v].is_empty())) else {
+            return Ok(Vec::new());
+        };
+
+        // Follows unused edges until it gets stuck, then backtracks and splices
+        // the detours in. Edges are collected in reverse order when backtracking.
+        let mut used = vec![false; ids.len()];
+        let mut next = vec![0; keys.len()];
+        let mut walk = Vec::with_capacity(ids.len());
+        let mut stack: Vec<(usize, Option<&Edge<Key>>)> = vec![(start, None)];
+        while let Some(&(v, via)) = stack.last() {
+            while next[v] < adj[v].len() && used[adj[v][next[v]].1] {
+                next[v] += 1;
+            }
+            if let Some(&(w, id, edge)) = adj[v].get(next[v]) {
+                used[id] = true;
+                stack.push((w, Some(edge)));
+            } else {
+                stack.pop();
+                walk.extend(via);
+            }
+        }
+
+        if walk.len() < ids.len() {
+            return Err(NotEulerian::Disconnected(
+                (0..keys.len())
+                    .filter(|&v| adj[v].iter().any(|&(_, id, _)| !used[id]))
+                    .map(|v| keys[v])
+                    .collect(),
+            ));
+        }
+        walk.reverse();
+        Ok(walk)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::NotEulerian;
+    use crate::{
+        edge::Edge,
+        graph::{
+            algorithms::testing::{graph, sorted},
+            Graph,
+        },
+        marker::{Directed, GraphType, Undirected},
+    };
+    use pretty_assertions::assert_eq;
+
+    /// Checks that the walk is connected and uses every edge once.
+    fn assert_walk<Type: GraphType>(graph: &Graph<i32, (), Type>, walk: &[&Edge<i32>]) {
+        for pair in walk.windows(2) {
+            assert_eq!(pair[0].to(), pair[1].from());
+        }
+        let mut edges: Vec<(i32, i32)> = walk
+            .iter()
+            .map(|e| (*e.from(), *e.to()))
+            .map(|(a, b)| {
+                if Type::is_directed() {
+                    (a, b)
+                } else {
+                    (a.min(b), a.max(b))
+                }
+            })
+            .collect();
+        edges.sort_unstable();
+        edges.dedup();
+        assert_eq!(edges.len(), graph.edges_count());
+        assert_eq!(walk.len(), graph.edges_count());
+    }
+
+    #[test]
+    fn undirected_test() {
+        // Two triangles sharing the vertex 2
+        let bowtie: Graph<i32, (), Undirected> =
+            graph(5, &[(0, 1), (1, 2), (2, 0), (2, 3), (3, 4), (4, 2)]);
+        let circuit = bowtie.eulerian_circuit().unwrap();
+        assert_walk(&bowtie, &circuit);
+        assert_eq!(circuit[0].from(), circuit[circuit.len() - 1].to());
+
+        // A square with a roof and a diagonal, only 0 and 3 have odd degrees
+        let house: Graph<i32, (), Undirected> =
+            graph(5, &[(0, 1), (1, 2), (2, 3), (3, 0), (2, 4), (3, 4), (0, 2)]);
+        let Err(NotEulerian::OddDegree(odd)) = house.eulerian_circuit() else {
+            panic!("house has no circuit");
+        };
+        assert_eq!(sorted(&odd), vec![0, 3]);
+        let path = house.eulerian_path().unwrap();
+        assert_walk(&house, &path);
+        assert!([0, 3].contains(path[0].from()));
+
+        let star: Graph<i32, (), Undirected> = graph(4, &[(0, 1), (0, 2), (0, 3)]);
+        let Err(NotEulerian::OddDegree(odd)) = star.eulerian_path() else {
+            panic!("star has four vertices of odd degree");
+        };
+        assert_eq!(sorted(&odd), vec![0, 1, 2, 3]);
+
+        // A loop adds 2 to the degree, so the triangle keeps its circuit
+        let looped: Graph<i32, (), Undirected> = graph(3, &[(0, 1), (1, 2), (2, 0), (0, 0)]);
+        let circuit = looped.eulerian_circuit().unwrap();
+        assert_walk(&looped, &circuit);
+        assert_eq!(circuit.len(), 4);
+        assert_eq!(circuit[0].from(), circuit[3].to());
+    }
+
+    #[test]
+    fn directed_test() {
+        let cycle: Graph<i32, (), Directed> = graph(3, &[(0, 1), (1, 2), (2, 0), (1, 1)]);
+        assert_walk(&cycle, &cycle.eulerian_circuit().unwrap());
+
+        let path: Graph<i32, (), Directed> = graph(4, &[(0, 1), (1, 2), (2, 0), (0, 3)]);
+        let walk = path.eulerian_path().unwrap();
+        assert_walk(&path, &walk);
+        assert_eq!((walk[0].from(), walk[3].to()), (&0, &3));
+        let Err(NotEulerian::Unbalanced(mut keys)) = path.eulerian_circuit() else {
+            panic!("0 has one more outgoing edge");
+        };
+        keys.sort_unstable();
+        assert_eq!(keys, vec![(&0, 1), (&3, -1)]);
+
+        let forks: Graph<i32, (), Directed> = graph(3, &[(0, 1), (0, 2)]);
+        let Err(NotEulerian::Unbalanced(mut keys)) = forks.eulerian_path() else {
+            panic!("two edges leave 0");
+        };
+        keys.sort_unstable();
+        assert_eq!(keys, vec![(&0, 2), (&1, -1), (&2, -1)]);
+    }
+
+    #[test]
+    fn disconnected_test() {
+        let triangles: Graph<i32, (), Undirected> =
+            graph(7, &[(0, 1), (1, 2), (2, 0), (3, 4), (4, 5), (5, 3)]);
+        let Err(NotEulerian::Disconnected(keys)) = triangles.eulerian_circuit() else {
+            panic!("triangles are not connected");
+        };
+        // The walk covers one of the triangles, the other one is reported
+        let keys = sorted(&keys);
+        assert!(keys == vec![0, 1, 2] || keys == vec![3, 4, 5]);
+
+        let error = NotEulerian::Unbalanced(vec![(&1, 1), (&2, -2)]);
+        assert_eq!(
+            error.to_string(),
+            "vertices with out-degree minus in-degree: 1 (+1), 2 (-2)"
+        );
+
+        let empty: Graph<i32, (), Directed> = graph(3, &[]);
+        assert_eq!(empty.eulerian_circuit(), Ok(vec![]));
+    }
+}
@@ -0,0 +1,706 @@
+use std::{collections::HashMap, hash::Hash};
+
+use super::bipartite::Matching;
+use crate::{graph::Graph, marker::Undirected};
+
+/// Marks a missing vertex, edge endpoint or blossom.
+const NONE: usize = usize::MAX;
+
+/// Labels of top-level blossoms in the alternating forest.
+const FREE: u8 = 0;
+const OUTER: u8 = 1;
+const INNER: u8 = 2;
+/// Marks outer blossoms visited while looking for the base of a new blossom.
+const VISITED: u8 = 4;
+
+/// State of Edmonds' blossom algorithm for maximum weight matching with
+/// Galil's O(V^3) primal-dual updates.
+/// See <https://en.wikipedia.org/wiki/Blossom_algorithm> and Z. Galil,
+/// "Efficient algorithms for finding maximum matching in graphs", 1986.
+///
+/// Vertices are numbered from 0 to n, nontrivial blossoms from n to 2n.
+/// Every edge k has two endpoints: 2k is its first vertex and 2k + 1 is its second one,
+/// so p ^ 1 is the opposite endpoint of the same edge.
+/// Weights are doubled, so all dual variables stay integers.
+struct Blossoms {
+    n: usize,
+    edges: Vec<(usize, usize, i128)>,
+    /// Vertex of every endpoint.
+    endpoint: Vec<usize>,
+    /// Remote endpoints of the edges of every vertex.
+    neighbour_endpoints: Vec<Vec<usize>>,
+    /// Remote endpoint of the matched edge of every vertex.
+    mate: Vec<usize>,
+    /// Label of every vertex and top-level blossom.
+    label: Vec<u8>,
+    /// Endpoint through which the vertex or blossom got its label.
+    label_end: Vec<usize>,
+    /// Top-level blossom of every vertex.
+    in_blossom: Vec<usize>,
+    blossom_parent: Vec<usize>,
+    /// Sub-blossoms of every blossom in the order of its cycle, starting at the base.
+    blossom_children: Vec<Vec<usize>>,
+    blossom_base: Vec<usize>,
+    /// Endpoints of the edges that connect the sub-blossoms, in the same order.
+    blossom_endpoints: Vec<Vec<usize>>,
+    /// Edge of the least slack to an outer blossom, from a free vertex or an outer blossom.
+    best_edge: Vec<usize>,
+    /// Edges of the least slack to every outer blossom, from an outer blossom.
+    blossom_best_edges: Vec<Option<Vec<usize>>>,
+    unused_blossoms: Vec<usize>,
+    /// Dual variables of vertices, then of blossoms.
+    dual: Vec<i128>,
+    /// Edges with zero slack, that can be used by the alternating forest.
+    allowed: Vec<bool>,
+    /// Outer vertices whose edges are not scanned yet.
+    queue: Vec<usize>,
+}
+
+impl Blossoms {
+    fn new(n: usize, edges: Vec<(usize, usize, i128)>) -> Self {
+        let edges: Vec<(usize, usize, i128)> = edges
+            .into_iter()
+            .map(|(v, w, weight)| (v, w, 2 * weight))
+            .collect();
+        let endpoint = edges.iter().flat_map(|&(v, w, _)| [v, w]).collect();
+        let mut neighbour_endpoints = vec![Vec::new(); n];
+        for (k, &(v, w, _)) in edges.iter().enumerate() {
+            neighbour_endpoints[v].push(2 * k + 1);
+            neighbour_endpoints[w].push(2 * k);
+        }
+        let largest = edges.iter().map(|e| e.2).max().unwrap_or(0).max(0);
+
+        Blossoms {
+            n,
+            endpoint,
+            neighbour_endpoints,
+            mate: vec![NONE; n],
+            label: vec![FREE; 2 * n],
+            label_end: vec![NONE; 2 * n],
+            in_blossom: (0..n).collect(),
+            blossom_parent: vec![NONE; 2 * n],
+            blossom_children: vec![Vec::new(); 2 * n],
+            blossom_base: (0..n).chain(std::iter::repeat_n(NONE, n)).collect(),
+            blossom_endpoints: vec![Vec::new(); 2 * n],
+            best_edge: vec![NONE; 2 * n],
+            blossom_best_edges: vec![None; 2 * n],
+            unused_blossoms: (n..2 * n).collect(),
+            dual: std::iter::repeat_n(largest, n)
+                .chain(std::iter::repeat_n(0, n))
+                .collect(),
+            allowed: vec![false; edges.len()],
+            queue: Vec::new(),
+            edges,
+        }
+    }
+
+    fn slack(&self, k: usize) -> i128 {
+        let (v, w, weight) = self.edges[k];
+        self.dual[v] + self.dual[w] - 2 * weight
+    }
+
+    /// Returns the vertices of the blossom.
+    fn leaves(&self, b: usize) -> Vec<usize> {
+        let mut leaves = Vec::new();
+        let mut stack = vec![b];
+        while let Some(b) = stack.pop() {
+            if b < self.n {
+                leaves.push(b);
+            } else {
+                stack.extend(self.blossom_children[b].iter().rev());
+            }
+        }
+        leaves
+    }
+
+    /// Labels the vertex w and its top-level blossom, reached through the endpoint p.
+    /// The mate of the base of an inner blossom becomes outer.
+    fn assign_label(&mut self, w: usize, label: u8, p: usize) {
+        let b = self.in_blossom[w];
+        self.label[w] = label;
+        self.label[b] = label;
+        self.label_end[w] = p;
+        self.label_end[b] = p;
+        self.best_edge[w] = NONE;
+        self.best_edge[b] = NONE;
+        if label == OUTER {
+            let leaves = self.leaves(b);
+            self.queue.extend(leaves);
+        } else {
+            let base = self.blossom_base[b];
+            let mate = self.mate[base];
+            self.assign_label(self.endpoint[mate], OUTER, mate ^ 1);
+        }
+    }
+
+    /// Traces back from the outer vertices v and w to the roots of their trees.
+    /// Returns the base of the new blossom if the paths meet,
+    /// or NONE if they reach different roots and form an augmenting path.
+    fn scan_blossom(&mut self, mut v: usize, mut w: usize) -> usize {
+        let mut path = Vec::new();
+        let mut base = NONE;
+        while v != NONE {
+            let b = self.in_blossom[v];
+            if self.label[b] & VISITED != 0 {
+                base = self.blossom_base[b];
+                break;
+            }
+            path.push(b);
+            self.label[b] = OUTER | VISITED;
+            if self.label_end[b] == NONE {
+                // The root of the tree
+                v = NONE;
+            } else {
+                let t = self.in_blossom[self.endpoint[self.label_end[b]]];
+                v = self.endpoint[self.label_end[t]];
+            }
+            if w != NONE {
+                std::mem::swap(&mut v, &mut w);
+            }
+        }
+        for b in path {
+            self.label[b] = OUTER;
+        }
+        base
+    }
+
+    /// Makes a new blossom with the base, closed by the edge k between outer vertices.
+    fn add_blossom(&mut self, base: usize, k: usize) {
+        let (mut v, mut w, _) = self.edges[k];
+        let top = self.in_blossom[base];
+        let (mut bv, mut bw) = (self.in_blossom[v], self.in_blossom[w]);
+        let b = self
+            .unused_blossoms
+            .pop()
+            .expect("there are n blossoms at most");
+        self.blossom_base[b] = base;
+        self.blossom_parent[b] = NONE;
+        self.blossom_parent[top] = b;
+
+        let (mut children, mut endpoints) = (Vec::new(), Vec::new());
+        while bv != top {
+            self.blossom_parent[bv] = b;
+            children.push(bv);
+            endpoints.push(self.label_end[bv]);
+            v = self.endpoint[self.label_end[bv]];
+            bv = self.in_blossom[v];
+        }
+        children.push(top);
+        children.reverse();
+        endpoints.reverse();
+        endpoints.push(2 * k);
+        while bw != top {
+            self.blossom_parent[bw] = b;
+            children.push(bw);
+            endpoints.push(self.label_end[bw] ^ 1);
+            w = self.endpoint[self.label_end[bw]];
+            bw = self.in_blossom[w];
+        }
+
+        self.label[b] = OUTER;
+        self.label_end[b] = self.label_end[top];
+        self.dual[b] = 0;
+        for v in self.leaves_of(&children) {
+            if self.label[self.in_blossom[v]] == INNER {
+                // Inner vertices become outer, so their edges must be scanned
+                self.queue.push(v);
+            }
+            self.in_blossom[v] = b;
+        }
+
+        // Keeps only the edges of the least slack to every other outer blossom
+        let mut best_to = vec![NONE; 2 * self.n];
+        for &child in &children {
+            let lists = match self.blossom_best_edges[child].take() {
+                Some(list) => vec![list],
+                None => self
+                    .leaves(child)
+                    .into_iter()
+                    .map(|v| self.neighbour_endpoints[v].iter().map(|p| p / 2).collect())
+                    .collect(),
+            };
+            for k in lists.into_iter().flatten() {
+                let (i, j, _) = self.edges[k];
+                let j = if self.in_blossom[j] == b { i } else { j };
+                let bj = self.in_blossom[j];
+                if bj != b
+                    && self.label[bj] == OUTER
+                    && (best_to[bj] == NONE || self.slack(k) < self.slack(best_to[bj]))
+                {
+                    best_to[bj] = k;
+                }
+            }
+            self.best_edge[child] = NONE;
+        }
+        let best: Vec<usize> = best_to.into_iter().filter(|&k| k != NONE).collect();
+        self.best_edge[b] = best
+            .iter()
+            .copied()
+            .min_by_key(|&k| self.slack(k))
+            .unwrap_or(NONE);
+        self.blossom_best_edges[b] = Some(best);
+        self.blossom_children[b] = children;
+        self.blossom_endpoints[b] = endpoints;
+    }
+
+    fn leaves_of(&self, blossoms: &[usize]) -> Vec<usize> {
+        blossoms.iter().flat_map(|&b| self.leaves(b)).collect()
+    }
+
+    /// Splits the top-level blossom into its sub-blossoms.
+    /// In the middle of a stage the labels of an inner blossom pass to the sub-blossoms
+    /// on the even path from its entry to its base. At the end of a stage
+    /// sub-blossoms with zero dual variables are expanded as well.
+    fn expand_blossom(&mut self, b: usize, end_of_stage: bool) {
+        let children = self.blossom_children[b].clone();
+        for &s in &children {
+            self.blossom_parent[s] = NONE;
+            if s < self.n {
+                self.in_blossom[s] = s;
+            } else if end_of_stage && self.dual[s] == 0 {
+                self.expand_blossom(s, end_of_stage);
+            } else {
+                for v in self.leaves(s) {
+                    self.in_blossom[v] = s;
+                }
+            }
+        }
+
+        if !end_of_stage && self.label[b] == INNER {
+            let endpoints = self.blossom_endpoints[b].clone();
+            let len = children.len() as isize;
+            let child = |j: isize| children[j.rem_euclid(len) as usize];
+            let endpoint_at = |j: isize| endpoints[j.rem_euclid(len) as usize];
+
+            let entry = self.in_blossom[self.endpoint[self.label_end[b] ^ 1]];
+            let mut j = children
+                .iter()
+                .position(|&c| c == entry)
+                .expect("entry is a sub-blossom") as isize;
+            // Goes to the base along the even side of the cycle
+            let (step, trick): (isize, usize) = if j & 1 == 1 {
+                j -= len;
+                (1, 0)
+            } else {
+                (-1, 1)
+            };
+            let mut p = self.label_end[b];
+            while j != 0 {
+                self.label[self.endpoint[p ^ 1]] = FREE;
+                self.label[self.endpoint[endpoint_at(j - trick as isize) ^ trick ^ 1]] = FREE;
+                self.assign_label(self.endpoint[p ^ 1], INNER, p);
+                self.allowed[endpoint_at(j - trick as isize) / 2] = true;
+                j += step;
+                p = endpoint_at(j - trick as isize) ^ trick;
+                self.allowed[p / 2] = true;
+                j += step;
+            }
+
+            // The base becomes inner without passing the label to its mate
+            let bv = child(j);
+            let v = self.endpoint[p ^ 1];
+            self.label[v] = INNER;
+            self.label[bv] = INNER;
+            self.label_end[v] = p;
+            self.label_end[bv] = p;
+            self.best_edge[bv] = NONE;
+            j += step;
+
+            // Sub-blossoms on the odd side that are reachable from outside stay inner
+            while child(j) != entry {
+                let bv = child(j);
+                j += step;
+                if self.label[bv] == OUTER {
+                    continue;
+                }
+                let labelled = self.leaves(bv).into_iter().find(|&v| self.label[v] != FREE);
+                if let Some(v) = labelled {
+                    self.label[v] = FREE;
+                    let mate = self.mate[self.blossom_base[bv]];
+                    self.label[self.endpoint[mate]] = FREE;
+                    self.assign_label(v, INNER, self.label_end[v]);
+                }
+            }
+        }
+
+        self.label[b] = FREE;
+        self.label_end[b] = NONE;
+        self.blossom_children[b] = Vec::new();
+        self.blossom_endpoints[b] = Vec::new();
+        self.blossom_base[b] = NONE;
+        self.blossom_best_edges[b] = None;
+        self.best_edge[b] = NONE;
+        self.unused_blossoms.push(b);
+    }
+
+    /// Swaps matched and unmatched edges on the even path from the vertex v
+    /// to the base of the blossom b, so v becomes the new base.
+    fn augment_blossom(&mut self, b: usize, v: usize) {
+        let mut t = v;
+        while self.blossom_parent[t] != b {
+            t = self.blossom_parent[t];
+        }
+        if t >= self.n {
+            self.augment_blossom(t, v);
+        }
+
+        let children = self.blossom_children[b].clone();
+        let endpoints = self.blossom_endpoints[b].clone();
+        let len = children.len() as isize;
+        let child = |j: isize| children[j.rem_euclid(len) as usize];
+        let endpoint_at = |j: isize| endpoints[j.rem_euclid(len) as usize];
+
+        let i = children
+            .iter()
+            .position(|&c| c == t)
+            .expect("t is a sub-blossom");
+        let mut j = i as isize;
+        let (step, trick): (isize, usize) = if i & 1 == 1 {
+            j -= len;
+            (1, 0)
+        } else {
+            (-1, 1)
+        };
+        while j != 0 {
+            j += step;
+            let t = child(j);
+            let p = endpoint_at(j - trick as isize) ^ trick;
+            if t >= self.n {
+                self.augment_blossom(t, self.endpoint[p]);
+            }
+            j += step;
+            let t = child(j);
+            if t >= self.n {
+                self.augment_blossom(t, self.endpoint[p ^ 1]);
+            }
+            self.mate[self.endpoint[p]] = p ^ 1;
+            self.mate[self.endpoint[p ^ 1]] = p;
+        }
+
+        self.blossom_children[b].rotate_left(i);
+        self.blossom_endpoints[b].rotate_left(i);
+        self.blossom_base[b] = self.blossom_base[self.blossom_children[b][0]];
+    }
+
+    /// Swaps matched and unmatched edges on the augmenting path through the edge k
+    /// between the trees of two different free vertices.
+    fn augment_matching(&mut self, k: usize) {
+        let (v, w, _) = self.edges[k];
+        for (mut s, mut p) in [(v, 2 * k + 1), (w, 2 * k)] {
+            loop {
+                let bs = self.in_blossom[s];
+                if bs >= self.n {
+                    self.augment_blossom(bs, s);
+                }
+                self.mate[s] = p;
+                if self.label_end[bs] == NONE {
+                    // The root of the tree
+                    break;
+                }
+                let t = self.endpoint[self.label_end[bs]];
+                let bt = self.in_blossom[t];
+                s = self.endpoint[self.label_end[bt]];
+                let j = self.endpoint[self.label_end[bt] ^ 1];
+                if bt >= self.n {
+                    self.augment_blossom(bt, j);
+                }
+                self.mate[j] = self.label_end[bt];
+                p = self.label_end[bt] ^ 1;
+            }
+        }
+    }
+
+    /// Grows the alternating forest from the queued outer vertices until an augmenting
+    /// path is found. Returns false if the queue is exhausted.
+    fn scan(&mut self) -> bool {
+        while let Some(v) = self.queue.pop() {
+            for i in 0..self.neighbour_endpoints[v].len() {
+                let p = self.neighbour_endpoints[v][i];
+                let (k, w) = (p / 2, self.endpoint[p]);
+                if self.in_blossom[v] == self.in_blossom[w] {
+                    continue;
+                }
+                let slack = self.slack(k);
+                if slack <= 0 {
+                    self.allowed[k] = true;
+                }
+
+                let bw = self.in_blossom[w];
+                if self.allowed[k] {
+                    if self.label[bw] == FREE {
+                        self.assign_label(w, INNER, p ^ 1);
+                    } else if self.label[bw] == OUTER {
+                        let base = self.scan_blossom(v, w);
+                        if base != NONE {
+                            self.add_blossom(base, k);
+                        } else {
+                            self.augment_matching(k);
+                            return true;
+                        }
+                    } else if self.label[w] == FREE {
+                        // w is inside an inner blossom, but it isn't reached yet
+                        self.label[w] = INNER;
+                        self.label_end[w] = p ^ 1;
+                    }
+                } else if self.label[bw] == OUTER {
+                    let b = self.in_blossom[v];
+                    if self.best_edge[b] == NONE || slack < self.slack(self.best_edge[b]) {
+                        self.best_edge[b] = k;
+                    }
+                } else if self.label[w] == FREE
+                    && (self.best_edge[w] == NONE || slack < self.slack(self.best_edge[w]))
+                {
+                    self.best_edge[w] = k;
+                }
+            }
+        }
+        false
+    }
+
+    /// Changes the dual variables by the largest amount that keeps them feasible.
+    /// Returns false if the matching is optimal.
+    fn update_duals(&mut self) -> bool {
+        enum Change {
+            Optimal,
+            FreeEdge(usize),
+            OuterEdge(usize),
+            Expand(usize),
+        }
+        let n = self.n;
+
+        // A vertex dual reaches zero
+        let mut delta = *self.dual[..n].iter().min().unwrap_or(&0);
+        let mut change = Change::Optimal;
+        // An edge from a free vertex to an outer blossom gets zero slack
+        for v in 0..n {
+            if self.label[self.in_blossom[v]] == FREE && self.best_edge[v] != NONE {
+                let slack = self.slack(self.best_edge[v]);
+                if slack < delta {
+                    delta = slack;
+                    change = Change::FreeEdge(self.best_edge[v]);
+                }
+            }
+        }
+        // An edge between outer blossoms gets zero slack
+        for b in 0..2 * n {
+            if self.blossom_parent[b] == NONE && self.label[b] == OUTER && self.best_edge[b] != NONE
+            {
+                let slack = self.slack(self.best_edge[b]) / 2;
+                if slack < delta {
+                    delta = slack;
+                    change = Change::OuterEdge(self.best_edge[b]);
+                }
+            }
+        }
+        // The dual of an inner blossom reaches zero
+        for b in n..2 * n {
+            if self.blossom_base[b] != NONE
+                && self.blossom_parent[b] == NONE
+                && self.label[b] == INNER
+                && self.dual[b] < delta
+            {
+                delta = self.dual[b];
+                change = Change::Expand(b);
+            }
+        }
+
+        for v in 0..n {
+            match self.label[self.in_blossom[v]] {
+                OUTER => self.dual[v] -= delta,
+                INNER => self.dual[v] += delta,
+                _ => {}
+            }
+        }
+        for b in n..2 * n {
+            if self.blossom_base[b] != NONE && self.blossom_parent[b] == NONE {
+                match self.label[b] {
+                    OUTER => self.dual[b] += delta,
+                    INNER => self.dual[b] -= delta,
+                    _ => {}
+                }
+            }
+        }
+
+        match change {
+            Change::Optimal => return false,
+            Change::FreeEdge(k) => {
+                self.allowed[k] = true;
+                let (v, w, _) = self.edges[k];
+                let outer = if self.label[self.in_blossom[v]] == FREE {
+                    w
+                } else {
+                    v
+                };
+                self.queue.push(outer);
+            }
+            Change::OuterEdge(k) => {
+                self.allowed[k] = true;
+                self.queue.push(self.edges[k].0);
+            }
+            Change::Expand(b) => self.expand_blossom(b, false),
+        }
+        true
+    }
+
+    /// Runs stages until no augmenting path is left.
+    /// Returns the mate of every vertex, or NONE for unmatched ones.
+    fn solve(mut self) -> Vec<usize> {
+        let n = self.n;
+        for _ in 0..n {
+            self.label.fill(FREE);
+            self.best_edge.fill(NONE);
+            self.blossom_best_edges[n..].fill(None);
+            self.allowed.fill(false);
+            self.queue.clear();
+            for v in 0..n {
+                if self.mate[v] == NONE && self.label[self.in_blossom[v]] == FREE {
+                    self.assign_label(v, OUTER, NONE);
+                }
+            }
+
+            let mut augmented = false;
+            loop {
+                if self.scan() {
+                    augmented = true;
+                    break;
+                }
+                if !self.update_duals() {
+                    break;
+                }
+            }
+            if !augmented {
+                break;
+            }
+
+            for b in n..2 * n {
+                if self.blossom_parent[b] == NONE
+                    && self.blossom_base[b] != NONE
+                    && self.label[b] == OUTER
+                    && self.dual[b] == 0
+                {
+                    self.expand_blossom(b, true);
+                }
+            }
+        }
+
+        self.mate
+            .iter()
+            .map(|&p| if p == NONE { NONE } else { self.endpoint[p] })
+            .collect()
+    }
+}
+
+impl<Key, Value> Graph<Key, Value, Undirected>
+where
+    Key: Hash + Eq + Clone,
+{
+    /// Runs the blossom algorithm on the edges with the weights.
+    fn blossom_matching(&self, weight: impl Fn(i64) -> i128) -> Matching<'_, Key> {
+        let keys: Vec<&Key> = self.vertecies().map(|v| v.key()).collect();
+        let indices: HashMap<&Key, usize> = keys.iter().enumerate().map(|(i, k)| (*k, i)).collect();
+        let edges = self
+            .edges()
+            .map(|e| (indices[e.from()], indices[e.to()], weight(e.weight)))
+            .collect();
+
+        let mate = Blossoms::new(keys.len(), edges).solve();
+        (0..keys.len())
+            .filter(|&v| mate[v] != NONE && v < mate[v])
+            .map(|v| (keys[v], keys[mate[v]]))
+            .collect()
+    }
+
+    /// Finds a matching with the largest number of edges
+    /// with Edmonds' blossom algorithm in O(V^3).
+    /// See <https://en.wikipedia.org/wiki/Blossom_algorithm>.
+    /// Use Graph::maximum_bipartite_matching for bipartite graphs, it is faster.
+    pub fn maximum_matching(&self) -> Matching<'_, Key> {
+        self.blossom_matching(|_| 1)
+    }
+
+    /// Finds a matching with the largest total weight of its edges
+    /// with Edmonds' blossom algorithm in O(V^3).
+    /// Edges with negative weights are never matched,
+    /// so the matching is not necessarily of the largest size.
+    /// Returns the matched pairs and their total weight.
+    pub fn maximum_weight_matching(&self) -> (Matching<'_, Key>, i64) {
+        let pairs = self.blossom_matching(i128::from);
+        let total = pairs
+            .iter()
+            .map(|(from, to)| {
+                self.get_vertex(from)
+                    .and_then(|v| v.get_edge(to))
+                    .expect("matched vertices are adjacent")
+                    .weight
+            })
+            .sum();
+        (pairs, total)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::{
+        generators::classic,
+        graph::{
+            algorithms::testing::{graph, sorted_pairs, weighted},
+            Graph,
+        },
+        marker::Undirected,
+    };
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn maximum_matching_test() {
+        // Two triangles joined by an edge: the augmenting path 2-0-1-3-4-5
+        // goes through both odd cycles
+        let blossoms = graph(6, &[(0, 1), (1, 2), (2, 0), (1, 3), (3, 4), (4, 5), (5, 3)]);
+        let pairs = blossoms.maximum_matching();
+        assert_eq!(pairs.len(), 3);
+        let mut matched: Vec<i32> = sorted_pairs(&pairs)
+            .into_iter()
+            .flat_map(|(a, b)| [a, b])
+            .collect();
+        matched.sort_unstable();
+        assert_eq!(matched, vec![0, 1, 2, 3, 4, 5]);
+
+        let petersen: Graph<usize, (), Undirected> = classic::petersen(|_| (), |_, _| 0);
+        assert_eq!(petersen.maximum_matching().len(), 5);
+
+        let odd_cycle: Graph<usize, (), Undirected> = classic::cycle(7, |_| (), |_, _| 0).unwrap();
+        assert_eq!(odd_cycle.maximum_matching().len(), 3);
+
+        assert!(graph(3, &[]).maximum_matching().is_empty());
+    }
+
+    #[test]
+    fn maximum_weight_matching_test() {
+        // The heavy middle edge is worth more than both outer ones
+        let path = weighted(4, &[(0, 1, 5), (1, 2, 11), (2, 3, 5)]);
+        let (pairs, total) = path.maximum_weight_matching();
+        assert_eq!((sorted_pairs(&pairs), total), (vec![(1, 2)], 11));
+
+        let path = weighted(4, &[(0, 1, 6), (1, 2, 11), (2, 3, 6)]);
+        let (pairs, total) = path.maximum_weight_matching();
+        assert_eq!((sorted_pairs(&pairs), total), (vec![(0, 1), (2, 3)], 12));
+
+        // The heaviest edge of the triangle is not matched
+        let blossom = weighted(
+            5,
+            &[
+                (0, 1, 8),
+                (1, 2, 9),
+                (2, 0, 10),
+                (2, 3, 7),
+                (0, 4, 3),
+                (3, 4, -2),
+            ],
+        );
+        let (pairs, total) = blossom.maximum_weight_matching();
+        assert_eq!((sorted_pairs(&pairs), total), (vec![(0, 1), (2, 3)], 15));
+
+        let negative = weighted(2, &[(0, 1, -1)]);
+        assert_eq!(negative.maximum_weight_matching(), (vec![], 0));
+    }
+}
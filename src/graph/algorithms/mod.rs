@@ -0,0 +1,56 @@
+//! Graph algorithms, implemented as methods of Graph.
+
+mod biconnected;
+mod bipartite;
+mod components;
+mod euler;
+mod matching;
+mod mst;
+mod shortest_path;
+mod toposort;
+
+pub use bipartite::Bipartiteness;
+pub use euler::NotEulerian;
+
+/// Helpers shared by the tests of the algorithms.
+#[cfg(test)]
+mod testing {
+
+    use crate::{graph::Graph, marker::GraphType};
+
+    /// Builds a graph of the vertices 0..n and the unweighted edges.
+    pub(super) fn graph<Type: GraphType>(n: i32, edges: &[(i32, i32)]) -> Graph<i32, (), Type> {
+        let edges: Vec<(i32, i32, i64)> = edges.iter().map(|&(from, to)| (from, to, 0)).collect();
+        weighted(n, &edges)
+    }
+
+    /// Builds a graph of the vertices 0..n and the edges (from, to, weight).
+    pub(super) fn weighted<Type: GraphType>(
+        n: i32,
+        edges: &[(i32, i32, i64)],
+    ) -> Graph<i32, (), Type> {
+        let mut graph = Graph::new();
+        for key in 0..n {
+            graph.insert(key, ());
+        }
+        for &(from, to, weight) in edges {
+            graph.link(from, to, weight).unwrap();
+        }
+        graph
+    }
+
+    /// Copies the keys and sorts them.
+    pub(super) fn sorted(keys: &[&i32]) -> Vec<i32> {
+        let mut keys: Vec<i32> = keys.iter().map(|k| **k).collect();
+        keys.sort_unstable();
+        keys
+    }
+
+    /// Copies the pairs of undirected edges, so that they start at the smaller key, and sorts them.
+    pub(super) fn sorted_pairs(pairs: &[(&i32, &i32)]) -> Vec<(i32, i32)> {
+        let mut pairs: Vec<(i32, i32)> =
+            pairs.iter().map(|&(&a, &b)| (a.min(b), a.max(b))).collect();
+        pairs.sort_unstable();
+        pairs
+    }
+}
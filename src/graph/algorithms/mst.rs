@@ -0,0 +1,98 @@
+use std::{collections::HashMap, hash::Hash};
+
+use crate::{edge::Edge, graph::Graph, marker::GraphType};
+
+/// Disjoint set union over vertex indices.
+struct DisjointSet {
+    parents: Vec<usize>,
+}
+
+impl DisjointSet {
+    fn new(n: usize) -> Self {
+        DisjointSet {
+            parents: (0..n).collect(),
+        }
+    }
+
+    fn find(&mut self, mut i: usize) -> usize {
+        while self.parents[i] != i {
+            self.parents[i] = self.parents[self.parents[i]];
+            i = self.parents[i];
+        }
+        i
+    }
+
+    /// Joins the sets of a and b. Returns false if they are already joined.
+    fn union(&mut self, a: usize, b: usize) -> bool {
+        let (a, b) = (self.find(a), self.find(b));
+        self.parents[a] = b;
+        a != b
+    }
+}
+
+impl<Key, Value, Type> Graph<Key, Value, Type>
+where
+    Key: Hash + Eq + Clone,
+    Type: GraphType,
+{
+    /// Finds the minimum spanning forest with Kruskal's algorithm:
+    /// a minimum spanning tree of every connected component.
+    /// See <https://en.wikipedia.org/wiki/Kruskal%27s_algorithm>.
+    /// Directions of edges are ignored.
+    /// Returns the edges of the forest and their total weight.
+    pub fn minimum_spanning_forest(&self) -> (Vec<&Edge<Key>>, i64) {
+        let indices: HashMap<&Key, usize> = self
+            .vertecies()
+            .enumerate()
+            .map(|(i, v)| (v.key(), i))
+            .collect();
+
+        let mut edges: Vec<&Edge<Key>> = self.edges().collect();
+        edges.sort_by_key(|e| e.weight);
+
+        let mut sets = DisjointSet::new(self.len());
+        let mut forest = Vec::with_capacity(self.len().saturating_sub(1));
+        let mut total = 0;
+        for edge in edges {
+            if sets.union(indices[edge.from()], indices[edge.to()]) {
+                total += edge.weight;
+                forest.push(edge);
+            }
+        }
+
+        (forest, total)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::{
+        graph::algorithms::testing::weighted,
+        marker::{Directed, Undirected},
+    };
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn minimum_spanning_forest_test() {
+        let graph = weighted::<Undirected>(
+            6,
+            &[
+                (0, 1, 4),
+                (0, 2, 1),
+                (1, 2, 2),
+                (1, 3, 5),
+                (2, 3, 8),
+                (4, 5, 3),
+            ],
+        );
+
+        let (forest, total) = graph.minimum_spanning_forest();
+        assert_eq!(total, 1 + 2 + 5 + 3);
+        assert_eq!(forest.len(), 4);
+
+        let directed = weighted::<Directed>(2, &[(0, 1, 7), (1, 0, 2)]);
+        let (forest, total) = directed.minimum_spanning_forest();
+        assert_eq!((forest.len(), total), (1, 2));
+    }
+}
@@ -0,0 +1,159 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, VecDeque},
+    hash::Hash,
+};
+
+use crate::{graph::Graph, marker::GraphType};
+
+/// Follows the predecessors back from the destination and returns the path in order.
+fn unwind<'a, Key>(previous: &HashMap<&'a Key, &'a Key>, to: &'a Key) -> Vec<&'a Key>
+where
+    Key: Hash + Eq,
+{
+    let mut path = vec![to];
+    while let Some(key) = previous.get(path[path.len() - 1]) {
+        path.push(key);
+    }
+    path.reverse();
+    path
+}
+
+impl<Key, Value, Type> Graph<Key, Value, Type>
+where
+    Key: Hash + Eq + Clone,
+    Type: GraphType,
+{
+    /// Finds the path with the smallest total weight with Dijkstra's algorithm.
+    /// See <https://en.wikipedia.org/wiki/Dijkstra%27s_algorithm>.
+    /// Returns the vertices of the path, including both ends, and its weight.
+    /// Returns None if the destination is not reachable.
+    /// Returns Err if one of the vertices doesn't exist
+    /// or a reachable edge has a negative weight.
+    pub fn shortest_path<'a>(
+        &'a self,
+        from: &Key,
+        to: &Key,
+    ) -> Result<Option<(Vec<&'a Key>, i64)>, &'static str> {
+        let (from, to) = match (self.get_vertex(from), self.get_vertex(to)) {
+            (Some(from), Some(to)) => (from.key(), to.key()),
+            _ => return Err("vertex is not in the graph"),
+        };
+
+        let mut distances: HashMap<&Key, i64> = HashMap::from([(from, 0)]);
+        let mut previous: HashMap<&Key, &Key> = HashMap::new();
+        // Keys are not ordered, so the heap stores positions in this list
+        let mut queued = vec![from];
+        let mut heap = BinaryHeap::from([Reverse((0, 0))]);
+
+        while let Some(Reverse((distance, i))) = heap.pop() {
+            let key = queued[i];
+            if distance > distances[key] {
+                continue;
+            }
+            if key == to {
+                return Ok(Some((unwind(&previous, to), distance)));
+            }
+
+            let vertex = self.get_vertex(key).expect("edge leads to a vertex");
+            for edge in vertex.adjancency_list() {
+                if edge.weight < 0 {
+                    return Err("graph contains an edge with negative weight");
+                }
+
+                let candidate = distance + edge.weight;
+                if distances.get(edge.to()).is_none_or(|d| candidate < *d) {
+                    distances.insert(edge.to(), candidate);
+                    previous.insert(edge.to(), key);
+                    queued.push(edge.to());
+                    heap.push(Reverse((candidate, queued.len() - 1)));
+                }
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Finds the path with the fewest edges with breadth-first search, ignoring weights.
+    /// Returns the vertices of the path, including both ends.
+    /// Returns None if the destination is not reachable.
+    /// Returns Err if one of the vertices doesn't exist.
+    pub fn fewest_edges_path<'a>(
+        &'a self,
+        from: &Key,
+        to: &Key,
+    ) -> Result<Option<Vec<&'a Key>>, &'static str> {
+        let (from, to) = match (self.get_vertex(from), self.get_vertex(to)) {
+            (Some(from), Some(to)) => (from.key(), to.key()),
+            _ => return Err("vertex is not in the graph"),
+        };
+
+        let mut previous: HashMap<&Key, &Key> = HashMap::new();
+        let mut queue = VecDeque::from([from]);
+
+        while let Some(key) = queue.pop_front() {
+            if key == to {
+                return Ok(Some(unwind(&previous, to)));
+            }
+
+            let vertex = self.get_vertex(key).expect("edge leads to a vertex");
+            for edge in vertex.adjancency_list() {
+                if edge.to() != from && !previous.contains_key(edge.to()) {
+                    previous.insert(edge.to(), key);
+                    queue.push_back(edge.to());
+                }
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::{
+        graph::{
+            algorithms::testing::{graph, weighted},
+            Graph,
+        },
+        marker::{Directed, Undirected},
+    };
+    use pretty_assertions::assert_eq;
+
+    fn sample() -> Graph<i32, (), Directed> {
+        weighted(5, &[(0, 1, 1), (1, 2, 1), (2, 3, 1), (0, 3, 5)])
+    }
+
+    #[test]
+    fn shortest_path_test() {
+        let graph = sample();
+
+        assert_eq!(
+            graph.shortest_path(&0, &3),
+            Ok(Some((vec![&0, &1, &2, &3], 3)))
+        );
+        assert_eq!(graph.shortest_path(&0, &0), Ok(Some((vec![&0], 0))));
+        assert_eq!(graph.shortest_path(&3, &0), Ok(None));
+        assert_eq!(graph.shortest_path(&0, &4), Ok(None));
+        assert!(graph.shortest_path(&0, &9).is_err());
+
+        let mut negative = sample();
+        negative.insert_edge(1, 4, -1).unwrap();
+        assert!(negative.shortest_path(&0, &3).is_err());
+    }
+
+    #[test]
+    fn fewest_edges_path_test() {
+        let directed = sample();
+
+        assert_eq!(directed.fewest_edges_path(&0, &3), Ok(Some(vec![&0, &3])));
+        assert_eq!(directed.fewest_edges_path(&3, &0), Ok(None));
+
+        let undirected = graph::<Undirected>(3, &[(0, 1), (1, 2)]);
+        assert_eq!(
+            undirected.fewest_edges_path(&2, &0),
+            Ok(Some(vec![&2, &1, &0]))
+        );
+    }
+}
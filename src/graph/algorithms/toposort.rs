@@ -0,0 +1,70 @@
+use std::{collections::HashMap, hash::Hash};
+
+use crate::{graph::Graph, marker::Directed};
+
+impl<Key, Value> Graph<Key, Value, Directed>
+where
+    Key: Hash + Eq + Clone,
+{
+    /// Orders vertices so that every edge leads from an earlier vertex to a later one,
+    /// using Kahn's algorithm.
+    /// See <https://en.wikipedia.org/wiki/Topological_sorting>.
+    /// Returns None if the graph contains a cycle.
+    pub fn topological_sort(&self) -> Option<Vec<&Key>> {
+        let mut in_degree: HashMap<&Key, usize> = self.vertecies().map(|v| (v.key(), 0)).collect();
+        for edge in self.edges() {
+            *in_degree
+                .get_mut(edge.to())
+                .expect("edge leads to a vertex") += 1;
+        }
+
+        let mut ready: Vec<&Key> = in_degree
+            .iter()
+            .filter(|(_, degree)| **degree == 0)
+            .map(|(key, _)| *key)
+            .collect();
+        let mut order = Vec::with_capacity(self.len());
+
+        while let Some(key) = ready.pop() {
+            order.push(key);
+            let vertex = self.get_vertex(key).expect("key is in the graph");
+            for edge in vertex.adjancency_list() {
+                let degree = in_degree
+                    .get_mut(edge.to())
+                    .expect("edge leads to a vertex");
+                *degree -= 1;
+                if *degree == 0 {
+                    ready.push(edge.to());
+                }
+            }
+        }
+
+        if order.len() == self.len() {
+            Some(order)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::{graph::algorithms::testing::graph, marker::Directed};
+
+    #[test]
+    fn topological_sort_test() {
+        let edges = [(0, 1), (0, 2), (1, 3), (2, 3), (3, 4)];
+        let mut graph = graph::<Directed>(5, &edges);
+
+        let order = graph.topological_sort().expect("graph is acyclic");
+        assert_eq!(order.len(), 5);
+        let position = |key: i32| order.iter().position(|k| **k == key).unwrap();
+        for (from, to) in edges {
+            assert!(position(from) < position(to));
+        }
+
+        graph.insert_edge(4, 0, 0).unwrap();
+        assert_eq!(graph.topological_sort(), None);
+    }
+}
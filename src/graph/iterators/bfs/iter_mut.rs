@@ -24,7 +24,12 @@ where
 {
     type Item = &'a mut Vertex<Key, Value>;
     fn next(&mut self) -> Option<Self::Item> {
-        let key = self.queue.pop_front().unwrap();
+        let key = loop {
+            let key = self.queue.pop_front()?;
+            if !self.visited.contains(key) {
+                break key;
+            }
+        };
 
         unsafe {
             let vertex = if let Some(vertex) = self.graph.get_vertex_mut(key) {
@@ -27,7 +27,7 @@ where
     type Item = &'a Vertex<Key, Value>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let key = self.stack.pop().unwrap();
+        let key = self.stack.pop()?;
 
         let vertex = self.graph.get_vertex(key)?;
 
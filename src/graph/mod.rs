@@ -1,8 +1,14 @@
 pub mod iterators;
 
+mod algorithms;
 mod directed;
 mod undirected;
 
+#[cfg(feature = "serde")]
+mod serialization;
+
+pub use algorithms::{Bipartiteness, NotEulerian};
+
 use iterators::{
     bfs::{iter::BfsIterator, iter_mut::BfsIteratorMut},
     dfs::{iter::DfsIterator, iter_mut::DfsIteratorMut},
@@ -16,7 +22,7 @@ use crate::{
 };
 
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{HashMap, HashSet, TryReserveError},
     fmt::Display,
     hash::Hash,
     marker::PhantomData,
@@ -59,6 +65,14 @@ where
         }
     }
 
+    /// Same as Graph::with_capacity, but returns Err instead of aborting
+    /// if the memory can't be allocated.
+    pub(crate) fn try_with_capacity(capacity: usize) -> Result<Self, TryReserveError> {
+        let mut graph = Self::new();
+        graph.vertices.try_reserve(capacity)?;
+        Ok(graph)
+    }
+
     /// Insert a new vertex into the graph.
     /// Returns the old value if the key already exists.
     /// Returns None if the key does not exist.
@@ -90,6 +104,26 @@ where
         self.vertices.clear();
     }
 
+    /// Consumes the graph and returns the same graph with every value
+    /// replaced by the result of f called on the vertex's key and value.
+    pub fn map_values<NewValue>(
+        self,
+        mut f: impl FnMut(&Key, Value) -> NewValue,
+    ) -> Graph<Key, NewValue, Type> {
+        Graph {
+            vertices: self
+                .vertices
+                .into_iter()
+                .map(|(key, vertex)| {
+                    let (vertex_key, value, adj) = vertex.into_parts();
+                    let value = f(&vertex_key, value);
+                    (key, Vertex::from_parts(vertex_key, value, adj))
+                })
+                .collect(),
+            _type: PhantomData,
+        }
+    }
+
     /// Returns the number of vertices in the graph.
     pub fn len(&self) -> usize {
         self.vertices.len()
@@ -150,11 +184,82 @@ where
         self.get_vertex(from)?.get_edge(to)
     }
 
+    /// Inserts a new edge respecting the type of the graph: in one direction
+    /// for directed graphs and in both directions for undirected ones.
+    /// Used by the code that is generic over the graph type.
+    /// Returns Err if one of the vertices does not exist.
+    pub(crate) fn link(
+        &mut self,
+        from: Key,
+        to: Key,
+        weight: i64,
+    ) -> Result<Option<Edge<Key>>, &'static str> {
+        if !self.contains(&from) || !self.contains(&to) {
+            return Err("failure");
+        }
+
+        if !Type::is_directed() && from != to {
+            self.get_vertex_mut(&to)
+                .expect("destination vertex must exist")
+                .insert_edge(from.clone(), weight);
+        }
+
+        Ok(self
+            .get_vertex_mut(&from)
+            .expect("source vertex must exist")
+            .insert_edge(to, weight))
+    }
+
     /// Returns an iterator over the vertices in the graph.
     pub fn vertecies(&self) -> impl Iterator<Item = &Vertex<Key, Value>> {
         self.vertices.iter().map(|v| v.1)
     }
 
+    /// Returns an iterator over the edges in the graph.
+    /// Every edge of an undirected graph is returned only once,
+    /// though it is stored in both adjacency lists.
+    pub fn edges<'a>(&'a self) -> impl Iterator<Item = &'a Edge<Key>> + 'a {
+        let mut seen: HashSet<(&'a Key, &'a Key)> = HashSet::new();
+        self.vertecies()
+            .flat_map(|v| v.adjancency_list().iter())
+            .filter(move |edge| {
+                if Type::is_directed() {
+                    return true;
+                }
+                if seen.contains(&(edge.to(), edge.from())) {
+                    return false;
+                }
+                seen.insert((edge.from(), edge.to()));
+                true
+            })
+    }
+
+    /// Returns the number of edges in the graph.
+    /// Edges of an undirected graph are counted once.
+    pub fn edges_count(&self) -> usize {
+        self.edges().count()
+    }
+
+    /// Returns the degree of the vertex with the given key, or None if there is no such vertex.
+    /// For a directed graph it is the out-degree.
+    /// A loop of an undirected graph is counted twice, once for each of its ends.
+    pub fn degree(&self, key: &Key) -> Option<usize> {
+        let vertex = self.get_vertex(key)?;
+        Some(
+            vertex
+                .adjancency_list()
+                .iter()
+                .map(|edge| {
+                    if !Type::is_directed() && edge.to() == key {
+                        2
+                    } else {
+                        1
+                    }
+                })
+                .sum(),
+        )
+    }
+
     /// Same as Graph::vertecies, but returns a mutable reference.
     pub fn vertecies_mut(&mut self) -> impl Iterator<Item = &mut Vertex<Key, Value>> {
         self.vertices.iter_mut().map(|v| v.1)
@@ -205,6 +310,32 @@ where
     }
 }
 
+impl<Key, Value, Type> Graph<Key, Value, Type>
+where
+    Key: Hash + Eq + Clone + Ord,
+    Type: GraphType,
+{
+    /// Returns the vertices sorted by key, so serializers produce the same output
+    /// for the same graph regardless of the order of the hash map.
+    pub(crate) fn sorted_vertices(&self) -> Vec<&Vertex<Key, Value>> {
+        let mut vertices: Vec<&Vertex<Key, Value>> = self.vertecies().collect();
+        vertices.sort_unstable_by(|a, b| a.key().cmp(b.key()));
+        vertices
+    }
+
+    /// Same as Graph::edges, but sorted by their ends like Graph::sorted_vertices.
+    /// An undirected edge always starts at its smaller end.
+    pub(crate) fn sorted_edges(&self) -> Vec<&Edge<Key>> {
+        let mut edges: Vec<&Edge<Key>> = self
+            .vertecies()
+            .flat_map(|v| v.adjancency_list().iter())
+            .filter(|edge| Type::is_directed() || edge.from() <= edge.to())
+            .collect();
+        edges.sort_unstable_by(|a, b| (a.from(), a.to()).cmp(&(b.from(), b.to())));
+        edges
+    }
+}
+
 impl<Key, Value, Type> Default for Graph<Key, Value, Type>
 where
     Key: Hash + Eq + Clone,
@@ -215,10 +346,11 @@ where
     }
 }
 
-impl<Key, Value> TgfConvertible for Graph<Key, Value, Directed>
+impl<Key, Value, Type> TgfConvertible for Graph<Key, Value, Type>
 where
-    Key: Hash + Eq + Clone + Display + FromStr,
+    Key: Hash + Eq + Clone + Display + FromStr + Ord,
     Value: Display + FromStr,
+    Type: GraphType,
 {
     /// Deserializes TrivialGraphFormat into Graph
     fn from_tgf(tgf: TrivialGraphFormat) -> Result<Self, String> {
@@ -270,7 +402,6 @@ where
                     let first = if let Some(f) = tokens.next() {
                         f
                     } else {
-                        println!("SKIPPED");
                         continue;
                     };
 
@@ -280,14 +411,14 @@ where
                         return Err(String::from("couldn't parse vertex"));
                     };
 
-                    let to: Key = if let Ok(key) = tokens.next().unwrap().parse() {
-                        key
-                    } else {
-                        return Err(String::from("couldn't parse vertex"));
+                    let to: Key = match tokens.next().map(str::parse) {
+                        Some(Ok(key)) => key,
+                        Some(Err(_)) => return Err(String::from("couldn't parse vertex")),
+                        None => return Err(format!("edge from {} has no destination", first)),
                     };
-                    graph
-                        .insert_edge_unweighted(from, to)
-                        .expect("graph must contain src and dst vertecies");
+                    if graph.link(from, to, 0).is_err() {
+                        return Err(format!("edge from {} references an unknown vertex", first));
+                    }
                 }
             }
         }
@@ -295,26 +426,28 @@ where
         Ok(graph)
     }
 
-    /// Serializes Graph into TrivialGraphFormat
+    /// Serializes Graph into TrivialGraphFormat.
+    /// Vertices and edges are sorted by keys, so the output is stable.
     fn to_tgf(&self) -> TrivialGraphFormat {
         let mut buffer = String::new();
 
-        for vertex in self.vertecies() {
+        for vertex in self.sorted_vertices() {
             buffer += &format!("{} {}\n", vertex.key(), vertex.value);
         }
 
         buffer += "#\n";
 
-        for vertex in self.vertecies() {
-            for edge in vertex.adjancency_list() {
-                buffer += &format!("{} {}\n", edge.from(), edge.to());
-            }
+        for edge in self.sorted_edges() {
+            buffer += &format!("{} {}\n", edge.from(), edge.to());
         }
 
         buffer.into()
     }
 }
 
+/// Human-readable listing of the graph: a header line followed by one line
+/// per vertex with its value and adjacent vertices, e.g. `1 [A] -> 2, 3 (w=5)`.
+/// Use `json::JsonConvertible` for a machine-readable representation.
 impl<Key, Value, Type> Display for Graph<Key, Value, Type>
 where
     Key: Hash + Eq + Clone + Display,
@@ -322,40 +455,32 @@ where
     Type: GraphType,
 {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let s = 's: {
-            if self.is_empty() {
-                break 's String::from("[]");
-            }
+        write!(
+            f,
+            "{} graph: {} vertices, {} edges",
+            Type::type_name(),
+            self.len(),
+            self.edges_count()
+        )?;
+
+        let arrow = if Type::is_directed() { "->" } else { "--" };
 
-            let mut main_buf = String::new();
-            main_buf.push('[');
+        for vertex in self.vertecies() {
+            write!(f, "\n{} [{}]", vertex.key(), vertex.value)?;
 
-            for vertex in self.vertecies() {
-                let mut sub_buf = String::new();
-                for edge in vertex.adjancency_list() {
-                    sub_buf += &format!("{}, ", edge.to());
+            for (i, edge) in vertex.adjancency_list().iter().enumerate() {
+                if i == 0 {
+                    write!(f, " {} {}", arrow, edge.to())?;
+                } else {
+                    write!(f, ", {}", edge.to())?;
+                }
+                if edge.weight != 0 {
+                    write!(f, " (w={})", edge.weight)?;
                 }
-                let sub_buf = sub_buf.trim_end_matches(", ");
-
-                main_buf += &format!(
-                    r#"
-  {{
-    "key": {},
-    "value": "{}",
-    "adjacent_vertecies_keys": [{}],
-  }},"#,
-                    vertex.key(),
-                    vertex.value,
-                    sub_buf
-                );
             }
+        }
 
-            let main_buf = main_buf.trim_end_matches(',');
-
-            String::from(main_buf) + "\n]"
-        };
-
-        write!(f, "{}", s)
+        Ok(())
     }
 }
 
@@ -0,0 +1,181 @@
+use std::{collections::HashMap, hash::Hash, marker::PhantomData};
+
+use serde::{de::Error, ser::SerializeStruct, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{graph::Graph, marker::GraphType, vertex::Vertex};
+
+/// Graph is serialized as a struct with the name of its type
+/// ("Directed" or "Undirected") and a sequence of its vertices.
+impl<Key, Value, Type> Serialize for Graph<Key, Value, Type>
+where
+    Key: Hash + Eq + Clone + Serialize,
+    Value: Serialize,
+    Type: GraphType,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        struct Vertices<'a, Key, Value>(&'a HashMap<Key, Vertex<Key, Value>>)
+        where
+            Key: Eq + Clone;
+
+        impl<Key, Value> Serialize for Vertices<'_, Key, Value>
+        where
+            Key: Eq + Clone + Serialize,
+            Value: Serialize,
+        {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: Serializer,
+            {
+                serializer.collect_seq(self.0.values())
+            }
+        }
+
+        let mut state = serializer.serialize_struct("Graph", 2)?;
+        state.serialize_field("type", Type::type_name())?;
+        state.serialize_field("vertices", &Vertices(&self.vertices))?;
+        state.end()
+    }
+}
+
+/// GraphRepr is the unchecked form of Graph used during deserialization.
+#[derive(Deserialize)]
+struct GraphRepr<Key, Value>
+where
+    Key: Eq + Clone,
+{
+    #[serde(rename = "type")]
+    type_name: String,
+    vertices: Vec<Vertex<Key, Value>>,
+}
+
+/// Deserialization makes sure that the graph type matches, vertex keys are
+/// unique, there are no dangling edges and edges of undirected graphs exist
+/// in both directions with the same weight.
+impl<'de, Key, Value, Type> Deserialize<'de> for Graph<Key, Value, Type>
+where
+    Key: Hash + Eq + Clone + Deserialize<'de>,
+    Value: Deserialize<'de>,
+    Type: GraphType,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let repr = GraphRepr::<Key, Value>::deserialize(deserializer)?;
+
+        if repr.type_name != Type::type_name() {
+            return Err(D::Error::custom(format!(
+                "expected {} graph, found {}",
+                Type::type_name(),
+                repr.type_name
+            )));
+        }
+
+        let mut vertices = HashMap::with_capacity(repr.vertices.len());
+        for vertex in repr.vertices {
+            if vertices.insert(vertex.key().clone(), vertex).is_some() {
+                return Err(D::Error::custom("graph contains duplicated vertex keys"));
+            }
+        }
+
+        for vertex in vertices.values() {
+            for edge in vertex.adjancency_list() {
+                let destination = vertices
+                    .get(edge.to())
+                    .ok_or_else(|| D::Error::custom("graph contains dangling edges"))?;
+
+                if !Type::is_directed() {
+                    match destination.get_edge(edge.from()) {
+                        Some(reverse) if reverse.weight == edge.weight => {}
+                        _ => {
+                            return Err(D::Error::custom(
+                                "undirected graph must contain edges in both directions",
+                            ))
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(Graph {
+            vertices,
+            _type: PhantomData,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::marker::{Directed, Undirected};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn round_trip_test() {
+        let mut graph: Graph<i32, String, Undirected> = Graph::new();
+        graph.insert(1, String::from("A"));
+        graph.insert(2, String::from("B"));
+        graph.insert(3, String::from("C"));
+        graph.insert_edge(1, 2, 4).unwrap();
+        graph.insert_edge(2, 3, 5).unwrap();
+
+        let json = serde_json::to_string(&graph).expect("graph must be serializable");
+        let restored: Graph<i32, String, Undirected> =
+            serde_json::from_str(&json).expect("graph must be deserializable");
+
+        assert_eq!(restored.len(), 3);
+        assert_eq!(restored.get(&2), Some(&String::from("B")));
+        assert_eq!(restored.edges_count(), 2);
+        assert_eq!(
+            restored
+                .get_vertex(&3)
+                .unwrap()
+                .get_edge(&2)
+                .unwrap()
+                .weight,
+            5
+        );
+    }
+
+    #[test]
+    fn deserialize_invariants_test() {
+        let invalid = [
+            // type mismatch
+            r#"{"type": "Undirected", "vertices": []}"#,
+            // duplicated keys
+            r#"{"type": "Directed", "vertices": [
+                {"key": 1, "value": "A", "edges": []},
+                {"key": 1, "value": "B", "edges": []}]}"#,
+            // dangling edge
+            r#"{"type": "Directed", "vertices": [
+                {"key": 1, "value": "A", "edges": [{"from": 1, "to": 2, "weight": 0}]}]}"#,
+            // edge does not start at its vertex
+            r#"{"type": "Directed", "vertices": [
+                {"key": 1, "value": "A", "edges": []},
+                {"key": 2, "value": "B", "edges": [{"from": 1, "to": 2, "weight": 0}]}]}"#,
+        ];
+
+        for json in invalid {
+            assert!(
+                serde_json::from_str::<Graph<i32, String, Directed>>(json).is_err(),
+                "{} must be rejected",
+                json
+            );
+        }
+
+        let asymmetric = r#"{"type": "Undirected", "vertices": [
+            {"key": 1, "value": "A", "edges": [{"from": 1, "to": 2, "weight": 0}]},
+            {"key": 2, "value": "B", "edges": []}]}"#;
+        assert!(serde_json::from_str::<Graph<i32, String, Undirected>>(asymmetric).is_err());
+    }
+
+    #[test]
+    fn marker_test() {
+        assert_eq!(serde_json::to_string(&Directed).unwrap(), "null");
+        assert!(serde_json::from_str::<Undirected>("null").is_ok());
+    }
+}
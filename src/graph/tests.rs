@@ -1,4 +1,5 @@
 use super::*;
+use crate::marker::Undirected;
 use pretty_assertions::assert_eq;
 
 macro_rules! assert_option_vertices {
@@ -55,7 +56,7 @@ fn get_vertex_test() {
 
     let keys = vec![1, 2, 3].into_iter();
 
-    let holder = vec![Vertex::new(1, 1), Vertex::new(2, 2)];
+    let holder = [Vertex::new(1, 1), Vertex::new(2, 2)];
     let expected_results = vec![Some(&holder[0]), Some(&holder[1]), None].into_iter();
 
     assert_eq!(
@@ -144,7 +145,7 @@ fn dfs_empty_test() {
 
     let mut traversed: Vec<i32> = vec![];
     for vertex in graph.dfs(&0) {
-        traversed.push(vertex.key().clone());
+        traversed.push(*vertex.key());
     }
 
     assert_eq!(traversed.len(), 0);
@@ -171,7 +172,7 @@ fn dfs_simple_test() {
 
     let mut traversed: Vec<i32> = vec![];
     for vertex in graph.dfs(&1) {
-        traversed.push(vertex.key().clone());
+        traversed.push(*vertex.key());
     }
 
     assert_eq!(traversed, vec![1, 3, 4, 2]);
@@ -236,7 +237,7 @@ fn dfs_hard_test() {
 
     let mut traversed: Vec<i32> = vec![];
     for vertex in graph.dfs(&1) {
-        traversed.push(vertex.key().clone());
+        traversed.push(*vertex.key());
     }
 
     assert_eq!(traversed, vec![1, 2, 10, 6, 7, 5, 3, 11, 4,]);
@@ -248,7 +249,7 @@ fn dfs_mut_empty_test() {
 
     let mut traversed: Vec<i32> = vec![];
     for vertex in graph.dfs_mut(&0) {
-        traversed.push(vertex.key().clone());
+        traversed.push(*vertex.key());
     }
 
     assert_eq!(traversed.len(), 0);
@@ -275,7 +276,7 @@ fn dfs_mut_simple_test() {
 
     let mut traversed: Vec<i32> = vec![];
     for vertex in graph.dfs_mut(&1) {
-        traversed.push(vertex.key().clone());
+        traversed.push(*vertex.key());
     }
 
     assert_eq!(traversed, vec![1, 3, 4, 2]);
@@ -340,7 +341,7 @@ fn dfs_mut_hard_test() {
 
     let mut traversed: Vec<i32> = vec![];
     for vertex in graph.dfs_mut(&1) {
-        traversed.push(vertex.key().clone());
+        traversed.push(*vertex.key());
     }
 
     assert_eq!(traversed, vec![1, 2, 10, 6, 7, 5, 3, 11, 4,]);
@@ -352,7 +353,7 @@ fn bfs_empty_test() {
 
     let mut traversed: Vec<i32> = vec![];
     for vertex in graph.bfs(&0) {
-        traversed.push(vertex.key().clone());
+        traversed.push(*vertex.key());
     }
 
     assert_eq!(traversed.len(), 0);
@@ -379,7 +380,7 @@ fn bfs_simple_test() {
 
     let mut traversed: Vec<i32> = vec![];
     for vertex in graph.bfs(&1) {
-        traversed.push(vertex.key().clone());
+        traversed.push(*vertex.key());
     }
 
     assert_eq!(traversed, vec![1, 2, 3, 4]);
@@ -444,7 +445,7 @@ fn bfs_hard_test() {
 
     let mut traversed: Vec<i32> = vec![];
     for vertex in graph.bfs(&1) {
-        traversed.push(vertex.key().clone());
+        traversed.push(*vertex.key());
     }
 
     assert_eq!(traversed, vec![1, 2, 3, 10, 4, 11, 6, 5, 7,]);
@@ -456,7 +457,7 @@ fn bfs_mut_empty_test() {
 
     let mut traversed: Vec<i32> = vec![];
     for vertex in graph.bfs_mut(&0) {
-        traversed.push(vertex.key().clone());
+        traversed.push(*vertex.key());
     }
 
     assert_eq!(traversed.len(), 0);
@@ -483,7 +484,7 @@ fn bfs_mut_simple_test() {
 
     let mut traversed: Vec<i32> = vec![];
     for vertex in graph.bfs_mut(&1) {
-        traversed.push(vertex.key().clone());
+        traversed.push(*vertex.key());
     }
 
     assert_eq!(traversed, vec![1, 2, 3, 4]);
@@ -548,12 +549,58 @@ fn bfs_mut_hard_test() {
 
     let mut traversed: Vec<i32> = vec![];
     for vertex in graph.bfs_mut(&1) {
-        traversed.push(vertex.key().clone());
+        traversed.push(*vertex.key());
     }
 
     assert_eq!(traversed, vec![1, 2, 3, 10, 4, 11, 6, 5, 7,]);
 }
 
+#[test]
+fn traversal_exhausted_test() {
+    // Both paths from 1 lead to 4, so it is queued twice
+    let mut graph: Graph<i32, i32> = Graph::new();
+
+    graph.insert(1, 1);
+    graph.insert(2, 2);
+    graph.insert(3, 3);
+    graph.insert(4, 4);
+
+    for (from, to) in [(1, 2), (1, 3), (2, 4), (3, 4)] {
+        graph
+            .insert_edge_unweighted(from, to)
+            .expect("must contain both vertices");
+    }
+
+    {
+        let mut dfs = graph.dfs(&1);
+        assert_eq!(dfs.by_ref().count(), 4);
+        assert!(dfs.next().is_none());
+    }
+    {
+        let mut bfs = graph.bfs(&1);
+        assert_eq!(bfs.by_ref().count(), 4);
+        assert!(bfs.next().is_none());
+    }
+    {
+        let mut dfs_mut = graph.dfs_mut(&1);
+        let mut traversed: Vec<i32> = vec![];
+        for vertex in dfs_mut.by_ref() {
+            traversed.push(*vertex.key());
+        }
+        assert_eq!(traversed, vec![1, 3, 4, 2]);
+        assert!(dfs_mut.next().is_none());
+    }
+    {
+        let mut bfs_mut = graph.bfs_mut(&1);
+        let mut traversed: Vec<i32> = vec![];
+        for vertex in bfs_mut.by_ref() {
+            traversed.push(*vertex.key());
+        }
+        assert_eq!(traversed, vec![1, 2, 3, 4]);
+        assert!(bfs_mut.next().is_none());
+    }
+}
+
 #[test]
 fn from_tgf_test() {
     let tgf: TrivialGraphFormat = r#"1 A
@@ -652,3 +699,169 @@ fn from_tgf_empty_test() {
 
     assert_eq!(graph.len(), 0);
 }
+
+#[test]
+fn from_tgf_undirected_test() {
+    let tgf: TrivialGraphFormat = "A first\nB second\n#\nA B\n\nB B\n".into();
+
+    let graph: Graph<String, String, Undirected> = Graph::from_tgf(tgf).expect("tgf is not valid");
+    let (a, b) = (String::from("A"), String::from("B"));
+    assert!(graph.get_vertex(&b).unwrap().get_edge(&a).is_some());
+    assert_eq!(graph.edges_count(), 2);
+
+    let restored: Graph<String, String, Undirected> =
+        Graph::from_tgf(graph.to_tgf()).expect("tgf is not valid");
+    assert_eq!(restored.edges_count(), 2);
+}
+
+#[test]
+fn from_tgf_errors_test() {
+    let errors = [
+        ("1 A\n#\n1\n", "edge from 1 has no destination"),
+        ("1 A\n#\n1 2\n", "edge from 1 references an unknown vertex"),
+        ("1 A\n#\n1 x\n", "couldn't parse vertex"),
+        ("x A\n", "couldn't parse vertex"),
+    ];
+    for (tgf, error) in errors {
+        assert_eq!(
+            Graph::<i32, String>::from_tgf(tgf.into()).err(),
+            Some(String::from(error)),
+            "'{}' must be rejected",
+            tgf
+        );
+    }
+}
+
+#[test]
+fn edges_test() {
+    let mut directed: Graph<i32, i32> = Graph::new();
+    let mut undirected: Graph<i32, i32, Undirected> = Graph::new();
+    for i in 1..=3 {
+        directed.insert(i, i);
+        undirected.insert(i, i);
+    }
+
+    for (from, to) in [(1, 2), (2, 1), (2, 3), (3, 3)] {
+        directed
+            .insert_edge_unweighted(from, to)
+            .expect("must contain both vertices");
+    }
+    for (from, to) in [(1, 2), (2, 3)] {
+        undirected
+            .insert_edge_unweighted(from, to)
+            .expect("must contain both vertices");
+    }
+
+    assert_eq!(directed.edges_count(), 4);
+    assert_eq!(undirected.edges_count(), 2);
+}
+
+#[test]
+fn degree_test() {
+    let mut directed: Graph<i32, i32> = Graph::new();
+    let mut undirected: Graph<i32, i32, Undirected> = Graph::new();
+    for i in 1..=3 {
+        directed.insert(i, i);
+        undirected.insert(i, i);
+    }
+
+    for (from, to) in [(1, 2), (2, 3), (3, 3)] {
+        directed
+            .link(from, to, 0)
+            .expect("must contain both vertices");
+        undirected
+            .link(from, to, 0)
+            .expect("must contain both vertices");
+    }
+
+    assert_eq!(
+        (1..=4).map(|i| directed.degree(&i)).collect::<Vec<_>>(),
+        vec![Some(1), Some(1), Some(1), None]
+    );
+    assert_eq!(
+        (1..=4).map(|i| undirected.degree(&i)).collect::<Vec<_>>(),
+        vec![Some(1), Some(2), Some(3), None]
+    );
+}
+
+#[test]
+fn display_test() {
+    let mut graph: Graph<i32, String> = Graph::new();
+    graph.insert(1, String::from("A"));
+    graph.insert(2, String::from("B"));
+    graph
+        .insert_edge(1, 2, 5)
+        .expect("must contain both vertices");
+    graph
+        .insert_edge_unweighted(1, 1)
+        .expect("must contain both vertices");
+
+    let display = graph.to_string();
+    let mut lines: Vec<&str> = display.lines().collect();
+    lines[1..].sort();
+
+    assert_eq!(
+        lines,
+        vec![
+            "Directed graph: 2 vertices, 2 edges",
+            "1 [A] -> 2 (w=5), 1",
+            "2 [B]"
+        ]
+    );
+}
+
+#[test]
+fn map_values_test() {
+    let mut graph: Graph<i32, &str, Undirected> = Graph::new();
+    graph.insert(1, "A");
+    graph.insert(2, "BB");
+    graph
+        .insert_edge(1, 2, 7)
+        .expect("must contain both vertices");
+
+    let mapped = graph.map_values(|key, value| format!("{key}:{}", value.len()));
+
+    assert_eq!(mapped.get(&1).map(String::as_str), Some("1:1"));
+    assert_eq!(mapped.get(&2).map(String::as_str), Some("2:2"));
+    assert_eq!(mapped.edges_count(), 1);
+    assert_eq!(
+        mapped
+            .get_vertex(&2)
+            .and_then(|v| v.get_edge(&1))
+            .map(|e| e.weight),
+        Some(7)
+    );
+}
+
+#[test]
+fn to_tgf_sorted_test() {
+    let mut graph: Graph<i32, String, Undirected> = Graph::new();
+    for (key, value) in [(3, "C"), (1, "A"), (2, "B")] {
+        graph.insert(key, String::from(value));
+    }
+    graph
+        .insert_edge(3, 1, 0)
+        .expect("must contain both vertices");
+    graph
+        .insert_edge(2, 3, 0)
+        .expect("must contain both vertices");
+
+    let tgf: String = graph.to_tgf().into();
+
+    assert_eq!(tgf, "1 A\n2 B\n3 C\n#\n1 3\n2 3\n");
+
+    // Keys are ordered as numbers, not as text, and so are the ends of edges
+    let mut directed: Graph<i32, String> = Graph::new();
+    for key in [10, 2, 1] {
+        directed.insert(key, key.to_string());
+    }
+    for (from, to) in [(1, 10), (1, 2), (10, 2)] {
+        directed
+            .insert_edge(from, to, 0)
+            .expect("must contain both vertices");
+    }
+
+    let tgf: String = directed.to_tgf().into();
+
+    assert_eq!(tgf, "1 1\n2 2\n10 10\n#\n1 2\n1 10\n10 2\n");
+}
@@ -7,7 +7,7 @@ where
     Key: Hash + Eq + Clone,
 {
     /// Inserts a new edge into the undirected graph.
-    /// So edge will be created in both directions.
+    /// So edge will be created in both directions, a loop is stored once.
     /// Returns the old edge if it existed in the list.
     /// Returns None if the edge did not exist in the list.
     /// Returns Err if one of the vertices does not exist.
@@ -26,6 +26,9 @@ where
             .expect("source vertex must exist");
 
         let removed_edge_1 = v_1.insert_edge(to.clone(), weight);
+        if from == to {
+            return Ok(removed_edge_1);
+        }
 
         let v_2 = self
             .get_vertex_mut(&to)
@@ -60,9 +63,11 @@ where
             return Err("failure");
         }
 
-        self.get_vertex_mut(to)
-            .expect("destination vertex must exist")
-            .remove_edge(from);
+        if from != to {
+            self.get_vertex_mut(to)
+                .expect("destination vertex must exist")
+                .remove_edge(from);
+        }
 
         Ok(self
             .get_vertex_mut(from)
@@ -92,6 +97,10 @@ mod tests {
             graph.insert_edge_unweighted(1, 3),
             Ok(Some(Edge::new(1, 3, 0)))
         );
+
+        assert_eq!(graph.insert_edge(2, 2, 1), Ok(None));
+        assert_eq!(graph.insert_edge(2, 2, 3), Ok(Some(Edge::new(2, 2, 1))));
+        assert_eq!(graph.degree(&2), Some(4));
     }
 
     #[test]
@@ -119,5 +128,11 @@ mod tests {
         assert_eq!(graph.remove_edge(&1, &3), Ok(Some(Edge::new(1, 3, 0))));
         assert_eq!(graph.remove_edge(&1, &5), Err("failure"));
         assert_eq!(graph.remove_edge(&2, &3), Ok(Some(Edge::new(2, 3, 0))));
+
+        graph
+            .insert_edge_unweighted(4, 4)
+            .expect("the vertex must exist");
+        assert_eq!(graph.remove_edge(&4, &4), Ok(Some(Edge::new(4, 4, 0))));
+        assert_eq!(graph.remove_edge(&4, &4), Ok(None));
     }
 }
@@ -0,0 +1,428 @@
+//! Encoders and decoders for the compact graph6, sparse6 and digraph6 formats
+//! of nauty. See <https://users.cecs.anu.edu.au/~bdm/data/formats.txt>.
+//!
+//! These formats describe unweighted graphs with vertices numbered from 0 to
+//! n - 1, so they are mapped to `Graph<usize, (), Undirected>` for graph6 and
+//! sparse6 and to `Graph<usize, (), Directed>` for digraph6. Edge weights are
+//! not encoded.
+
+use crate::{
+    graph::Graph,
+    marker::{Directed, GraphType, Undirected},
+};
+
+const GRAPH6_HEADER: &str = ">>graph6<<";
+const SPARSE6_HEADER: &str = ">>sparse6<<";
+const DIGRAPH6_HEADER: &str = ">>digraph6<<";
+
+/// Format of a single encoded graph.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Graph6Format {
+    Graph6,
+    Sparse6,
+    Digraph6,
+}
+
+impl Graph6Format {
+    /// Detects the format of the line by its optional header and its first character.
+    pub fn detect(line: &str) -> Graph6Format {
+        let line = line.trim();
+        if line.starts_with(SPARSE6_HEADER) || line.starts_with(':') {
+            Graph6Format::Sparse6
+        } else if line.starts_with(DIGRAPH6_HEADER) || line.starts_with('&') {
+            Graph6Format::Digraph6
+        } else {
+            Graph6Format::Graph6
+        }
+    }
+}
+
+/// Checks that the graph is keyed by 0..n and returns n.
+fn check_keys<Type: GraphType>(graph: &Graph<usize, (), Type>) -> Result<usize, String> {
+    let n = graph.len();
+    if (0..n).any(|key| !graph.contains(&key)) {
+        return Err(format!(
+            "vertices must be numbered from 0 to {}",
+            n.max(1) - 1
+        ));
+    }
+    Ok(n)
+}
+
+/// Creates the graph of vertices 0..n.
+/// Returns Err if there is no memory for n vertices.
+fn new_graph<Type: GraphType>(n: usize) -> Result<Graph<usize, (), Type>, String> {
+    let mut graph = Graph::try_with_capacity(n)
+        .map_err(|_| format!("graph with {} vertices is too large", n))?;
+    for key in 0..n {
+        graph.insert(key, ());
+    }
+    Ok(graph)
+}
+
+/// Returns the number of bits in the adjacency data of the graph6 or digraph6 graph.
+fn adjacency_bits(n: usize, directed: bool) -> Result<usize, String> {
+    let bits = if directed {
+        n.checked_mul(n)
+    } else {
+        n.checked_mul(n.saturating_sub(1)).map(|bits| bits / 2)
+    };
+    bits.ok_or_else(|| format!("graph with {} vertices is too large", n))
+}
+
+/// Encodes the number of vertices.
+fn encode_size(n: usize, buffer: &mut String) -> Result<(), String> {
+    let (prefix, length) = match n {
+        0..=62 => {
+            buffer.push((n as u8 + 63) as char);
+            return Ok(());
+        }
+        63..=258047 => ("~", 3),
+        258048..=68719476735 => ("~~", 6),
+        _ => return Err(format!("graph with {} vertices is too large", n)),
+    };
+    *buffer += prefix;
+    for i in (0..length).rev() {
+        buffer.push((((n >> (6 * i)) & 0x3f) as u8 + 63) as char);
+    }
+    Ok(())
+}
+
+/// Decodes the number of vertices and returns the rest of the data.
+fn decode_size(data: &[u8]) -> Result<(usize, &[u8]), String> {
+    let (length, data) = match data {
+        [b'~', b'~', rest @ ..] => (6, rest),
+        [b'~', rest @ ..] => (3, rest),
+        [first, rest @ ..] => return Ok(((first - 63) as usize, rest)),
+        [] => return Err(String::from("number of vertices is missing")),
+    };
+    if data.len() < length {
+        return Err(String::from("number of vertices is truncated"));
+    }
+    let n = data[..length]
+        .iter()
+        .fold(0, |n, byte| (n << 6) | (byte - 63) as usize);
+    Ok((n, &data[length..]))
+}
+
+/// Encodes the bits in groups of six, padding the last group with zeros.
+fn encode_bits(bits: &[bool], buffer: &mut String) {
+    for chunk in bits.chunks(6) {
+        let mut byte = 0u8;
+        for i in 0..6 {
+            byte = (byte << 1) | chunk.get(i).copied().unwrap_or(false) as u8;
+        }
+        buffer.push((byte + 63) as char);
+    }
+}
+
+/// Decodes the data into bits, six bits per byte.
+fn decode_bits(data: &[u8]) -> impl Iterator<Item = bool> + '_ {
+    data.iter()
+        .flat_map(|byte| (0..6).rev().map(move |i| ((byte - 63) >> i) & 1 == 1))
+}
+
+/// Strips the optional header and checks that the line consists of printable characters.
+fn prepare<'a>(line: &'a str, header: &str, prefix: &str) -> Result<&'a [u8], String> {
+    let line = line.trim();
+    let line = line.strip_prefix(header).unwrap_or(line);
+    let line = line
+        .strip_prefix(prefix)
+        .ok_or_else(|| format!("line must start with '{}'", prefix))?;
+    if line.bytes().any(|b| !(63..=126).contains(&b)) {
+        return Err(String::from("line contains invalid characters"));
+    }
+    Ok(line.as_bytes())
+}
+
+/// Encodes the undirected graph into graph6. Graph6 can't represent loops.
+/// Returns Err if the graph is not keyed by 0..n or contains loops.
+pub fn to_graph6(graph: &Graph<usize, (), Undirected>) -> Result<String, String> {
+    let n = check_keys(graph)?;
+
+    let mut bits = vec![false; adjacency_bits(n, false)?];
+    for edge in graph.edges() {
+        let (i, j) = (*edge.from().min(edge.to()), *edge.from().max(edge.to()));
+        if i == j {
+            return Err(String::from("graph6 can't represent loops, use sparse6"));
+        }
+        bits[j * (j - 1) / 2 + i] = true;
+    }
+
+    let mut buffer = String::new();
+    encode_size(n, &mut buffer)?;
+    encode_bits(&bits, &mut buffer);
+    Ok(buffer)
+}
+
+/// Decodes the undirected graph from graph6.
+pub fn from_graph6(line: &str) -> Result<Graph<usize, (), Undirected>, String> {
+    let data = prepare(line, GRAPH6_HEADER, "")?;
+    let (n, data) = decode_size(data)?;
+
+    let expected = adjacency_bits(n, false)?.div_ceil(6);
+    if data.len() != expected {
+        return Err(format!(
+            "expected {} bytes of adjacency data, found {}",
+            expected,
+            data.len()
+        ));
+    }
+
+    let mut graph: Graph<usize, (), Undirected> = new_graph(n)?;
+    let pairs = (1..n).flat_map(|j| (0..j).map(move |i| (i, j)));
+    for ((i, j), bit) in pairs.zip(decode_bits(data)) {
+        if bit {
+            graph
+                .insert_edge_unweighted(i, j)
+                .expect("graph must contain src and dst vertecies");
+        }
+    }
+
+    Ok(graph)
+}
+
+/// Encodes the directed graph into digraph6.
+/// Returns Err if the graph is not keyed by 0..n.
+pub fn to_digraph6(graph: &Graph<usize, (), Directed>) -> Result<String, String> {
+    let n = check_keys(graph)?;
+
+    let mut bits = vec![false; adjacency_bits(n, true)?];
+    for edge in graph.edges() {
+        bits[edge.from() * n + edge.to()] = true;
+    }
+
+    let mut buffer = String::from("&");
+    encode_size(n, &mut buffer)?;
+    encode_bits(&bits, &mut buffer);
+    Ok(buffer)
+}
+
+/// Decodes the directed graph from digraph6.
+pub fn from_digraph6(line: &str) -> Result<Graph<usize, (), Directed>, String> {
+    let data = prepare(line, DIGRAPH6_HEADER, "&")?;
+    let (n, data) = decode_size(data)?;
+
+    let bits = adjacency_bits(n, true)?;
+    let expected = bits.div_ceil(6);
+    if data.len() != expected {
+        return Err(format!(
+            "expected {} bytes of adjacency data, found {}",
+            expected,
+            data.len()
+        ));
+    }
+
+    let mut graph: Graph<usize, (), Directed> = new_graph(n)?;
+    for (position, bit) in decode_bits(data).take(bits).enumerate() {
+        if bit {
+            graph
+                .insert_edge_unweighted(position / n, position % n)
+                .expect("graph must contain src and dst vertecies");
+        }
+    }
+
+    Ok(graph)
+}
+
+/// Number of bits needed to write the largest vertex number in sparse6.
+fn sparse6_width(n: usize) -> usize {
+    let mut k = 1;
+    while (1 << k) < n {
+        k += 1;
+    }
+    k
+}
+
+/// Encodes the undirected graph into sparse6. Unlike graph6 it can represent loops.
+/// Returns Err if the graph is not keyed by 0..n.
+pub fn to_sparse6(graph: &Graph<usize, (), Undirected>) -> Result<String, String> {
+    let n = check_keys(graph)?;
+    let k = sparse6_width(n);
+
+    let mut edges: Vec<(usize, usize)> = graph
+        .edges()
+        .map(|e| (*e.from().max(e.to()), *e.from().min(e.to())))
+        .collect();
+    edges.sort_unstable();
+
+    let mut bits = Vec::new();
+    let push = |bits: &mut Vec<bool>, x: usize| {
+        for i in (0..k).rev() {
+            bits.push((x >> i) & 1 == 1);
+        }
+    };
+
+    let mut current = 0;
+    for (v, u) in edges {
+        if v == current {
+            bits.push(false);
+            push(&mut bits, u);
+        } else if v == current + 1 {
+            current = v;
+            bits.push(true);
+            push(&mut bits, u);
+        } else {
+            current = v;
+            bits.push(true);
+            push(&mut bits, v);
+            bits.push(false);
+            push(&mut bits, u);
+        }
+    }
+
+    // Padding must not be read as an edge to the vertex n - 1
+    let padding = (6 - bits.len() % 6) % 6;
+    if k < 6 && n == (1 << k) && padding >= k && current < n - 1 {
+        bits.push(false);
+    }
+    while bits.len() % 6 != 0 {
+        bits.push(true);
+    }
+
+    let mut buffer = String::from(":");
+    encode_size(n, &mut buffer)?;
+    encode_bits(&bits, &mut buffer);
+    Ok(buffer)
+}
+
+/// Decodes the undirected graph from sparse6. Parallel edges are merged.
+pub fn from_sparse6(line: &str) -> Result<Graph<usize, (), Undirected>, String> {
+    let data = prepare(line, SPARSE6_HEADER, ":")?;
+    let (n, data) = decode_size(data)?;
+    let k = sparse6_width(n);
+
+    // Sparse6 data doesn't grow with n, so n can only be checked by the allocation
+    let mut graph: Graph<usize, (), Undirected> = new_graph(n)?;
+    let mut bits = decode_bits(data);
+    let mut v = 0;
+
+    while let Some(b) = bits.next() {
+        let mut x = 0;
+        for _ in 0..k {
+            match bits.next() {
+                Some(bit) => x = (x << 1) | bit as usize,
+                None => return Ok(graph),
+            }
+        }
+
+        if b {
+            v += 1;
+        }
+        if x >= n || v >= n {
+            break;
+        } else if x > v {
+            v = x;
+        } else {
+            graph
+                .link(x, v, 0)
+                .expect("graph must contain src and dst vertecies");
+        }
+    }
+
+    Ok(graph)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn undirected(n: usize, edges: &[(usize, usize)]) -> Graph<usize, (), Undirected> {
+        let mut graph = new_graph(n).unwrap();
+        for (from, to) in edges {
+            graph.link(*from, *to, 0).unwrap();
+        }
+        graph
+    }
+
+    fn edge_set<Type: GraphType>(graph: &Graph<usize, (), Type>) -> Vec<(usize, usize)> {
+        let mut edges: Vec<(usize, usize)> = graph
+            .edges()
+            .map(|e| {
+                if Type::is_directed() {
+                    (*e.from(), *e.to())
+                } else {
+                    (*e.from().min(e.to()), *e.from().max(e.to()))
+                }
+            })
+            .collect();
+        edges.sort_unstable();
+        edges
+    }
+
+    #[test]
+    fn graph6_test() {
+        // Examples from the nauty format description
+        let graph = undirected(5, &[(0, 2), (0, 4), (1, 3), (3, 4)]);
+        assert_eq!(to_graph6(&graph), Ok(String::from("DQc")));
+
+        let decoded = from_graph6(">>graph6<<DQc").expect("graph6 is valid");
+        assert_eq!(edge_set(&decoded), vec![(0, 2), (0, 4), (1, 3), (3, 4)]);
+
+        let empty = from_graph6("?").expect("graph6 is valid");
+        assert_eq!(empty.len(), 0);
+
+        let large = undirected(100, &[(0, 99)]);
+        let decoded = from_graph6(&to_graph6(&large).unwrap()).expect("graph6 is valid");
+        assert_eq!(decoded.len(), 100);
+        assert_eq!(edge_set(&decoded), vec![(0, 99)]);
+
+        assert!(to_graph6(&undirected(2, &[(1, 1)])).is_err());
+        assert!(from_graph6("DQ").is_err());
+        assert!(from_graph6("D Qc").is_err());
+    }
+
+    #[test]
+    fn sparse6_test() {
+        // Example from the nauty format description
+        let decoded = from_sparse6(":Fa@x^").expect("sparse6 is valid");
+        assert_eq!(decoded.len(), 7);
+        assert_eq!(edge_set(&decoded), vec![(0, 1), (0, 2), (1, 2), (5, 6)]);
+        assert_eq!(to_sparse6(&decoded), Ok(String::from(":Fa@x^")));
+
+        for n in [1, 2, 4, 8, 9, 70] {
+            let graph = undirected(n, &[(0, 0), (0, n - 1), (n / 2, n - 1)]);
+            let decoded = from_sparse6(&to_sparse6(&graph).unwrap()).expect("sparse6 is valid");
+            assert_eq!(decoded.len(), n);
+            assert_eq!(edge_set(&decoded), edge_set(&graph), "n = {}", n);
+        }
+    }
+
+    #[test]
+    fn digraph6_test() {
+        // Example from the nauty format description
+        let decoded = from_digraph6("&DI?AO?").expect("digraph6 is valid");
+        assert_eq!(decoded.len(), 5);
+        assert_eq!(edge_set(&decoded), vec![(0, 2), (0, 4), (3, 1), (3, 4)]);
+        assert_eq!(to_digraph6(&decoded), Ok(String::from("&DI?AO?")));
+
+        let mut graph: Graph<usize, ()> = new_graph(3).unwrap();
+        graph.insert_edge_unweighted(1, 1).unwrap();
+        graph.insert_edge_unweighted(2, 0).unwrap();
+        let decoded = from_digraph6(&to_digraph6(&graph).unwrap()).expect("digraph6 is valid");
+        assert_eq!(edge_set(&decoded), vec![(1, 1), (2, 0)]);
+
+        assert!(from_digraph6("DI?AO?").is_err());
+    }
+
+    #[test]
+    fn too_large_test() {
+        assert!(from_graph6("~~~~~~~~").is_err());
+        assert!(from_digraph6("&~~~~~~~~").is_err());
+        assert!(from_sparse6(":~~~~~~~~").is_err());
+        // Size is fine, but the adjacency data is missing
+        assert!(from_graph6("~??~").is_err());
+    }
+
+    #[test]
+    fn detect_test() {
+        assert_eq!(Graph6Format::detect("DQc"), Graph6Format::Graph6);
+        assert_eq!(
+            Graph6Format::detect(">>sparse6<<:Fa@x^"),
+            Graph6Format::Sparse6
+        );
+        assert_eq!(Graph6Format::detect("&DI?AO?"), Graph6Format::Digraph6);
+    }
+}
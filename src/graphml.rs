@@ -0,0 +1,456 @@
+use std::{fmt::Display, hash::Hash, str::FromStr};
+
+use crate::{edge, graph::Graph, marker::GraphType, xml};
+
+/// GraphMl is an XML based format for graphs.
+/// See <http://graphml.graphdrawing.org/specification.html>.
+pub struct GraphMl(String);
+
+impl From<String> for GraphMl {
+    fn from(value: String) -> Self {
+        GraphMl(value)
+    }
+}
+
+impl<'a> From<&'a str> for GraphMl {
+    fn from(value: &'a str) -> GraphMl {
+        GraphMl(String::from(value))
+    }
+}
+
+impl From<GraphMl> for String {
+    fn from(value: GraphMl) -> Self {
+        value.0
+    }
+}
+
+impl Display for GraphMl {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl GraphMl {
+    /// Returns true if the edge default of the first graph is directed.
+    /// Allows choosing the type of the graph before converting.
+    pub fn is_directed(&self) -> Result<bool, String> {
+        let root = xml::parse(&self.0)?;
+        let element = root
+            .child("graph")
+            .ok_or_else(|| String::from("document does not contain a graph"))?;
+        edge_default_directed(element)
+    }
+}
+
+/// GraphMlConvertible is a trait that allows converting from and to GraphMl.
+pub trait GraphMlConvertible {
+    fn to_graphml(&self) -> GraphMl;
+
+    fn from_graphml(graphml: GraphMl) -> Result<Self, String>
+    where
+        Self: Sized;
+}
+
+/// GraphMlType maps a vertex value type to the GraphML attribute type
+/// used in the `<key>` declaration (boolean, int, long, float, double or string).
+pub trait GraphMlType {
+    fn graphml_type() -> &'static str;
+}
+
+macro_rules! impl_graphml_type {
+    ($name:literal: $($t:ty),+) => {
+        $(
+            impl GraphMlType for $t {
+                fn graphml_type() -> &'static str {
+                    $name
+                }
+            }
+        )+
+    };
+}
+
+impl_graphml_type!("boolean": bool);
+impl_graphml_type!("int": i8, i16, i32, u8, u16);
+impl_graphml_type!("long": i64, isize, u32, u64, usize);
+impl_graphml_type!("float": f32);
+impl_graphml_type!("double": f64);
+impl_graphml_type!("string": String, char);
+
+const VALUE_KEY_ID: &str = "value";
+const WEIGHT_KEY_ID: &str = "weight";
+
+/// Declared attribute of nodes or edges.
+struct KeyDeclaration<'a> {
+    id: &'a str,
+    attr_type: &'a str,
+    default: Option<&'a str>,
+}
+
+/// Returns true if a value of the declared type can be read as the expected type.
+fn types_compatible(declared: &str, expected: &str) -> bool {
+    let integer = |t: &str| t == "int" || t == "long";
+    let float = |t: &str| t == "float" || t == "double";
+
+    declared == expected
+        || (integer(declared) && integer(expected))
+        || (float(declared) && float(expected))
+}
+
+/// Finds the key declared for the domain with the given attribute name.
+fn find_key<'a>(
+    root: &'a xml::Element,
+    domain: &str,
+    attr_name: &str,
+) -> Result<Option<KeyDeclaration<'a>>, String> {
+    for key in root.children_named("key") {
+        let key_domain = key.attr("for").unwrap_or("all");
+        if (key_domain != domain && key_domain != "all") || key.attr("attr.name") != Some(attr_name)
+        {
+            continue;
+        }
+
+        return Ok(Some(KeyDeclaration {
+            id: key.required_attr("id")?,
+            attr_type: key.attr("attr.type").unwrap_or("string"),
+            default: key.child("default").map(|d| d.text.as_str()),
+        }));
+    }
+
+    Ok(None)
+}
+
+/// Returns the raw text of the data element of the key, falling back to the key's default.
+fn data<'a>(element: &'a xml::Element, key: &Option<KeyDeclaration<'a>>) -> Option<&'a str> {
+    let key = key.as_ref()?;
+    element
+        .children_named("data")
+        .find(|d| d.attr("key") == Some(key.id))
+        .map(|d| d.text.as_str())
+        .or(key.default)
+}
+
+fn edge_default_directed(graph: &xml::Element) -> Result<bool, String> {
+    match graph.attr("edgedefault").unwrap_or("directed") {
+        "directed" => Ok(true),
+        "undirected" => Ok(false),
+        other => Err(format!("unknown edgedefault '{}'", other)),
+    }
+}
+
+fn parse_weight(raw: &str, attr_type: &str) -> Result<i64, String> {
+    match attr_type {
+        "int" | "long" | "float" | "double" => edge::parse_weight(raw),
+        other => Err(format!(
+            "edge weight is declared as '{}', but integer type was expected",
+            other
+        )),
+    }
+}
+
+impl<Key, Value, Type> GraphMlConvertible for Graph<Key, Value, Type>
+where
+    Key: Hash + Eq + Clone + Display + FromStr + Ord,
+    Value: Display + FromStr + GraphMlType,
+    Type: GraphType,
+{
+    /// Deserializes GraphMl into Graph.
+    /// Only the first graph of the document is read.
+    fn from_graphml(graphml: GraphMl) -> Result<Self, String> {
+        let raw: String = graphml.into();
+        let root = xml::parse(&raw)?;
+        if root.local_name() != "graphml" {
+            return Err(format!(
+                "expected <graphml> root element, found <{}>",
+                root.name
+            ));
+        }
+
+        let element = root
+            .child("graph")
+            .ok_or_else(|| String::from("document does not contain a graph"))?;
+
+        let directed = edge_default_directed(element)?;
+        if directed != Type::is_directed() {
+            return Err(format!(
+                "graph is declared as {}, but {} graph was requested",
+                if directed { "directed" } else { "undirected" },
+                Type::type_name()
+            ));
+        }
+
+        let value_key = find_key(&root, "node", VALUE_KEY_ID)?;
+        if let Some(key) = &value_key {
+            if !types_compatible(key.attr_type, Value::graphml_type()) {
+                return Err(format!(
+                    "vertex value is declared as '{}', but '{}' was expected",
+                    key.attr_type,
+                    Value::graphml_type()
+                ));
+            }
+        }
+
+        let weight_key = find_key(&root, "edge", WEIGHT_KEY_ID)?;
+        if let Some(key) = &weight_key {
+            if !types_compatible(key.attr_type, "long")
+                && !types_compatible(key.attr_type, "double")
+            {
+                return Err(format!(
+                    "edge weight is declared as '{}', but numeric type was expected",
+                    key.attr_type
+                ));
+            }
+        }
+
+        let mut graph: Self = Graph::new();
+
+        for node in element.children_named("node") {
+            if node.child("graph").is_some() {
+                return Err(String::from("nested graphs are not supported"));
+            }
+
+            let id = node.required_attr("id")?;
+            let key: Key = id
+                .parse()
+                .map_err(|_| format!("couldn't parse vertex '{}'", id))?;
+
+            let raw_value = data(node, &value_key).unwrap_or("");
+            let value = raw_value
+                .parse()
+                .map_err(|_| format!("couldn't parse value '{}' of vertex '{}'", raw_value, id))?;
+
+            if graph.insert(key, value).is_some() {
+                return Err(format!("vertex '{}' is declared twice", id));
+            }
+        }
+
+        if element.child("hyperedge").is_some() {
+            return Err(String::from("hyperedges are not supported"));
+        }
+
+        for edge in element.children_named("edge") {
+            if let Some(edge_directed) = edge.attr("directed") {
+                if (edge_directed == "true") != directed {
+                    return Err(format!(
+                        "edge with directed=\"{}\" in {} graph",
+                        edge_directed,
+                        Type::type_name()
+                    ));
+                }
+            }
+
+            let source = edge.required_attr("source")?;
+            let target = edge.required_attr("target")?;
+            let from: Key = source
+                .parse()
+                .map_err(|_| format!("couldn't parse vertex '{}'", source))?;
+            let to: Key = target
+                .parse()
+                .map_err(|_| format!("couldn't parse vertex '{}'", target))?;
+
+            let weight = match (data(edge, &weight_key), &weight_key) {
+                (Some(raw), Some(key)) => parse_weight(raw, key.attr_type)?,
+                _ => 0,
+            };
+
+            graph.link(from, to, weight).map_err(|_| {
+                format!("edge {} -> {} references an unknown vertex", source, target)
+            })?;
+        }
+
+        Ok(graph)
+    }
+
+    /// Serializes Graph into GraphMl.
+    /// Nodes and edges are sorted by keys, so the output is stable.
+    fn to_graphml(&self) -> GraphMl {
+        let mut buffer = String::from(concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+            "<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\"",
+            " xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\"",
+            " xsi:schemaLocation=\"http://graphml.graphdrawing.org/xmlns",
+            " http://graphml.graphdrawing.org/xmlns/1.0/graphml.xsd\">\n",
+        ));
+
+        buffer += &format!(
+            "  <key id=\"{}\" for=\"node\" attr.name=\"{}\" attr.type=\"{}\"/>\n",
+            VALUE_KEY_ID,
+            VALUE_KEY_ID,
+            Value::graphml_type()
+        );
+        buffer += &format!(
+            "  <key id=\"{}\" for=\"edge\" attr.name=\"{}\" attr.type=\"long\">\n",
+            WEIGHT_KEY_ID, WEIGHT_KEY_ID
+        );
+        buffer += "    <default>0</default>\n  </key>\n";
+
+        buffer += &format!(
+            "  <graph id=\"G\" edgedefault=\"{}\">\n",
+            if Type::is_directed() {
+                "directed"
+            } else {
+                "undirected"
+            }
+        );
+
+        for vertex in self.sorted_vertices() {
+            buffer += &format!(
+                "    <node id=\"{}\">\n      <data key=\"{}\">{}</data>\n    </node>\n",
+                xml::escape(&vertex.key().to_string()),
+                VALUE_KEY_ID,
+                xml::escape(&vertex.value.to_string())
+            );
+        }
+
+        for edge in self.sorted_edges() {
+            buffer += &format!(
+                "    <edge source=\"{}\" target=\"{}\">\n      <data key=\"{}\">{}</data>\n    </edge>\n",
+                xml::escape(&edge.from().to_string()),
+                xml::escape(&edge.to().to_string()),
+                WEIGHT_KEY_ID,
+                edge.weight
+            );
+        }
+
+        buffer += "  </graph>\n</graphml>\n";
+
+        buffer.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::marker::{Directed, Undirected};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn round_trip_directed_test() {
+        let mut graph: Graph<i32, String, Directed> = Graph::new();
+        graph.insert(1, String::from("one & <one>"));
+        graph.insert(2, String::from("two"));
+        graph.insert(3, String::from("three"));
+        graph.insert_edge(1, 2, 7).unwrap();
+        graph.insert_edge(2, 3, -3).unwrap();
+
+        let restored: Graph<i32, String, Directed> =
+            Graph::from_graphml(graph.to_graphml()).expect("graphml is not valid");
+
+        assert_eq!(restored.len(), 3);
+        assert_eq!(restored.get(&1), Some(&String::from("one & <one>")));
+        assert_eq!(
+            restored
+                .get_vertex(&1)
+                .unwrap()
+                .get_edge(&2)
+                .unwrap()
+                .weight,
+            7
+        );
+        assert_eq!(
+            restored
+                .get_vertex(&2)
+                .unwrap()
+                .get_edge(&3)
+                .unwrap()
+                .weight,
+            -3
+        );
+        assert!(restored.get_vertex(&2).unwrap().get_edge(&1).is_none());
+        assert_eq!(restored.edges_count(), 2);
+    }
+
+    #[test]
+    fn round_trip_undirected_test() {
+        let mut graph: Graph<String, i64, Undirected> = Graph::new();
+        graph.insert(String::from("a"), 1);
+        graph.insert(String::from("b"), 2);
+        graph
+            .insert_edge(String::from("a"), String::from("b"), 5)
+            .unwrap();
+
+        let graphml = graph.to_graphml();
+        assert!(graphml.to_string().contains("edgedefault=\"undirected\""));
+        assert!(graphml.to_string().contains("attr.type=\"long\""));
+
+        let restored: Graph<String, i64, Undirected> =
+            Graph::from_graphml(graphml).expect("graphml is not valid");
+
+        assert_eq!(restored.edges_count(), 1);
+        let b = String::from("b");
+        assert_eq!(restored.get(&b), Some(&2));
+        assert_eq!(
+            restored
+                .get_vertex(&b)
+                .unwrap()
+                .get_edge(&String::from("a"))
+                .unwrap()
+                .weight,
+            5
+        );
+    }
+
+    #[test]
+    fn from_graphml_external_test() {
+        let graphml: GraphMl = r#"<?xml version="1.0" encoding="UTF-8"?>
+<graphml xmlns="http://graphml.graphdrawing.org/xmlns">
+  <key id="d0" for="node" attr.name="value" attr.type="string">
+    <default>unknown</default>
+  </key>
+  <key id="d1" for="edge" attr.name="weight" attr.type="double"/>
+  <graph id="G" edgedefault="directed">
+    <node id="1"><data key="d0">A</data></node>
+    <node id="2"/>
+    <edge source="1" target="2"><data key="d1">2.0</data></edge>
+    <edge source="2" target="1"/>
+  </graph>
+</graphml>"#
+            .into();
+
+        let graph: Graph<i32, String> = Graph::from_graphml(graphml).expect("graphml is not valid");
+
+        assert_eq!(graph.get(&1), Some(&String::from("A")));
+        assert_eq!(graph.get(&2), Some(&String::from("unknown")));
+        assert_eq!(
+            graph.get_vertex(&1).unwrap().get_edge(&2).unwrap().weight,
+            2
+        );
+        assert_eq!(
+            graph.get_vertex(&2).unwrap().get_edge(&1).unwrap().weight,
+            0
+        );
+    }
+
+    #[test]
+    fn from_graphml_mismatch_test() {
+        let undirected = r#"<graphml><graph edgedefault="undirected"/></graphml>"#;
+        assert_eq!(GraphMl::from(undirected).is_directed(), Ok(false));
+        assert!(Graph::<i32, String, Directed>::from_graphml(undirected.into()).is_err());
+        assert!(Graph::<i32, String, Undirected>::from_graphml(undirected.into()).is_ok());
+
+        let value_type = r#"<graphml>
+  <key id="v" for="node" attr.name="value" attr.type="string"/>
+  <graph edgedefault="directed"><node id="1"><data key="v">x</data></node></graph>
+</graphml>"#;
+        assert!(Graph::<i32, i32>::from_graphml(value_type.into()).is_err());
+
+        let fractional = r#"<graphml>
+  <key id="w" for="edge" attr.name="weight" attr.type="double"/>
+  <graph edgedefault="directed">
+    <node id="1"/><node id="2"/>
+    <edge source="1" target="2"><data key="w">0.5</data></edge>
+  </graph>
+</graphml>"#;
+        assert!(Graph::<i32, String>::from_graphml(fractional.into()).is_err());
+
+        let dangling = r#"<graphml><graph edgedefault="directed">
+  <node id="1"/><edge source="1" target="2"/>
+</graph></graphml>"#;
+        assert!(Graph::<i32, String>::from_graphml(dangling.into()).is_err());
+
+        let edge_direction = r#"<graphml><graph edgedefault="directed">
+  <node id="1"/><node id="2"/><edge source="1" target="2" directed="false"/>
+</graph></graphml>"#;
+        assert!(Graph::<i32, String>::from_graphml(edge_direction.into()).is_err());
+    }
+}
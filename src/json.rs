@@ -0,0 +1,798 @@
+use std::{fmt::Display, hash::Hash, str::FromStr};
+
+use crate::{edge, graph::Graph, marker::GraphType, MAX_DEPTH};
+
+/// NodeLinkJson is a JSON format for graphs that lists vertices in the "nodes"
+/// array and edges in the "links" array. It is compatible with the node-link
+/// format of NetworkX and D3.
+///
+/// ```json
+/// {
+///   "directed": true,
+///   "multigraph": false,
+///   "nodes": [{"id": 1, "value": "A"}, {"id": 2, "value": "B"}],
+///   "links": [{"source": 1, "target": 2, "weight": 0}]
+/// }
+/// ```
+///
+/// Keys and values are written as the JSON type given by their JsonType,
+/// e.g. numbers for primitive numeric types and strings for String.
+pub struct NodeLinkJson(String);
+
+impl From<String> for NodeLinkJson {
+    fn from(value: String) -> Self {
+        NodeLinkJson(value)
+    }
+}
+
+impl<'a> From<&'a str> for NodeLinkJson {
+    fn from(value: &'a str) -> NodeLinkJson {
+        NodeLinkJson(String::from(value))
+    }
+}
+
+impl From<NodeLinkJson> for String {
+    fn from(value: NodeLinkJson) -> Self {
+        value.0
+    }
+}
+
+impl Display for NodeLinkJson {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl NodeLinkJson {
+    /// Returns the value of "directed", or false if it is missing as NetworkX does.
+    /// Allows choosing the type of the graph before converting.
+    pub fn is_directed(&self) -> Result<bool, String> {
+        match parse(&self.0)?.get("directed") {
+            Some(directed) => directed
+                .as_bool()
+                .ok_or_else(|| String::from("\"directed\" must be a boolean")),
+            None => Ok(false),
+        }
+    }
+}
+
+/// JsonConvertible is a trait that allows converting from and to NodeLinkJson.
+pub trait JsonConvertible {
+    fn to_json(&self) -> NodeLinkJson;
+
+    fn from_json(json: NodeLinkJson) -> Result<Self, String>
+    where
+        Self: Sized;
+}
+
+/// JsonType maps a key or value type to the JSON type it is written as
+/// (boolean, number or string).
+pub trait JsonType {
+    fn json_type() -> &'static str;
+}
+
+macro_rules! impl_json_type {
+    ($name:literal: $($t:ty),+) => {
+        $(
+            impl JsonType for $t {
+                fn json_type() -> &'static str {
+                    $name
+                }
+            }
+        )+
+    };
+}
+
+impl_json_type!("boolean": bool);
+impl_json_type!("number": i8, i16, i32, i64, i128, isize);
+impl_json_type!("number": u8, u16, u32, u64, u128, usize, f32, f64);
+impl_json_type!("string": String, char);
+
+/// JsonValue is a parsed JSON document.
+/// Its Display writes the document back as compact JSON.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum JsonValue {
+    Null,
+    Bool(bool),
+    /// Numbers are kept in their textual form, so integers of any size survive a round trip.
+    Number(String),
+    String(String),
+    Array(Vec<JsonValue>),
+    /// Members are kept in the document order.
+    Object(Vec<(String, JsonValue)>),
+}
+
+impl JsonValue {
+    /// Returns the member of the object if it exists, otherwise returns None.
+    pub fn get(&self, name: &str) -> Option<&JsonValue> {
+        match self {
+            JsonValue::Object(members) => members.iter().find(|(n, _)| n == name).map(|m| &m.1),
+            _ => None,
+        }
+    }
+
+    /// Returns the items if the value is an array, otherwise returns None.
+    pub fn as_array(&self) -> Option<&Vec<JsonValue>> {
+        match self {
+            JsonValue::Array(items) => Some(items),
+            _ => None,
+        }
+    }
+
+    /// Returns the boolean if the value is one, otherwise returns None.
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            JsonValue::Bool(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    /// Returns the number as an integer. Floats without fractional part are accepted.
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            JsonValue::Number(raw) => edge::parse_weight(raw).ok(),
+            _ => None,
+        }
+    }
+
+    /// Returns the textual representation of a scalar (string, number or boolean).
+    /// Used to feed FromStr implementations.
+    pub fn as_scalar(&self) -> Option<String> {
+        match self {
+            JsonValue::String(s) => Some(s.clone()),
+            JsonValue::Number(raw) => Some(raw.clone()),
+            JsonValue::Bool(b) => Some(b.to_string()),
+            _ => None,
+        }
+    }
+
+    /// Builds a scalar of the JSON type of the value, so a String "1" stays a string.
+    /// Non-finite floats are written as strings, as JSON has no literal for them.
+    pub fn scalar<T: Display + JsonType>(value: &T) -> Self {
+        let text = value.to_string();
+        match T::json_type() {
+            "boolean" => JsonValue::Bool(text == "true"),
+            "number" if is_number(&text) => JsonValue::Number(text),
+            _ => JsonValue::String(text),
+        }
+    }
+}
+
+impl Display for JsonValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            JsonValue::Null => write!(f, "null"),
+            JsonValue::Bool(b) => write!(f, "{}", b),
+            JsonValue::Number(raw) => write!(f, "{}", raw),
+            JsonValue::String(s) => write!(f, "{}", quote(s)),
+            JsonValue::Array(items) => {
+                write!(f, "[")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                write!(f, "]")
+            }
+            JsonValue::Object(members) => {
+                write!(f, "{{")?;
+                for (i, (name, value)) in members.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}: {}", quote(name), value)?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+/// Returns true if the text is a valid JSON number literal.
+fn is_number(text: &str) -> bool {
+    let bytes = text.as_bytes();
+    let mut i = 0;
+    if bytes.get(i) == Some(&b'-') {
+        i += 1;
+    }
+    match bytes.get(i) {
+        Some(b'0') => i += 1,
+        Some(b'1'..=b'9') => {
+            while bytes.get(i).is_some_and(u8::is_ascii_digit) {
+                i += 1;
+            }
+        }
+        _ => return false,
+    }
+    if bytes.get(i) == Some(&b'.') {
+        i += 1;
+        if !bytes.get(i).is_some_and(u8::is_ascii_digit) {
+            return false;
+        }
+        while bytes.get(i).is_some_and(u8::is_ascii_digit) {
+            i += 1;
+        }
+    }
+    if matches!(bytes.get(i), Some(b'e' | b'E')) {
+        i += 1;
+        if matches!(bytes.get(i), Some(b'+' | b'-')) {
+            i += 1;
+        }
+        if !bytes.get(i).is_some_and(u8::is_ascii_digit) {
+            return false;
+        }
+        while bytes.get(i).is_some_and(u8::is_ascii_digit) {
+            i += 1;
+        }
+    }
+    i == bytes.len()
+}
+
+/// Returns the text as a quoted and escaped JSON string.
+pub fn quote(text: &str) -> String {
+    let mut result = String::with_capacity(text.len() + 2);
+    result.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => result += "\\\"",
+            '\\' => result += "\\\\",
+            '\n' => result += "\\n",
+            '\r' => result += "\\r",
+            '\t' => result += "\\t",
+            '\u{8}' => result += "\\b",
+            '\u{c}' => result += "\\f",
+            c if (c as u32) < 0x20 => result += &format!("\\u{:04x}", c as u32),
+            c => result.push(c),
+        }
+    }
+    result.push('"');
+    result
+}
+
+/// Parses the JSON document.
+/// Returns Err with the position of the first error if the document is malformed
+/// or its arrays and objects are nested too deep.
+pub(crate) fn parse(input: &str) -> Result<JsonValue, String> {
+    let mut parser = Parser {
+        chars: input.char_indices().peekable(),
+        depth: 0,
+    };
+    let value = parser.value()?;
+    parser.skip_whitespace();
+    match parser.chars.next() {
+        None => Ok(value),
+        Some((i, c)) => Err(format!("unexpected '{}' at position {}", c, i)),
+    }
+}
+
+/// Parses a JSON object, e.g. a request body, into its scalar members
+/// (strings, numbers and booleans) in their textual form. Other members are left out.
+/// Returns Err if the document is malformed or isn't an object.
+pub fn parse_members(input: &str) -> Result<Vec<(String, String)>, String> {
+    match parse(input)? {
+        JsonValue::Object(members) => Ok(members
+            .into_iter()
+            .filter_map(|(name, value)| Some((name, value.as_scalar()?)))
+            .collect()),
+        _ => Err(String::from("expected an object")),
+    }
+}
+
+struct Parser<'a> {
+    chars: std::iter::Peekable<std::str::CharIndices<'a>>,
+    /// Number of arrays and objects the parser is in.
+    depth: usize,
+}
+
+impl Parser<'_> {
+    fn skip_whitespace(&mut self) {
+        while self
+            .chars
+            .next_if(|(_, c)| matches!(c, ' ' | '\t' | '\n' | '\r'))
+            .is_some()
+        {}
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), String> {
+        self.skip_whitespace();
+        match self.chars.next() {
+            Some((_, c)) if c == expected => Ok(()),
+            Some((i, c)) => Err(format!(
+                "expected '{}', found '{}' at position {}",
+                expected, c, i
+            )),
+            None => Err(format!("expected '{}', found end of input", expected)),
+        }
+    }
+
+    fn literal(&mut self, word: &str, value: JsonValue) -> Result<JsonValue, String> {
+        for expected in word.chars() {
+            match self.chars.next() {
+                Some((_, c)) if c == expected => {}
+                _ => return Err(format!("invalid literal, expected '{}'", word)),
+            }
+        }
+        Ok(value)
+    }
+
+    fn value(&mut self) -> Result<JsonValue, String> {
+        self.skip_whitespace();
+        match self.chars.peek().copied() {
+            Some((_, c @ ('{' | '['))) => {
+                if self.depth == MAX_DEPTH {
+                    return Err(format!(
+                        "arrays and objects are nested deeper than {}",
+                        MAX_DEPTH
+                    ));
+                }
+                self.depth += 1;
+                let value = if c == '{' {
+                    self.object()
+                } else {
+                    self.array()
+                };
+                self.depth -= 1;
+                value
+            }
+            Some((_, '"')) => Ok(JsonValue::String(self.string()?)),
+            Some((_, 't')) => self.literal("true", JsonValue::Bool(true)),
+            Some((_, 'f')) => self.literal("false", JsonValue::Bool(false)),
+            Some((_, 'n')) => self.literal("null", JsonValue::Null),
+            Some((_, c)) if c == '-' || c.is_ascii_digit() => self.number(),
+            Some((i, c)) => Err(format!("unexpected '{}' at position {}", c, i)),
+            None => Err(String::from("unexpected end of input")),
+        }
+    }
+
+    fn object(&mut self) -> Result<JsonValue, String> {
+        self.expect('{')?;
+        let mut members = Vec::new();
+        self.skip_whitespace();
+        if self.chars.next_if(|(_, c)| *c == '}').is_some() {
+            return Ok(JsonValue::Object(members));
+        }
+        loop {
+            self.skip_whitespace();
+            if self.chars.peek().map(|(_, c)| *c) != Some('"') {
+                return Err(String::from("expected object member name"));
+            }
+            let name = self.string()?;
+            self.expect(':')?;
+            members.push((name, self.value()?));
+            self.skip_whitespace();
+            match self.chars.next() {
+                Some((_, ',')) => continue,
+                Some((_, '}')) => return Ok(JsonValue::Object(members)),
+                _ => return Err(String::from("expected ',' or '}' in object")),
+            }
+        }
+    }
+
+    fn array(&mut self) -> Result<JsonValue, String> {
+        self.expect('[')?;
+        let mut items = Vec::new();
+        self.skip_whitespace();
+        if self.chars.next_if(|(_, c)| *c == ']').is_some() {
+            return Ok(JsonValue::Array(items));
+        }
+        loop {
+            items.push(self.value()?);
+            self.skip_whitespace();
+            match self.chars.next() {
+                Some((_, ',')) => continue,
+                Some((_, ']')) => return Ok(JsonValue::Array(items)),
+                _ => return Err(String::from("expected ',' or ']' in array")),
+            }
+        }
+    }
+
+    fn hex4(&mut self) -> Result<u32, String> {
+        let mut code = 0;
+        for _ in 0..4 {
+            let digit = self
+                .chars
+                .next()
+                .and_then(|(_, c)| c.to_digit(16))
+                .ok_or("invalid unicode escape")?;
+            code = code * 16 + digit;
+        }
+        Ok(code)
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        self.expect('"')?;
+        let mut result = String::new();
+        loop {
+            match self.chars.next() {
+                Some((_, '"')) => return Ok(result),
+                Some((_, '\\')) => {
+                    let c = match self.chars.next() {
+                        Some((_, '"')) => '"',
+                        Some((_, '\\')) => '\\',
+                        Some((_, '/')) => '/',
+                        Some((_, 'b')) => '\u{8}',
+                        Some((_, 'f')) => '\u{c}',
+                        Some((_, 'n')) => '\n',
+                        Some((_, 'r')) => '\r',
+                        Some((_, 't')) => '\t',
+                        Some((_, 'u')) => {
+                            let mut code = self.hex4()?;
+                            if (0xd800..0xdc00).contains(&code) {
+                                self.expect('\\')?;
+                                self.expect('u')?;
+                                let low = self.hex4()?;
+                                if !(0xdc00..0xe000).contains(&low) {
+                                    return Err(String::from("invalid surrogate pair"));
+                                }
+                                code = 0x10000 + ((code - 0xd800) << 10) + (low - 0xdc00);
+                            }
+                            char::from_u32(code).ok_or("invalid unicode escape")?
+                        }
+                        _ => return Err(String::from("invalid escape sequence")),
+                    };
+                    result.push(c);
+                }
+                Some((_, c)) if (c as u32) < 0x20 => {
+                    return Err(String::from("control character in string"))
+                }
+                Some((_, c)) => result.push(c),
+                None => return Err(String::from("unterminated string")),
+            }
+        }
+    }
+
+    fn number(&mut self) -> Result<JsonValue, String> {
+        let mut raw = String::new();
+        while let Some((_, c)) = self
+            .chars
+            .next_if(|(_, c)| c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E'))
+        {
+            raw.push(c);
+        }
+        if !is_number(&raw) {
+            return Err(format!("invalid number '{}'", raw));
+        }
+        Ok(JsonValue::Number(raw))
+    }
+}
+
+fn parse_scalar<T: FromStr>(json: &JsonValue, what: &str) -> Result<T, String> {
+    let raw = json
+        .as_scalar()
+        .ok_or_else(|| format!("{} must be a string, a number or a boolean", what))?;
+    raw.parse()
+        .map_err(|_| format!("couldn't parse {} '{}'", what, raw))
+}
+
+impl<Key, Value, Type> JsonConvertible for Graph<Key, Value, Type>
+where
+    Key: Hash + Eq + Clone + Display + FromStr + JsonType + Ord,
+    Value: Display + FromStr + JsonType,
+    Type: GraphType,
+{
+    /// Deserializes NodeLinkJson into Graph
+    fn from_json(json: NodeLinkJson) -> Result<Self, String> {
+        let raw: String = json.into();
+        let document = parse(&raw)?;
+
+        if let Some(directed) = document.get("directed") {
+            let directed = directed.as_bool().ok_or("\"directed\" must be a boolean")?;
+            if directed != Type::is_directed() {
+                return Err(format!(
+                    "graph is declared as {}, but {} graph was requested",
+                    if directed { "directed" } else { "undirected" },
+                    Type::type_name()
+                ));
+            }
+        }
+        if document.get("multigraph").and_then(JsonValue::as_bool) == Some(true) {
+            return Err(String::from("multigraphs are not supported"));
+        }
+
+        let nodes = document
+            .get("nodes")
+            .and_then(JsonValue::as_array)
+            .ok_or("document must contain \"nodes\" array")?;
+        let empty = vec![];
+        let links = match document.get("links").or(document.get("edges")) {
+            Some(links) => links.as_array().ok_or("\"links\" must be an array")?,
+            None => &empty,
+        };
+
+        let mut graph: Self = Graph::with_capacity(nodes.len());
+
+        for node in nodes {
+            let id = node.get("id").ok_or("node is missing \"id\"")?;
+            let key: Key = parse_scalar(id, "vertex")?;
+            let value = match node.get("value") {
+                Some(value) => parse_scalar(value, "vertex value")?,
+                None => parse_scalar(&JsonValue::String(String::new()), "vertex value")?,
+            };
+
+            if graph.insert(key, value).is_some() {
+                return Err(format!("vertex {} is declared twice", id));
+            }
+        }
+
+        for link in links {
+            let source = link.get("source").ok_or("link is missing \"source\"")?;
+            let target = link.get("target").ok_or("link is missing \"target\"")?;
+            let from: Key = parse_scalar(source, "vertex")?;
+            let to: Key = parse_scalar(target, "vertex")?;
+            let weight = match link.get("weight") {
+                Some(weight) => weight.as_i64().ok_or("edge weight must be an integer")?,
+                None => 0,
+            };
+
+            graph.link(from, to, weight).map_err(|_| {
+                format!("link {} -> {} references an unknown vertex", source, target)
+            })?;
+        }
+
+        Ok(graph)
+    }
+
+    /// Serializes Graph into NodeLinkJson.
+    /// Nodes and links are sorted by keys, so the output is stable.
+    fn to_json(&self) -> NodeLinkJson {
+        let mut buffer = format!(
+            "{{\n  \"directed\": {},\n  \"multigraph\": false,\n  \"nodes\": [",
+            Type::is_directed()
+        );
+
+        let nodes: Vec<String> = self
+            .sorted_vertices()
+            .into_iter()
+            .map(|vertex| {
+                JsonValue::Object(vec![
+                    (String::from("id"), JsonValue::scalar(vertex.key())),
+                    (String::from("value"), JsonValue::scalar(&vertex.value)),
+                ])
+                .to_string()
+            })
+            .collect();
+        if !nodes.is_empty() {
+            buffer += &format!("\n    {}\n  ", nodes.join(",\n    "));
+        }
+
+        buffer += "],\n  \"links\": [";
+
+        let links: Vec<String> = self
+            .sorted_edges()
+            .into_iter()
+            .map(|edge| {
+                JsonValue::Object(vec![
+                    (String::from("source"), JsonValue::scalar(edge.from())),
+                    (String::from("target"), JsonValue::scalar(edge.to())),
+                    (
+                        String::from("weight"),
+                        JsonValue::Number(edge.weight.to_string()),
+                    ),
+                ])
+                .to_string()
+            })
+            .collect();
+        if !links.is_empty() {
+            buffer += &format!("\n    {}\n  ", links.join(",\n    "));
+        }
+
+        buffer += "]\n}\n";
+
+        buffer.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::{
+        assert_depth_limit,
+        marker::{Directed, Undirected},
+    };
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parse_test() {
+        let value = parse(r#" {"a": [1, -2.5e3, true, null], "b": "x\"\u00e9\ud83d\ude00\n"} "#)
+            .expect("json is valid");
+
+        assert_eq!(
+            value,
+            JsonValue::Object(vec![
+                (
+                    String::from("a"),
+                    JsonValue::Array(vec![
+                        JsonValue::Number(String::from("1")),
+                        JsonValue::Number(String::from("-2.5e3")),
+                        JsonValue::Bool(true),
+                        JsonValue::Null,
+                    ])
+                ),
+                (
+                    String::from("b"),
+                    JsonValue::String(String::from("x\"é😀\n"))
+                ),
+            ])
+        );
+
+        assert_eq!(
+            value.get("b").and_then(JsonValue::as_scalar),
+            Some(String::from("x\"é😀\n"))
+        );
+        assert_eq!(value.get("a").and_then(JsonValue::as_scalar), None);
+        let items = value.get("a").and_then(JsonValue::as_array).unwrap();
+        assert_eq!(items[0].as_i64(), Some(1));
+        assert_eq!(items[1].as_scalar(), Some(String::from("-2.5e3")));
+        assert_eq!(items[2].as_bool(), Some(true));
+        assert_eq!(items[3].as_scalar(), None);
+        assert_eq!(value.get("b").and_then(JsonValue::as_array), None);
+
+        assert_eq!(parse(&value.to_string()), Ok(value));
+    }
+
+    #[test]
+    fn parse_errors_test() {
+        for invalid in [
+            "",
+            "{",
+            "[1,]",
+            "{\"a\" 1}",
+            "01",
+            "\"\\x\"",
+            "tru",
+            "[1] 2",
+        ] {
+            assert!(parse(invalid).is_err(), "'{}' must not be parsed", invalid);
+        }
+
+        assert_depth_limit(|depth| parse(&("[".repeat(depth) + &"]".repeat(depth))));
+        assert_depth_limit(|depth| {
+            let objects = "{\"a\": ".repeat(depth);
+            parse(&(objects + "1" + &"}".repeat(depth)))
+        });
+    }
+
+    #[test]
+    fn parse_members_test() {
+        assert_eq!(
+            parse_members(r#"{"id": "a", "weight": -3, "flag": true, "list": [1], "none": null}"#),
+            Ok(vec![
+                (String::from("id"), String::from("a")),
+                (String::from("weight"), String::from("-3")),
+                (String::from("flag"), String::from("true")),
+            ])
+        );
+        assert_eq!(
+            parse_members("[1]"),
+            Err(String::from("expected an object"))
+        );
+        assert!(parse_members("{").is_err());
+    }
+
+    #[test]
+    fn round_trip_test() {
+        let mut graph: Graph<i32, String, Directed> = Graph::new();
+        graph.insert(1, String::from("say \"hi\"\n"));
+        graph.insert(2, String::from("B"));
+        graph.insert_edge(1, 2, 42).unwrap();
+
+        let json = graph.to_json();
+        parse(&json.to_string()).expect("output must be valid json");
+
+        let restored: Graph<i32, String, Directed> =
+            Graph::from_json(json).expect("json is not valid");
+
+        assert_eq!(restored.len(), 2);
+        assert_eq!(restored.get(&1), Some(&String::from("say \"hi\"\n")));
+        assert_eq!(
+            restored
+                .get_vertex(&1)
+                .unwrap()
+                .get_edge(&2)
+                .unwrap()
+                .weight,
+            42
+        );
+        assert!(restored.get_vertex(&2).unwrap().get_edge(&1).is_none());
+    }
+
+    #[test]
+    fn from_json_undirected_test() {
+        let json: NodeLinkJson = r#"{
+  "directed": false,
+  "nodes": [{"id": "a", "value": 1}, {"id": "b", "value": 2}],
+  "links": [{"source": "a", "target": "b", "weight": 3.0}]
+}"#
+        .into();
+        assert_eq!(json.is_directed(), Ok(false));
+        assert_eq!(
+            NodeLinkJson::from("{\"directed\": true}").is_directed(),
+            Ok(true)
+        );
+
+        let graph: Graph<String, i32, Undirected> =
+            Graph::from_json(json).expect("json is not valid");
+
+        let (a, b) = (String::from("a"), String::from("b"));
+        assert_eq!(graph.get(&b), Some(&2));
+        assert_eq!(
+            graph.get_vertex(&b).unwrap().get_edge(&a).unwrap().weight,
+            3
+        );
+        assert_eq!(graph.edges_count(), 1);
+    }
+
+    #[test]
+    fn from_json_errors_test() {
+        let directed = r#"{"directed": true, "nodes": [], "links": []}"#;
+        assert!(Graph::<i32, String, Undirected>::from_json(directed.into()).is_err());
+
+        let dangling = r#"{"nodes": [{"id": 1}], "links": [{"source": 1, "target": 2}]}"#;
+        assert!(Graph::<i32, String>::from_json(dangling.into()).is_err());
+
+        let bad_key = r#"{"nodes": [{"id": "x"}], "links": []}"#;
+        assert!(Graph::<i32, String>::from_json(bad_key.into()).is_err());
+
+        let bad_weight =
+            r#"{"nodes": [{"id": 1}], "links": [{"source": 1, "target": 1, "weight": 0.5}]}"#;
+        assert!(Graph::<i32, String>::from_json(bad_weight.into()).is_err());
+
+        let huge_weight =
+            r#"{"nodes": [{"id": 1}], "links": [{"source": 1, "target": 1, "weight": 1e19}]}"#;
+        assert!(Graph::<i32, String>::from_json(huge_weight.into()).is_err());
+    }
+
+    #[test]
+    fn as_i64_test() {
+        let number = |raw: &str| JsonValue::Number(String::from(raw)).as_i64();
+
+        assert_eq!(number("-42"), Some(-42));
+        assert_eq!(number("1e3"), Some(1000));
+        assert_eq!(number("-9.223372036854775808e18"), Some(i64::MIN));
+        assert_eq!(number("9.223372036854775808e18"), None);
+        assert_eq!(number("-1e19"), None);
+        assert_eq!(number("1.5"), None);
+    }
+
+    #[test]
+    fn scalar_test() {
+        assert_eq!(
+            JsonValue::scalar(&String::from("1")),
+            JsonValue::String(String::from("1"))
+        );
+        assert_eq!(
+            JsonValue::scalar(&String::from("true")),
+            JsonValue::String(String::from("true"))
+        );
+        assert_eq!(
+            JsonValue::scalar(&String::from("null")),
+            JsonValue::String(String::from("null"))
+        );
+        assert_eq!(
+            JsonValue::scalar(&-7i64),
+            JsonValue::Number(String::from("-7"))
+        );
+        assert_eq!(JsonValue::scalar(&true), JsonValue::Bool(true));
+        assert_eq!(
+            JsonValue::scalar(&f64::NAN),
+            JsonValue::String(String::from("NaN"))
+        );
+
+        let mut graph: Graph<String, String> = Graph::new();
+        graph.insert(String::from("1"), String::from("false"));
+        let json = graph.to_json().to_string();
+        assert!(
+            json.contains(r#"{"id": "1", "value": "false"}"#),
+            "{}",
+            json
+        );
+
+        let restored: Graph<String, String> = Graph::from_json(json.into()).unwrap();
+        assert_eq!(
+            restored.get(&String::from("1")),
+            Some(&String::from("false"))
+        );
+    }
+}
@@ -0,0 +1,419 @@
+//! Layout algorithms that place vertices of a graph on a plane.
+//!
+//! Both algorithms are deterministic: vertices are processed in the order of
+//! their keys, so the same graph always gets the same layout.
+
+use std::{collections::HashMap, hash::Hash};
+
+use crate::{graph::Graph, marker::GraphType};
+
+/// Distance between the layout content and the border of the drawing.
+const MARGIN: f64 = 40.0;
+
+/// Point on a plane. The y axis points down, as in SVG.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
+}
+
+impl Point {
+    pub fn new(x: f64, y: f64) -> Self {
+        Point { x, y }
+    }
+
+    fn distance(&self, other: &Point) -> f64 {
+        (self.x - other.x).hypot(self.y - other.y)
+    }
+}
+
+/// Layout is the result of a layout algorithm.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Layout<Key>
+where
+    Key: Hash + Eq,
+{
+    /// Positions of the centers of vertices.
+    pub positions: HashMap<Key, Point>,
+
+    /// Intermediate points of edges that should not be drawn as straight lines,
+    /// keyed by the source and the destination of the edge.
+    pub bends: HashMap<(Key, Key), Vec<Point>>,
+
+    /// Size of the drawing, including margins.
+    pub width: f64,
+    pub height: f64,
+}
+
+/// ForceOptions configures the Fruchterman-Reingold layout.
+pub struct ForceOptions {
+    /// Size of the drawing, including margins.
+    pub width: f64,
+    pub height: f64,
+
+    /// Number of simulation steps.
+    pub iterations: usize,
+}
+
+impl Default for ForceOptions {
+    fn default() -> Self {
+        ForceOptions {
+            width: 800.0,
+            height: 600.0,
+            iterations: 100,
+        }
+    }
+}
+
+/// LayeredOptions configures the Sugiyama layout.
+pub struct LayeredOptions {
+    /// Horizontal distance between neighbouring vertices of a layer.
+    pub node_spacing: f64,
+
+    /// Vertical distance between layers.
+    pub layer_spacing: f64,
+
+    /// Number of barycenter sweeps used to reduce edge crossings.
+    pub sweeps: usize,
+}
+
+impl Default for LayeredOptions {
+    fn default() -> Self {
+        LayeredOptions {
+            node_spacing: 120.0,
+            layer_spacing: 100.0,
+            sweeps: 8,
+        }
+    }
+}
+
+/// Returns keys sorted in ascending order and edges between their indices.
+/// Loops are left out, because they don't affect layouts.
+fn index<Key, Value, Type>(graph: &Graph<Key, Value, Type>) -> (Vec<&Key>, Vec<(usize, usize)>)
+where
+    Key: Hash + Eq + Clone + Ord,
+    Type: GraphType,
+{
+    let keys: Vec<&Key> = graph.sorted_vertices().iter().map(|v| v.key()).collect();
+    let indices: HashMap<&Key, usize> = keys.iter().enumerate().map(|(i, k)| (*k, i)).collect();
+
+    let edges: Vec<(usize, usize)> = graph
+        .sorted_edges()
+        .into_iter()
+        .map(|e| (indices[e.from()], indices[e.to()]))
+        .filter(|(from, to)| from != to)
+        .collect();
+
+    (keys, edges)
+}
+
+/// Places vertices with the force-directed algorithm of Fruchterman and Reingold:
+/// all vertices repel each other, edges pull their ends together.
+/// Vertices start on a circle and settle down as the simulation cools.
+pub fn force_directed<Key, Value, Type>(
+    graph: &Graph<Key, Value, Type>,
+    options: &ForceOptions,
+) -> Layout<Key>
+where
+    Key: Hash + Eq + Clone + Ord,
+    Type: GraphType,
+{
+    let (keys, edges) = index(graph);
+    let n = keys.len();
+
+    let (min_x, max_x) = (MARGIN, (options.width - MARGIN).max(MARGIN));
+    let (min_y, max_y) = (MARGIN, (options.height - MARGIN).max(MARGIN));
+    let center = Point::new((min_x + max_x) / 2.0, (min_y + max_y) / 2.0);
+    let radius = (max_x - min_x).min(max_y - min_y) / 3.0;
+
+    let mut positions: Vec<Point> = (0..n)
+        .map(|i| {
+            let angle = 2.0 * std::f64::consts::PI * i as f64 / n as f64;
+            Point::new(
+                center.x + radius * angle.cos(),
+                center.y + radius * angle.sin(),
+            )
+        })
+        .collect();
+    if n == 1 {
+        positions[0] = center;
+    }
+
+    let k = ((max_x - min_x) * (max_y - min_y) / n.max(1) as f64)
+        .sqrt()
+        .max(1.0);
+    let initial_temperature = (max_x - min_x).min(max_y - min_y) / 10.0;
+
+    for iteration in 0..options.iterations {
+        let mut displacement = vec![Point::default(); n];
+
+        for i in 0..n {
+            for j in i + 1..n {
+                let (dx, dy, distance) = delta(&positions, i, j);
+                let force = k * k / distance;
+                displacement[i].x += dx / distance * force;
+                displacement[i].y += dy / distance * force;
+                displacement[j].x -= dx / distance * force;
+                displacement[j].y -= dy / distance * force;
+            }
+        }
+
+        for (from, to) in &edges {
+            let (dx, dy, distance) = delta(&positions, *from, *to);
+            let force = distance * distance / k;
+            displacement[*from].x -= dx / distance * force;
+            displacement[*from].y -= dy / distance * force;
+            displacement[*to].x += dx / distance * force;
+            displacement[*to].y += dy / distance * force;
+        }
+
+        let temperature =
+            initial_temperature * (1.0 - iteration as f64 / options.iterations as f64);
+        for (position, displacement) in positions.iter_mut().zip(displacement) {
+            let length = displacement.x.hypot(displacement.y);
+            if length > 0.0 {
+                let step = length.min(temperature) / length;
+                position.x = (position.x + displacement.x * step).clamp(min_x, max_x);
+                position.y = (position.y + displacement.y * step).clamp(min_y, max_y);
+            }
+        }
+    }
+
+    Layout {
+        positions: keys.into_iter().cloned().zip(positions).collect(),
+        bends: HashMap::new(),
+        width: options.width,
+        height: options.height,
+    }
+}
+
+/// Returns the vector from the vertex j to the vertex i and its length.
+/// Vertices at the same point are pushed apart in a fixed direction.
+fn delta(positions: &[Point], i: usize, j: usize) -> (f64, f64, f64) {
+    let (dx, dy) = (
+        positions[i].x - positions[j].x,
+        positions[i].y - positions[j].y,
+    );
+    let distance = positions[i].distance(&positions[j]);
+    if distance < 0.01 {
+        (0.01, 0.01, 0.01 * std::f64::consts::SQRT_2)
+    } else {
+        (dx, dy, distance)
+    }
+}
+
+/// Places vertices of a directed acyclic graph in layers with the Sugiyama method:
+/// every edge points down, edges spanning several layers get bends on the
+/// intermediate layers, and vertices are ordered within layers by the barycenter
+/// heuristic to reduce edge crossings.
+/// Returns Err if the graph is undirected or contains a cycle.
+pub fn layered<Key, Value, Type>(
+    graph: &Graph<Key, Value, Type>,
+    options: &LayeredOptions,
+) -> Result<Layout<Key>, String>
+where
+    Key: Hash + Eq + Clone + Ord,
+    Type: GraphType,
+{
+    if !Type::is_directed() {
+        return Err(String::from("layered layout requires a directed graph"));
+    }
+    if graph.edges().any(|e| e.from() == e.to()) {
+        return Err(String::from("layered layout requires an acyclic graph"));
+    }
+
+    let (keys, edges) = index(graph);
+    let n = keys.len();
+
+    // Assign every vertex to the layer of the longest path leading to it
+    let mut in_degree = vec![0; n];
+    let mut successors = vec![Vec::new(); n];
+    for (from, to) in &edges {
+        in_degree[*to] += 1;
+        successors[*from].push(*to);
+    }
+    let mut queue: Vec<usize> = (0..n).filter(|v| in_degree[*v] == 0).collect();
+    let mut layer_of = vec![0; n];
+    let mut processed = 0;
+    while let Some(v) = queue.pop() {
+        processed += 1;
+        for to in &successors[v] {
+            layer_of[*to] = layer_of[*to].max(layer_of[v] + 1);
+            in_degree[*to] -= 1;
+            if in_degree[*to] == 0 {
+                queue.push(*to);
+            }
+        }
+    }
+    if processed != n {
+        return Err(String::from("layered layout requires an acyclic graph"));
+    }
+
+    // Split long edges with dummy vertices, so that every edge connects neighbouring layers
+    let layers_count = layer_of.iter().max().map_or(0, |l| l + 1);
+    let mut layers: Vec<Vec<usize>> = vec![Vec::new(); layers_count];
+    for (v, layer) in layer_of.iter().enumerate() {
+        layers[*layer].push(v);
+    }
+    let mut upper: Vec<Vec<usize>> = vec![Vec::new(); n];
+    let mut lower: Vec<Vec<usize>> = vec![Vec::new(); n];
+    let mut chains: Vec<(usize, usize, Vec<usize>)> = Vec::new();
+    for (from, to) in &edges {
+        let mut previous = *from;
+        let mut dummies = Vec::new();
+        for layer in &mut layers[layer_of[*from] + 1..layer_of[*to]] {
+            let dummy = upper.len();
+            upper.push(vec![previous]);
+            lower.push(Vec::new());
+            lower[previous].push(dummy);
+            layer.push(dummy);
+            dummies.push(dummy);
+            previous = dummy;
+        }
+        upper[*to].push(previous);
+        lower[previous].push(*to);
+        if !dummies.is_empty() {
+            chains.push((*from, *to, dummies));
+        }
+    }
+
+    // Reorder layers by the average position of the neighbours in the previous layer,
+    // sweeping down and up in turns
+    let mut order = vec![0.0; upper.len()];
+    for layer in &layers {
+        for (i, v) in layer.iter().enumerate() {
+            order[*v] = i as f64;
+        }
+    }
+    for sweep in 0..options.sweeps {
+        let (range, neighbours): (Vec<usize>, _) = if sweep % 2 == 0 {
+            ((1..layers_count).collect(), &upper)
+        } else {
+            ((0..layers_count.saturating_sub(1)).rev().collect(), &lower)
+        };
+        for layer in range {
+            let barycenters: HashMap<usize, f64> = layers[layer]
+                .iter()
+                .map(|v| {
+                    let adjacent = &neighbours[*v];
+                    let barycenter = if adjacent.is_empty() {
+                        order[*v]
+                    } else {
+                        adjacent.iter().map(|u| order[*u]).sum::<f64>() / adjacent.len() as f64
+                    };
+                    (*v, barycenter)
+                })
+                .collect();
+            layers[layer].sort_by(|a, b| barycenters[a].total_cmp(&barycenters[b]));
+            for (i, v) in layers[layer].iter().enumerate() {
+                order[*v] = i as f64;
+            }
+        }
+    }
+
+    // Center layers horizontally
+    let widest = layers.iter().map(Vec::len).max().unwrap_or(0);
+    let mut points = vec![Point::default(); upper.len()];
+    for (l, layer) in layers.iter().enumerate() {
+        let offset = (widest - layer.len()) as f64 * options.node_spacing / 2.0;
+        for (i, v) in layer.iter().enumerate() {
+            points[*v] = Point::new(
+                MARGIN + offset + i as f64 * options.node_spacing,
+                MARGIN + l as f64 * options.layer_spacing,
+            );
+        }
+    }
+
+    Ok(Layout {
+        positions: keys
+            .iter()
+            .map(|k| (*k).clone())
+            .zip(points.clone())
+            .collect(),
+        bends: chains
+            .into_iter()
+            .map(|(from, to, dummies)| {
+                let bends = dummies.iter().map(|d| points[*d]).collect();
+                ((keys[from].clone(), keys[to].clone()), bends)
+            })
+            .collect(),
+        width: 2.0 * MARGIN + widest.saturating_sub(1) as f64 * options.node_spacing,
+        height: 2.0 * MARGIN + layers_count.saturating_sub(1) as f64 * options.layer_spacing,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::marker::{Directed, Undirected};
+    use pretty_assertions::assert_eq;
+
+    fn dag() -> Graph<char, (), Directed> {
+        let mut graph: Graph<char, (), Directed> = Graph::new();
+        for key in ['a', 'b', 'c', 'd'] {
+            graph.insert(key, ());
+        }
+        graph.insert_edge('a', 'b', 0).unwrap();
+        graph.insert_edge('a', 'c', 0).unwrap();
+        graph.insert_edge('b', 'd', 0).unwrap();
+        graph.insert_edge('a', 'd', 0).unwrap();
+        graph
+    }
+
+    #[test]
+    fn layered_test() {
+        let layout = layered(&dag(), &LayeredOptions::default()).expect("graph is acyclic");
+
+        let y = |key: char| layout.positions[&key].y;
+        assert!(y('a') < y('b'));
+        assert_eq!(y('b'), y('c'));
+        assert!(y('b') < y('d'));
+
+        // a -> d skips the layer of b and c
+        let bends = &layout.bends[&('a', 'd')];
+        assert_eq!(bends.len(), 1);
+        assert_eq!(bends[0].y, y('b'));
+        assert_eq!(layout.bends.len(), 1);
+
+        assert_eq!(layout.height, 2.0 * MARGIN + 200.0);
+        assert!(layout.positions.values().all(|p| p.x <= layout.width));
+
+        let mut cyclic = dag();
+        cyclic.insert_edge('d', 'a', 0).unwrap();
+        assert!(layered(&cyclic, &LayeredOptions::default()).is_err());
+
+        let undirected: Graph<char, (), Undirected> = Graph::new();
+        assert!(layered(&undirected, &LayeredOptions::default()).is_err());
+    }
+
+    #[test]
+    fn force_directed_test() {
+        let mut graph: Graph<i32, (), Undirected> = Graph::new();
+        for key in 0..6 {
+            graph.insert(key, ());
+        }
+        for key in 0..5 {
+            graph.insert_edge(key, key + 1, 0).unwrap();
+        }
+
+        let options = ForceOptions::default();
+        let layout = force_directed(&graph, &options);
+        assert_eq!(layout.positions.len(), 6);
+        for point in layout.positions.values() {
+            assert!((MARGIN..=options.width - MARGIN).contains(&point.x));
+            assert!((MARGIN..=options.height - MARGIN).contains(&point.y));
+        }
+        for i in 0..6 {
+            for j in i + 1..6 {
+                assert!(layout.positions[&i].distance(&layout.positions[&j]) > 1.0);
+            }
+        }
+
+        // Neighbours end up closer to each other than the ends of the path
+        let distance = |a: i32, b: i32| layout.positions[&a].distance(&layout.positions[&b]);
+        assert!(distance(0, 1) < distance(0, 5));
+
+        assert_eq!(layout, force_directed(&graph, &options));
+    }
+}
@@ -1,5 +1,45 @@
+pub mod ascii;
+pub mod binary;
+pub mod csv;
+pub mod diagram;
+pub mod dimacs;
+pub mod dot;
 pub mod edge;
+pub mod generators;
+pub mod gexf;
+pub mod gml;
 pub mod graph;
+pub mod graph6;
+pub mod graphml;
+pub mod json;
+pub mod layout;
 pub mod marker;
+pub mod matrix_market;
+pub mod pajek;
+pub mod query;
+pub mod svg;
 pub mod tgf;
 pub mod vertex;
+
+mod xml;
+
+/// Parsers reject documents nested deeper than this, because parsing or dropping
+/// them recurses once per level and a hostile document could overflow the stack.
+pub(crate) const MAX_DEPTH: usize = 128;
+
+/// Checks that the parser accepts a document nested MAX_DEPTH levels deep
+/// and rejects deeper ones. The closure builds the document of the depth and parses it.
+#[cfg(test)]
+pub(crate) fn assert_depth_limit<T, E>(parse: impl Fn(usize) -> Result<T, E>) {
+    assert!(
+        parse(MAX_DEPTH).is_ok(),
+        "{} levels must be parsed",
+        MAX_DEPTH
+    );
+    assert!(
+        parse(MAX_DEPTH + 1).is_err(),
+        "{} levels must be rejected",
+        MAX_DEPTH + 1
+    );
+    assert!(parse(200_000).is_err(), "200000 levels must be rejected");
+}
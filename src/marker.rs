@@ -1,18 +1,31 @@
 /// GraphType trait is used to constarint the type of graph and acts as a marker.
 pub trait GraphType {
     fn type_name() -> &'static str;
+
+    /// Returns true if edges of the graph have a direction.
+    fn is_directed() -> bool;
 }
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Directed;
 impl GraphType for Directed {
     fn type_name() -> &'static str {
         "Directed"
     }
+
+    fn is_directed() -> bool {
+        true
+    }
 }
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Undirected;
 impl GraphType for Undirected {
     fn type_name() -> &'static str {
         "Undirected"
     }
+
+    fn is_directed() -> bool {
+        false
+    }
 }
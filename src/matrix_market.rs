@@ -0,0 +1,245 @@
+//! Reader and writer for the Matrix Market coordinate format.
+//! See <https://math.nist.gov/MatrixMarket/formats.html>.
+//!
+//! An entry in row `i` and column `j` is an edge from vertex `i` to vertex `j`,
+//! so a M x N matrix is read into `Graph<usize, Value, Directed>` with vertices
+//! numbered from 1 to max(M, N) and entries stored in `Edge::weight`.
+
+use crate::{
+    dimacs::{check_keys, field},
+    edge::parse_weight,
+    graph::Graph,
+    marker::Directed,
+};
+
+#[derive(Clone, Copy, PartialEq)]
+enum Field {
+    Integer,
+    Real,
+    Pattern,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Symmetry {
+    General,
+    Symmetric,
+    SkewSymmetric,
+}
+
+fn parse_header(line: &str) -> Result<(Field, Symmetry), String> {
+    let tokens: Vec<String> = line.split_whitespace().map(str::to_lowercase).collect();
+    let tokens: Vec<&str> = tokens.iter().map(String::as_str).collect();
+
+    match tokens.as_slice() {
+        ["%%matrixmarket", "matrix", format, field, symmetry] => {
+            if *format != "coordinate" {
+                return Err(format!(
+                    "only coordinate format is supported, found '{}'",
+                    format
+                ));
+            }
+            let field = match *field {
+                "integer" => Field::Integer,
+                "real" | "double" => Field::Real,
+                "pattern" => Field::Pattern,
+                other => return Err(format!("unsupported field '{}'", other)),
+            };
+            let symmetry = match *symmetry {
+                "general" => Symmetry::General,
+                "symmetric" => Symmetry::Symmetric,
+                "skew-symmetric" => Symmetry::SkewSymmetric,
+                other => return Err(format!("unsupported symmetry '{}'", other)),
+            };
+            if field == Field::Pattern && symmetry == Symmetry::SkewSymmetric {
+                return Err(String::from("pattern matrix can't be skew-symmetric"));
+            }
+            Ok((field, symmetry))
+        }
+        _ => Err(String::from("expected '%%MatrixMarket matrix' header")),
+    }
+}
+
+/// Reads the graph from the Matrix Market coordinate file.
+/// Entries of real matrices must be integers, because weights of edges are integers.
+/// Entries of pattern matrices become unweighted edges.
+pub fn read_matrix_market<Value: Default>(
+    input: &str,
+) -> Result<Graph<usize, Value, Directed>, String> {
+    let mut lines = input.lines().enumerate();
+
+    let (field_type, symmetry) = parse_header(lines.next().map(|l| l.1).unwrap_or(""))?;
+
+    let mut lines = lines.filter(|(_, line)| {
+        let line = line.trim();
+        !line.is_empty() && !line.starts_with('%')
+    });
+
+    let (size_line_no, size_line) = lines.next().ok_or("size line is missing")?;
+    let mut tokens = size_line.split_whitespace();
+    let rows: usize = field(&mut tokens, size_line_no + 1, "number of rows")?;
+    let columns: usize = field(&mut tokens, size_line_no + 1, "number of columns")?;
+    let entries: usize = field(&mut tokens, size_line_no + 1, "number of entries")?;
+    if symmetry != Symmetry::General && rows != columns {
+        return Err(String::from("symmetric matrix must be square"));
+    }
+
+    let n = rows.max(columns);
+    let mut graph: Graph<usize, Value, Directed> = Graph::try_with_capacity(n)
+        .map_err(|_| format!("matrix with {} rows and columns is too large", n))?;
+    for key in 1..=n {
+        graph.insert(key, Value::default());
+    }
+
+    let mut count = 0;
+    for (i, line) in lines {
+        let line_no = i + 1;
+        let mut tokens = line.split_whitespace();
+        let row: usize = field(&mut tokens, line_no, "row")?;
+        let column: usize = field(&mut tokens, line_no, "column")?;
+        if !(1..=rows).contains(&row) || !(1..=columns).contains(&column) {
+            return Err(format!("line {}: entry is out of bounds", line_no));
+        }
+
+        let weight = match field_type {
+            Field::Pattern => 0,
+            Field::Integer => field(&mut tokens, line_no, "entry")?,
+            Field::Real => {
+                let raw: String = field(&mut tokens, line_no, "entry")?;
+                parse_weight(&raw).map_err(|e| format!("line {}: {}", line_no, e))?
+            }
+        };
+
+        graph
+            .insert_edge(row, column, weight)
+            .expect("graph must contain src and dst vertecies");
+        if row != column {
+            match symmetry {
+                Symmetry::General => {}
+                Symmetry::Symmetric => {
+                    graph
+                        .insert_edge(column, row, weight)
+                        .expect("graph must contain src and dst vertecies");
+                }
+                Symmetry::SkewSymmetric => {
+                    let negated = weight.checked_neg().ok_or_else(|| {
+                        format!("line {}: entry {} can't be negated", line_no, weight)
+                    })?;
+                    graph
+                        .insert_edge(column, row, negated)
+                        .expect("graph must contain src and dst vertecies");
+                }
+            }
+        }
+        count += 1;
+    }
+
+    if count != entries {
+        return Err(format!(
+            "size line declares {} entries, but {} were found",
+            entries, count
+        ));
+    }
+
+    Ok(graph)
+}
+
+/// Writes the graph as a square general integer matrix.
+/// Returns Err if the graph is not keyed by 1..=n.
+pub fn write_matrix_market<Value>(graph: &Graph<usize, Value, Directed>) -> Result<String, String> {
+    let n = check_keys(graph)?;
+
+    let mut buffer = String::from("%%MatrixMarket matrix coordinate integer general\n");
+    buffer += &format!("{} {} {}\n", n, n, graph.edges_count());
+
+    for key in 1..=n {
+        let vertex = graph.get_vertex(&key).expect("key must exist");
+        for edge in vertex.adjancency_list() {
+            buffer += &format!("{} {} {}\n", edge.from(), edge.to(), edge.weight);
+        }
+    }
+
+    Ok(buffer)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn read_test() {
+        let input =
+            "%%MatrixMarket matrix coordinate real symmetric\n% comment\n3 3 2\n2 1 4.0\n3 3 -1\n";
+
+        let graph: Graph<usize, ()> = read_matrix_market(input).expect("file is not valid");
+        assert_eq!(graph.len(), 3);
+        assert_eq!(graph.edges_count(), 3);
+        assert_eq!(
+            graph.get_vertex(&1).unwrap().get_edge(&2).unwrap().weight,
+            4
+        );
+        assert_eq!(
+            graph.get_vertex(&3).unwrap().get_edge(&3).unwrap().weight,
+            -1
+        );
+
+        let skew = "%%MatrixMarket matrix coordinate integer skew-symmetric\n2 2 1\n2 1 5\n";
+        let graph: Graph<usize, ()> = read_matrix_market(skew).expect("file is not valid");
+        assert_eq!(
+            graph.get_vertex(&1).unwrap().get_edge(&2).unwrap().weight,
+            -5
+        );
+
+        let pattern = "%%MatrixMarket matrix coordinate pattern general\n2 3 1\n1 3\n";
+        let graph: Graph<usize, ()> = read_matrix_market(pattern).expect("file is not valid");
+        assert_eq!(graph.len(), 3);
+        assert!(graph.get_vertex(&1).unwrap().get_edge(&3).is_some());
+    }
+
+    #[test]
+    fn round_trip_test() {
+        let mut graph: Graph<usize, ()> = Graph::new();
+        for key in 1..=3 {
+            graph.insert(key, ());
+        }
+        graph.insert_edge(1, 2, 10).unwrap();
+        graph.insert_edge(3, 1, -2).unwrap();
+
+        let output = write_matrix_market(&graph).expect("graph is keyed by 1..=n");
+        let restored: Graph<usize, ()> = read_matrix_market(&output).expect("file is not valid");
+        assert_eq!(restored.edges_count(), 2);
+        assert_eq!(
+            restored
+                .get_vertex(&3)
+                .unwrap()
+                .get_edge(&1)
+                .unwrap()
+                .weight,
+            -2
+        );
+    }
+
+    #[test]
+    fn errors_test() {
+        let invalid = [
+            "",
+            "%%MatrixMarket matrix array real general\n1 1\n1\n",
+            "%%MatrixMarket matrix coordinate complex general\n1 1 0\n",
+            "%%MatrixMarket matrix coordinate real general\n2 2 1\n1 2 0.5\n",
+            "%%MatrixMarket matrix coordinate real general\n2 2 1\n1 3 1\n",
+            "%%MatrixMarket matrix coordinate real general\n2 2 2\n1 2 1\n",
+            "%%MatrixMarket matrix coordinate real symmetric\n2 3 0\n",
+            "%%MatrixMarket matrix coordinate real general\n2 2 1\n1 2 1e19\n",
+            "%%MatrixMarket matrix coordinate pattern general\n18446744073709551615 1 0\n",
+            "%%MatrixMarket matrix coordinate integer skew-symmetric\n2 2 1\n2 1 -9223372036854775808\n",
+        ];
+        for input in invalid {
+            assert!(
+                read_matrix_market::<()>(input).is_err(),
+                "'{}' must be rejected",
+                input
+            );
+        }
+    }
+}
@@ -0,0 +1,455 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Display,
+    hash::Hash,
+    str::FromStr,
+};
+
+use crate::{edge::parse_weight, graph::Graph, marker::GraphType};
+
+/// PajekNet is the `.net` format of the Pajek network analysis program.
+/// See <http://mrvar.fdv.uni-lj.si/pajek/DrawEPS.htm>.
+///
+/// Vertices are numbered from 1 to n and the numbers are read as vertex keys,
+/// labels are read as vertex values. Vertices up to the number in `*Vertices n`
+/// that are not listed get empty labels. Quotes and backslashes in quoted labels
+/// are escaped with a backslash. Edges listed in `*Arcs` sections are
+/// directed, in `*Edges` sections are undirected.
+pub struct PajekNet(String);
+
+impl From<String> for PajekNet {
+    fn from(value: String) -> Self {
+        PajekNet(value)
+    }
+}
+
+impl<'a> From<&'a str> for PajekNet {
+    fn from(value: &'a str) -> PajekNet {
+        PajekNet(String::from(value))
+    }
+}
+
+impl From<PajekNet> for String {
+    fn from(value: PajekNet) -> Self {
+        value.0
+    }
+}
+
+impl Display for PajekNet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl PajekNet {
+    /// Returns true if the network contains arcs, false if it contains edges.
+    /// Allows choosing the type of the graph before converting.
+    /// Returns Err if the network contains both arcs and edges.
+    pub fn is_directed(&self) -> Result<bool, String> {
+        Ok(parse(&self.0)?.directed)
+    }
+}
+
+/// PajekConvertible is a trait that allows converting from and to PajekNet.
+pub trait PajekConvertible {
+    fn to_pajek(&self) -> PajekNet;
+
+    fn from_pajek(pajek: PajekNet) -> Result<Self, String>
+    where
+        Self: Sized;
+}
+
+/// Vertices and lines of a parsed network.
+struct Network<'a> {
+    vertices: Vec<(String, String)>,
+    lines: Vec<(&'a str, &'a str, i64)>,
+    directed: bool,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Section {
+    None,
+    Vertices,
+    Arcs,
+    Edges,
+    ArcsList,
+    EdgesList,
+}
+
+/// Splits the vertex line into its number and its label.
+/// Labels may be quoted, coordinates and shapes after the label are ignored.
+fn split_vertex(line: &str) -> Result<(String, String), String> {
+    let line = line.trim();
+    let (id, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+    let rest = rest.trim_start();
+
+    let label = if let Some(quoted) = rest.strip_prefix('"') {
+        let mut label = String::new();
+        let mut chars = quoted.chars();
+        loop {
+            match chars.next() {
+                Some('"') => break,
+                Some('\\') => match chars.next() {
+                    Some(c @ ('"' | '\\')) => label.push(c),
+                    Some(c) => {
+                        label.push('\\');
+                        label.push(c);
+                    }
+                    None => label.push('\\'),
+                },
+                Some(c) => label.push(c),
+                None => return Err(format!("unterminated label of vertex {}", id)),
+            }
+        }
+        label
+    } else {
+        String::from(rest.split_whitespace().next().unwrap_or(""))
+    };
+
+    Ok((String::from(id), label))
+}
+
+/// Returns the label as a quoted Pajek label.
+fn quote(label: &str) -> String {
+    format!("\"{}\"", label.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn parse(input: &str) -> Result<Network<'_>, String> {
+    let mut section = Section::None;
+    let mut network = Network {
+        vertices: Vec::new(),
+        lines: Vec::new(),
+        directed: true,
+    };
+    let mut has_arcs = false;
+    let mut has_edges = false;
+    let mut declared: usize = 0;
+
+    for (i, line) in input.lines().enumerate() {
+        let line_no = i + 1;
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('%') {
+            continue;
+        }
+
+        if let Some(header) = trimmed.strip_prefix('*') {
+            let mut words = header.split_whitespace();
+            let name = words.next().unwrap_or("").to_lowercase();
+            if name == "vertices" {
+                declared = match words.next() {
+                    Some(raw) => raw.parse().map_err(|_| {
+                        format!("line {}: invalid number of vertices '{}'", line_no, raw)
+                    })?,
+                    None => 0,
+                };
+            }
+            section = match name.as_str() {
+                "network" => Section::None,
+                "vertices" => Section::Vertices,
+                "arcs" => Section::Arcs,
+                "edges" => Section::Edges,
+                "arcslist" => Section::ArcsList,
+                "edgeslist" => Section::EdgesList,
+                other => return Err(format!("line {}: unknown section '*{}'", line_no, other)),
+            };
+            continue;
+        }
+
+        let mut tokens = trimmed.split_whitespace();
+        match section {
+            Section::None => {
+                return Err(format!("line {}: expected a section header", line_no));
+            }
+            Section::Vertices => network.vertices.push(split_vertex(trimmed)?),
+            Section::Arcs | Section::Edges => {
+                let (from, to) = match (tokens.next(), tokens.next()) {
+                    (Some(from), Some(to)) => (from, to),
+                    _ => return Err(format!("line {}: expected two vertices", line_no)),
+                };
+                let weight = match tokens.next() {
+                    Some(raw) => {
+                        parse_weight(raw).map_err(|e| format!("line {}: {}", line_no, e))?
+                    }
+                    None => 0,
+                };
+                network.lines.push((from, to, weight));
+            }
+            Section::ArcsList | Section::EdgesList => {
+                let from = tokens.next().expect("line is not empty");
+                for to in tokens {
+                    network.lines.push((from, to, 0));
+                }
+            }
+        }
+
+        match section {
+            Section::Arcs | Section::ArcsList => has_arcs = true,
+            Section::Edges | Section::EdgesList => has_edges = true,
+            _ => {}
+        }
+    }
+
+    if has_arcs && has_edges {
+        return Err(String::from(
+            "networks with both arcs and edges are not supported",
+        ));
+    }
+    network.directed = !has_edges;
+
+    let listed: HashSet<usize> = network
+        .vertices
+        .iter()
+        .filter_map(|(id, _)| id.parse().ok())
+        .collect();
+    network
+        .vertices
+        .try_reserve(declared.saturating_sub(listed.len()))
+        .map_err(|_| format!("network with {} vertices is too large", declared))?;
+    for number in 1..=declared {
+        if !listed.contains(&number) {
+            network.vertices.push((number.to_string(), String::new()));
+        }
+    }
+
+    Ok(network)
+}
+
+impl<Key, Value, Type> PajekConvertible for Graph<Key, Value, Type>
+where
+    Key: Hash + Eq + Clone + Display + FromStr + Ord,
+    Value: Display + FromStr,
+    Type: GraphType,
+{
+    /// Deserializes PajekNet into Graph
+    fn from_pajek(pajek: PajekNet) -> Result<Self, String> {
+        let raw: String = pajek.into();
+        let network = parse(&raw)?;
+
+        if !network.lines.is_empty() && network.directed != Type::is_directed() {
+            return Err(format!(
+                "network contains {}, but {} graph was requested",
+                if network.directed { "arcs" } else { "edges" },
+                Type::type_name()
+            ));
+        }
+
+        let mut graph: Self = Graph::with_capacity(network.vertices.len());
+
+        for (id, label) in network.vertices {
+            let key: Key = id
+                .parse()
+                .map_err(|_| format!("couldn't parse vertex '{}'", id))?;
+            let value = label
+                .parse()
+                .map_err(|_| format!("couldn't parse value '{}' of vertex '{}'", label, id))?;
+
+            if graph.insert(key, value).is_some() {
+                return Err(format!("vertex '{}' is declared twice", id));
+            }
+        }
+
+        for (source, target, weight) in network.lines {
+            let from: Key = source
+                .parse()
+                .map_err(|_| format!("couldn't parse vertex '{}'", source))?;
+            let to: Key = target
+                .parse()
+                .map_err(|_| format!("couldn't parse vertex '{}'", target))?;
+
+            graph.link(from, to, weight).map_err(|_| {
+                format!("line {} -> {} references an unknown vertex", source, target)
+            })?;
+        }
+
+        Ok(graph)
+    }
+
+    /// Serializes Graph into PajekNet.
+    /// Keys are kept if they are the numbers from 1 to n,
+    /// otherwise vertices are renumbered in the order of their keys.
+    fn to_pajek(&self) -> PajekNet {
+        let n = self.len();
+        let sorted = self.sorted_vertices();
+        let mut numbers: HashMap<&Key, usize> = HashMap::with_capacity(n);
+        for vertex in &sorted {
+            match vertex.key().to_string().parse::<usize>() {
+                Ok(number) if (1..=n).contains(&number) => {
+                    numbers.insert(vertex.key(), number);
+                }
+                _ => break,
+            }
+        }
+        let mut used: Vec<usize> = numbers.values().copied().collect();
+        used.sort_unstable();
+        used.dedup();
+        if used.len() != n {
+            numbers = sorted
+                .iter()
+                .enumerate()
+                .map(|(i, v)| (v.key(), i + 1))
+                .collect();
+        }
+
+        let mut vertices: Vec<(usize, &Value)> = sorted
+            .iter()
+            .map(|v| (numbers[v.key()], &v.value))
+            .collect();
+        vertices.sort_unstable_by_key(|(number, _)| *number);
+
+        let mut buffer = format!("*Vertices {}\n", n);
+        for (number, value) in vertices {
+            buffer += &format!("{} {}\n", number, quote(&value.to_string()));
+        }
+
+        buffer += if Type::is_directed() {
+            "*Arcs\n"
+        } else {
+            "*Edges\n"
+        };
+        for edge in self.sorted_edges() {
+            buffer += &format!(
+                "{} {} {}\n",
+                numbers[edge.from()],
+                numbers[edge.to()],
+                edge.weight
+            );
+        }
+
+        buffer.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::marker::{Directed, Undirected};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn from_pajek_test() {
+        let pajek: PajekNet = r#"% comment
+*Network test
+*Vertices 3
+1 "Node A" 0.1 0.2 0.5
+2 B
+3
+*Arcs
+1 2 5
+2 3
+*Arcslist
+3 1 2
+"#
+        .into();
+        assert_eq!(pajek.is_directed(), Ok(true));
+
+        let graph: Graph<usize, String, Directed> =
+            Graph::from_pajek(pajek).expect("network is not valid");
+        assert_eq!(graph.get(&1), Some(&String::from("Node A")));
+        assert_eq!(graph.get(&2), Some(&String::from("B")));
+        assert_eq!(graph.get(&3), Some(&String::new()));
+        assert_eq!(graph.edges_count(), 4);
+        assert_eq!(
+            graph.get_vertex(&1).unwrap().get_edge(&2).unwrap().weight,
+            5
+        );
+    }
+
+    #[test]
+    fn round_trip_test() {
+        let mut graph: Graph<String, String, Undirected> = Graph::new();
+        graph.insert(String::from("a"), String::from("A"));
+        graph.insert(String::from("b"), String::from("B"));
+        graph
+            .insert_edge(String::from("a"), String::from("b"), 7)
+            .unwrap();
+
+        let pajek = graph.to_pajek();
+        assert_eq!(pajek.is_directed(), Ok(false));
+
+        let restored: Graph<usize, String, Undirected> =
+            Graph::from_pajek(pajek).expect("network is not valid");
+        assert_eq!(restored.len(), 2);
+        assert_eq!(restored.edges_count(), 1);
+        assert_eq!(
+            restored
+                .get_vertex(&1)
+                .unwrap()
+                .get_edge(&2)
+                .unwrap()
+                .weight,
+            7
+        );
+
+        // Keys that are not numbers are renumbered in their order
+        let mut named: Graph<String, String> = Graph::new();
+        named.insert(String::from("b"), String::from("B"));
+        named.insert(String::from("a"), String::from("A"));
+        named
+            .insert_edge(String::from("b"), String::from("a"), 1)
+            .unwrap();
+        assert_eq!(
+            named.to_pajek().to_string(),
+            "*Vertices 2\n1 \"A\"\n2 \"B\"\n*Arcs\n2 1 1\n"
+        );
+
+        let mut numbered: Graph<usize, String> = Graph::new();
+        numbered.insert(2, String::from("two"));
+        numbered.insert(1, String::from("one"));
+        let restored: Graph<usize, String> =
+            Graph::from_pajek(numbered.to_pajek()).expect("network is not valid");
+        assert_eq!(restored.get(&2), Some(&String::from("two")));
+
+        let mut quoted: Graph<usize, String> = Graph::new();
+        quoted.insert(1, String::from("say \"hi\" \\o/"));
+        let pajek = quoted.to_pajek();
+        assert_eq!(
+            pajek.to_string(),
+            "*Vertices 1\n1 \"say \\\"hi\\\" \\\\o/\"\n*Arcs\n"
+        );
+        let restored: Graph<usize, String> =
+            Graph::from_pajek(pajek).expect("network is not valid");
+        assert_eq!(restored.get(&1), Some(&String::from("say \"hi\" \\o/")));
+    }
+
+    #[test]
+    fn unlisted_vertices_test() {
+        let pajek: PajekNet = "*Vertices 3\n*Arcs\n1 3\n".into();
+        let graph: Graph<usize, String, Directed> =
+            Graph::from_pajek(pajek).expect("network is not valid");
+
+        assert_eq!(graph.len(), 3);
+        assert_eq!(graph.get(&2), Some(&String::new()));
+        assert!(graph.get_vertex(&1).unwrap().get_edge(&3).is_some());
+
+        let partial: PajekNet = "*Vertices 2\n2 \"B\"\n".into();
+        let graph: Graph<usize, String, Directed> =
+            Graph::from_pajek(partial).expect("network is not valid");
+        assert_eq!(graph.len(), 2);
+        assert_eq!(graph.get(&2), Some(&String::from("B")));
+    }
+
+    #[test]
+    fn errors_test() {
+        let mixed = "*Vertices 2\n1\n2\n*Arcs\n1 2\n*Edges\n1 2\n";
+        assert!(PajekNet::from(mixed).is_directed().is_err());
+
+        let edges = "*Vertices 2\n1\n2\n*Edges\n1 2\n";
+        assert!(Graph::<usize, String, Directed>::from_pajek(edges.into()).is_err());
+
+        let dangling = "*Vertices 1\n1\n*Arcs\n1 2\n";
+        assert!(Graph::<usize, String, Directed>::from_pajek(dangling.into()).is_err());
+
+        let bad_count = "*Vertices x\n";
+        assert!(Graph::<usize, String, Directed>::from_pajek(bad_count.into()).is_err());
+
+        let no_section = "1 2\n";
+        assert!(Graph::<usize, String, Directed>::from_pajek(no_section.into()).is_err());
+
+        let too_large = "*Vertices 18446744073709551615\n";
+        assert!(Graph::<usize, String, Directed>::from_pajek(too_large.into()).is_err());
+
+        let huge_weight = "*Vertices 2\n*Arcs\n1 2 1e30\n";
+        assert!(Graph::<usize, String, Directed>::from_pajek(huge_weight.into()).is_err());
+    }
+}
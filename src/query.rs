@@ -0,0 +1,1170 @@
+//! A small Cypher-like query language for matching patterns in graphs.
+//!
+//! ```text
+//! MATCH (a)-[w>5]->(b), (b)--(c {value: "C"})
+//! WHERE a.value = "X" AND NOT c.key = 3
+//! RETURN DISTINCT b, c.value LIMIT 10
+//! ```
+//!
+//! - Vertices are written as `(name)`, `(name {key: 1, value: "A"})` or `()`.
+//! - Edges are written as `-[name]->`, `<-[name]-` or `-[name]-` for any direction,
+//!   with short forms `-->`, `<--` and `--`. The name may be followed by a filter
+//!   of the weight: `-[w>5]->` or `-[>=2]->`.
+//! - Properties of vertices are `key`, `value` and `degree`, properties of edges
+//!   are `weight`, `from` and `to`.
+//! - Conditions compare properties and literals with `=`, `<>` (`!=`), `<`, `<=`, `>`,
+//!   `>=` and `CONTAINS`, and join them with `AND`, `OR`, `NOT` and parentheses.
+//!   Numbers are compared as numbers, everything else as text.
+//!
+//! As in Cypher, the same edge is never used twice in one match, but vertices may repeat.
+//! Vertices are tried in the order of their keys' textual form,
+//! so the same query on the same graph returns rows in the same order.
+
+use std::{
+    cmp::Ordering,
+    collections::{HashMap, HashSet},
+    fmt::Display,
+    hash::Hash,
+};
+
+use crate::{edge::Edge, graph::Graph, marker::GraphType, vertex::Vertex, MAX_DEPTH};
+
+/// Value of a returned column.
+pub enum QueryValue<'a, Key, Value>
+where
+    Key: Eq + Clone,
+{
+    Vertex(&'a Vertex<Key, Value>),
+    Edge(&'a Edge<Key>),
+    Key(&'a Key),
+    Value(&'a Value),
+    /// Degree of a vertex or weight of an edge.
+    Number(i64),
+}
+
+/// Rows of values returned by a query.
+pub struct QueryResult<'a, Key, Value>
+where
+    Key: Eq + Clone,
+{
+    /// Items of the RETURN clause as they are written, e.g. `a.value`.
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<QueryValue<'a, Key, Value>>>,
+    arrow: &'static str,
+}
+
+impl<Key, Value> QueryResult<'_, Key, Value>
+where
+    Key: Eq + Clone + Display,
+    Value: Display,
+{
+    /// Returns the rows as tab separated text. Vertices are written as their keys
+    /// and edges as `from -> to`, or `from -- to` in undirected graphs.
+    pub fn lines(&self) -> Vec<String> {
+        self.rows
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|value| match value {
+                        QueryValue::Vertex(vertex) => vertex.key().to_string(),
+                        QueryValue::Edge(edge) => {
+                            format!("{} {} {}", edge.from(), self.arrow, edge.to())
+                        }
+                        QueryValue::Key(key) => key.to_string(),
+                        QueryValue::Value(value) => value.to_string(),
+                        QueryValue::Number(n) => n.to_string(),
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\t")
+            })
+            .collect()
+    }
+}
+
+impl<Key, Value> Display for QueryResult<'_, Key, Value>
+where
+    Key: Eq + Clone + Display,
+    Value: Display,
+{
+    /// Writes the columns and then the rows, separated by tabs.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{}", self.columns.join("\t"))?;
+        for line in self.lines() {
+            writeln!(f, "{}", line)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Text(String),
+    Number(String),
+    Symbol(&'static str),
+}
+
+impl Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::Ident(name) => write!(f, "{}", name),
+            Token::Text(text) => write!(f, "\"{}\"", text),
+            Token::Number(n) => write!(f, "{}", n),
+            Token::Symbol(symbol) => write!(f, "{}", symbol),
+        }
+    }
+}
+
+const KEYWORDS: [&str; 9] = [
+    "MATCH", "WHERE", "RETURN", "DISTINCT", "LIMIT", "AND", "OR", "NOT", "CONTAINS",
+];
+
+const SYMBOLS: [&str; 17] = [
+    "<=", ">=", "<>", "!=", "(", ")", "[", "]", "{", "}", ",", ".", ":", "-", "<", ">", "=",
+];
+
+/// Splits the query into tokens with their positions.
+fn tokenize(query: &str) -> Result<Vec<(usize, Token)>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = query.char_indices().peekable();
+
+    while let Some(&(i, c)) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c.is_alphabetic() || c == '_' {
+            let mut name = String::new();
+            while let Some(&(_, c)) = chars
+                .peek()
+                .filter(|(_, c)| c.is_alphanumeric() || *c == '_')
+            {
+                name.push(c);
+                chars.next();
+            }
+            tokens.push((i, Token::Ident(name)));
+        } else if c.is_ascii_digit() {
+            let mut number = String::new();
+            while let Some(&(_, c)) = chars.peek().filter(|(_, c)| c.is_ascii_digit()) {
+                number.push(c);
+                chars.next();
+            }
+            tokens.push((i, Token::Number(number)));
+        } else if c == '"' || c == '\'' {
+            chars.next();
+            let mut text = String::new();
+            loop {
+                match chars.next() {
+                    Some((_, '\\')) => match chars.next() {
+                        Some((_, escaped)) => text.push(escaped),
+                        None => return Err(format!("unterminated string at position {}", i)),
+                    },
+                    Some((_, q)) if q == c => break,
+                    Some((_, other)) => text.push(other),
+                    None => return Err(format!("unterminated string at position {}", i)),
+                }
+            }
+            tokens.push((i, Token::Text(text)));
+        } else {
+            let symbol = SYMBOLS
+                .iter()
+                .find(|s| query[i..].starts_with(*s))
+                .ok_or_else(|| format!("unexpected '{}' at position {}", c, i))?;
+            for _ in 0..symbol.len() {
+                chars.next();
+            }
+            tokens.push((i, Token::Symbol(symbol)));
+        }
+    }
+
+    Ok(tokens)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Operator {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Contains,
+}
+
+impl Operator {
+    fn from_symbol(symbol: &str) -> Option<Self> {
+        match symbol {
+            "=" => Some(Operator::Eq),
+            "<>" | "!=" => Some(Operator::Ne),
+            "<" => Some(Operator::Lt),
+            "<=" => Some(Operator::Le),
+            ">" => Some(Operator::Gt),
+            ">=" => Some(Operator::Ge),
+            _ => None,
+        }
+    }
+
+    /// Compares numerically if both sides are integers, otherwise as text.
+    fn apply(self, left: &str, right: &str) -> bool {
+        if self == Operator::Contains {
+            return left.contains(right);
+        }
+        let ordering = match (left.parse::<i64>(), right.parse::<i64>()) {
+            (Ok(l), Ok(r)) => l.cmp(&r),
+            _ => left.cmp(right),
+        };
+        match self {
+            Operator::Eq => ordering == Ordering::Equal,
+            Operator::Ne => ordering != Ordering::Equal,
+            Operator::Lt => ordering == Ordering::Less,
+            Operator::Le => ordering != Ordering::Greater,
+            Operator::Gt => ordering == Ordering::Greater,
+            Operator::Ge => ordering != Ordering::Less,
+            Operator::Contains => unreachable!("handled above"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Direction {
+    /// `-[]->`
+    Forward,
+    /// `<-[]-`
+    Backward,
+    /// `-[]-`
+    Any,
+}
+
+#[derive(Debug)]
+struct NodePattern {
+    name: String,
+    properties: Vec<(String, String)>,
+}
+
+#[derive(Debug)]
+struct EdgePattern {
+    name: String,
+    direction: Direction,
+    weight: Option<(Operator, i64)>,
+}
+
+/// A chain of vertices joined by edges.
+#[derive(Debug)]
+struct Pattern {
+    start: NodePattern,
+    steps: Vec<(EdgePattern, NodePattern)>,
+}
+
+#[derive(Debug)]
+enum Operand {
+    Property(String, String),
+    Literal(String),
+}
+
+#[derive(Debug)]
+enum Condition {
+    Compare(Operand, Operator, Operand),
+    Not(Box<Condition>),
+    And(Box<Condition>, Box<Condition>),
+    Or(Box<Condition>, Box<Condition>),
+}
+
+#[derive(Debug)]
+struct ReturnItem {
+    name: String,
+    property: Option<String>,
+}
+
+#[derive(Debug)]
+struct Query {
+    patterns: Vec<Pattern>,
+    condition: Option<Condition>,
+    distinct: bool,
+    items: Vec<ReturnItem>,
+    limit: Option<usize>,
+}
+
+struct Parser {
+    tokens: Vec<(usize, Token)>,
+    position: usize,
+    /// Length of the query, reported as the position of the end.
+    end: usize,
+    anonymous: usize,
+    /// Number of NOTs and parentheses the parser is in.
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position).map(|(_, t)| t)
+    }
+
+    fn error(&self, expected: &str) -> String {
+        match self.tokens.get(self.position) {
+            Some((i, token)) => {
+                format!("expected {}, found '{}' at position {}", expected, token, i)
+            }
+            None => format!(
+                "expected {}, found the end at position {}",
+                expected, self.end
+            ),
+        }
+    }
+
+    fn symbol(&mut self, symbol: &str) -> bool {
+        if self.peek() == Some(&Token::Symbol(symbol_of(symbol))) {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect_symbol(&mut self, symbol: &str) -> Result<(), String> {
+        if self.symbol(symbol) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("'{}'", symbol)))
+        }
+    }
+
+    fn keyword(&mut self, keyword: &str) -> bool {
+        match self.peek() {
+            Some(Token::Ident(name)) if name.eq_ignore_ascii_case(keyword) => {
+                self.position += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> Result<(), String> {
+        if self.keyword(keyword) {
+            Ok(())
+        } else {
+            Err(self.error(keyword))
+        }
+    }
+
+    /// Returns the name if the next token is one. Keywords are not names.
+    fn name(&mut self) -> Option<String> {
+        match self.peek() {
+            Some(Token::Ident(name)) if !KEYWORDS.iter().any(|k| k.eq_ignore_ascii_case(name)) => {
+                let name = name.clone();
+                self.position += 1;
+                Some(name)
+            }
+            _ => None,
+        }
+    }
+
+    fn expect_name(&mut self) -> Result<String, String> {
+        self.name().ok_or_else(|| self.error("a name"))
+    }
+
+    /// Name for a vertex or an edge without one. It can't clash with names in the query.
+    fn anonymous(&mut self) -> String {
+        self.anonymous += 1;
+        format!(" {}", self.anonymous)
+    }
+
+    fn literal(&mut self) -> Result<String, String> {
+        let negative = self.symbol("-");
+        match self.peek().cloned() {
+            Some(Token::Number(n)) => {
+                self.position += 1;
+                Ok(if negative { format!("-{}", n) } else { n })
+            }
+            Some(Token::Text(text)) if !negative => {
+                self.position += 1;
+                Ok(text)
+            }
+            _ => Err(self.error("a number or a string")),
+        }
+    }
+
+    fn integer(&mut self) -> Result<i64, String> {
+        let position = self.position;
+        let literal = self.literal()?;
+        literal.parse().map_err(|_| {
+            self.position = position;
+            self.error("an integer")
+        })
+    }
+
+    fn query(&mut self) -> Result<Query, String> {
+        self.expect_keyword("MATCH")?;
+        let mut patterns = vec![self.pattern()?];
+        while self.symbol(",") {
+            patterns.push(self.pattern()?);
+        }
+
+        let condition = if self.keyword("WHERE") {
+            Some(self.or()?)
+        } else {
+            None
+        };
+
+        self.expect_keyword("RETURN")?;
+        let distinct = self.keyword("DISTINCT");
+        let mut items = vec![self.return_item()?];
+        while self.symbol(",") {
+            items.push(self.return_item()?);
+        }
+
+        let limit = if self.keyword("LIMIT") {
+            let position = self.position;
+            let limit = self.integer()?;
+            Some(usize::try_from(limit).map_err(|_| {
+                self.position = position;
+                self.error("a non-negative limit")
+            })?)
+        } else {
+            None
+        };
+
+        match self.peek() {
+            None => Ok(Query {
+                patterns,
+                condition,
+                distinct,
+                items,
+                limit,
+            }),
+            Some(_) => Err(self.error("the end of the query")),
+        }
+    }
+
+    fn pattern(&mut self) -> Result<Pattern, String> {
+        let start = self.node()?;
+        let mut steps = Vec::new();
+        while matches!(self.peek(), Some(Token::Symbol("-" | "<"))) {
+            steps.push((self.edge()?, self.node()?));
+        }
+        Ok(Pattern { start, steps })
+    }
+
+    fn node(&mut self) -> Result<NodePattern, String> {
+        self.expect_symbol("(")?;
+        let name = match self.name() {
+            Some(name) => name,
+            None => self.anonymous(),
+        };
+
+        let mut properties = Vec::new();
+        if self.symbol("{") {
+            loop {
+                let property = self.expect_name()?;
+                if property != "key" && property != "value" {
+                    self.position -= 1;
+                    return Err(self.error("key or value"));
+                }
+                self.expect_symbol(":")?;
+                properties.push((property, self.literal()?));
+                if !self.symbol(",") {
+                    break;
+                }
+            }
+            self.expect_symbol("}")?;
+        }
+
+        self.expect_symbol(")")?;
+        Ok(NodePattern { name, properties })
+    }
+
+    fn edge(&mut self) -> Result<EdgePattern, String> {
+        let backward = self.symbol("<");
+        self.expect_symbol("-")?;
+
+        let mut name = None;
+        let mut weight = None;
+        if self.symbol("[") {
+            name = self.name();
+            if let Some(Token::Symbol(symbol)) = self.peek() {
+                if let Some(operator) = Operator::from_symbol(symbol) {
+                    self.position += 1;
+                    weight = Some((operator, self.integer()?));
+                }
+            }
+            self.expect_symbol("]")?;
+        }
+
+        self.expect_symbol("-")?;
+        let forward = self.symbol(">");
+        let direction = match (backward, forward) {
+            (false, true) => Direction::Forward,
+            (true, false) => Direction::Backward,
+            (false, false) => Direction::Any,
+            (true, true) => {
+                self.position -= 1;
+                return Err(self.error("an edge with one direction"));
+            }
+        };
+
+        Ok(EdgePattern {
+            name: name.unwrap_or_else(|| self.anonymous()),
+            direction,
+            weight,
+        })
+    }
+
+    fn or(&mut self) -> Result<Condition, String> {
+        let mut condition = self.and()?;
+        while self.keyword("OR") {
+            condition = Condition::Or(Box::new(condition), Box::new(self.and()?));
+        }
+        Ok(condition)
+    }
+
+    fn and(&mut self) -> Result<Condition, String> {
+        let mut condition = self.not()?;
+        while self.keyword("AND") {
+            condition = Condition::And(Box::new(condition), Box::new(self.not()?));
+        }
+        Ok(condition)
+    }
+
+    fn not(&mut self) -> Result<Condition, String> {
+        if self.keyword("NOT") {
+            return Ok(Condition::Not(Box::new(self.nested(Self::not)?)));
+        }
+        if self.symbol("(") {
+            let condition = self.nested(Self::or)?;
+            self.expect_symbol(")")?;
+            return Ok(condition);
+        }
+
+        let left = self.operand()?;
+        let operator = match self.peek() {
+            Some(Token::Symbol(symbol)) => Operator::from_symbol(symbol),
+            Some(Token::Ident(name)) if name.eq_ignore_ascii_case("CONTAINS") => {
+                Some(Operator::Contains)
+            }
+            _ => None,
+        }
+        .ok_or_else(|| self.error("a comparison"))?;
+        self.position += 1;
+        Ok(Condition::Compare(left, operator, self.operand()?))
+    }
+
+    /// Parses a condition one level deeper, so deeply nested conditions can't overflow the stack.
+    fn nested(
+        &mut self,
+        parse: fn(&mut Self) -> Result<Condition, String>,
+    ) -> Result<Condition, String> {
+        if self.depth == MAX_DEPTH {
+            return Err(format!("conditions are nested deeper than {}", MAX_DEPTH));
+        }
+        self.depth += 1;
+        let condition = parse(self);
+        self.depth -= 1;
+        condition
+    }
+
+    fn operand(&mut self) -> Result<Operand, String> {
+        match self.name() {
+            Some(name) => {
+                self.expect_symbol(".")?;
+                Ok(Operand::Property(name, self.expect_name()?))
+            }
+            None => Ok(Operand::Literal(self.literal()?)),
+        }
+    }
+
+    fn return_item(&mut self) -> Result<ReturnItem, String> {
+        let name = self.expect_name()?;
+        let property = if self.symbol(".") {
+            Some(self.expect_name()?)
+        } else {
+            None
+        };
+        Ok(ReturnItem { name, property })
+    }
+}
+
+/// Returns the static symbol equal to the text, so tokens can be compared.
+fn symbol_of(text: &str) -> &'static str {
+    SYMBOLS
+        .iter()
+        .find(|s| **s == text)
+        .expect("only known symbols are expected")
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Kind {
+    Vertex,
+    Edge,
+}
+
+const VERTEX_PROPERTIES: [&str; 3] = ["key", "value", "degree"];
+const EDGE_PROPERTIES: [&str; 3] = ["weight", "from", "to"];
+
+/// Checks that every name is used for one kind of element,
+/// and that conditions and returned items refer to known names and properties.
+fn check(query: &Query) -> Result<(), String> {
+    let elements = query.patterns.iter().flat_map(|pattern| {
+        std::iter::once((pattern.start.name.as_str(), Kind::Vertex)).chain(
+            pattern.steps.iter().flat_map(|(edge, node)| {
+                [
+                    (edge.name.as_str(), Kind::Edge),
+                    (node.name.as_str(), Kind::Vertex),
+                ]
+            }),
+        )
+    });
+    let mut kinds: HashMap<&str, Kind> = HashMap::new();
+    for (name, kind) in elements {
+        match kinds.insert(name, kind) {
+            Some(previous) if previous != kind => {
+                return Err(format!("{} is used both as a vertex and as an edge", name))
+            }
+            _ => {}
+        }
+    }
+
+    let property = |name: &str, property: &str| -> Result<(), String> {
+        let known = match kinds.get(name) {
+            Some(Kind::Vertex) => VERTEX_PROPERTIES.as_slice(),
+            Some(Kind::Edge) => EDGE_PROPERTIES.as_slice(),
+            None => return Err(format!("{} is not defined", name)),
+        };
+        if known.contains(&property) {
+            Ok(())
+        } else {
+            Err(format!(
+                "{} has no property {}, expected one of: {}",
+                name,
+                property,
+                known.join(", ")
+            ))
+        }
+    };
+
+    fn conditions<'a>(condition: &'a Condition, operands: &mut Vec<&'a Operand>) {
+        match condition {
+            Condition::Compare(left, _, right) => operands.extend([left, right]),
+            Condition::Not(inner) => conditions(inner, operands),
+            Condition::And(left, right) | Condition::Or(left, right) => {
+                conditions(left, operands);
+                conditions(right, operands);
+            }
+        }
+    }
+    let mut operands = Vec::new();
+    if let Some(condition) = &query.condition {
+        conditions(condition, &mut operands);
+    }
+    for operand in operands {
+        if let Operand::Property(name, prop) = operand {
+            property(name, prop)?;
+        }
+    }
+
+    for item in &query.items {
+        match &item.property {
+            Some(prop) => property(&item.name, prop)?,
+            None if kinds.contains_key(item.name.as_str()) => {}
+            None => return Err(format!("{} is not defined", item.name)),
+        }
+    }
+
+    Ok(())
+}
+
+/// Vertex or edge bound to a name.
+enum Bound<'a, Key, Value>
+where
+    Key: Eq + Clone,
+{
+    Vertex(&'a Vertex<Key, Value>),
+    Edge(&'a Edge<Key>),
+}
+
+impl<Key, Value> Clone for Bound<'_, Key, Value>
+where
+    Key: Eq + Clone,
+{
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<Key, Value> Copy for Bound<'_, Key, Value> where Key: Eq + Clone {}
+
+/// Matches patterns of the query against the graph.
+struct Matcher<'a, 'q, Key, Value, Type>
+where
+    Key: Hash + Eq + Clone,
+    Type: GraphType,
+{
+    graph: &'a Graph<Key, Value, Type>,
+    query: &'q Query,
+    /// Vertices in the order of their keys' textual form.
+    vertices: Vec<&'a Vertex<Key, Value>>,
+    /// Edges leading to each vertex, for backward steps in directed graphs.
+    incoming: HashMap<&'a Key, Vec<&'a Edge<Key>>>,
+    bindings: Vec<(&'q str, Bound<'a, Key, Value>)>,
+    used: Vec<&'a Edge<Key>>,
+    rows: Vec<Vec<QueryValue<'a, Key, Value>>>,
+    seen: HashSet<Vec<String>>,
+}
+
+impl<'a, 'q, Key, Value, Type> Matcher<'a, 'q, Key, Value, Type>
+where
+    Key: Hash + Eq + Clone + Display,
+    Value: Display,
+    Type: GraphType,
+{
+    fn bound(&self, name: &str) -> Option<Bound<'a, Key, Value>> {
+        self.bindings
+            .iter()
+            .rev()
+            .find(|(n, _)| *n == name)
+            .map(|(_, b)| *b)
+    }
+
+    fn vertex(&self, name: &str) -> &'a Vertex<Key, Value> {
+        match self.bound(name) {
+            Some(Bound::Vertex(vertex)) => vertex,
+            _ => unreachable!("names are checked before matching"),
+        }
+    }
+
+    fn degree(&self, vertex: &Vertex<Key, Value>) -> usize {
+        self.graph
+            .degree(vertex.key())
+            .expect("bound vertices are in the graph")
+    }
+
+    fn property(&self, name: &str, property: &str) -> String {
+        match (self.bound(name), property) {
+            (Some(Bound::Vertex(v)), "key") => v.key().to_string(),
+            (Some(Bound::Vertex(v)), "value") => v.value.to_string(),
+            (Some(Bound::Vertex(v)), _) => self.degree(v).to_string(),
+            (Some(Bound::Edge(e)), "weight") => e.weight.to_string(),
+            (Some(Bound::Edge(e)), "from") => e.from().to_string(),
+            (Some(Bound::Edge(e)), _) => e.to().to_string(),
+            (None, _) => unreachable!("names are checked before matching"),
+        }
+    }
+
+    fn operand(&self, operand: &Operand) -> String {
+        match operand {
+            Operand::Property(name, property) => self.property(name, property),
+            Operand::Literal(literal) => literal.clone(),
+        }
+    }
+
+    fn holds(&self, condition: &Condition) -> bool {
+        match condition {
+            Condition::Compare(left, operator, right) => {
+                operator.apply(&self.operand(left), &self.operand(right))
+            }
+            Condition::Not(inner) => !self.holds(inner),
+            Condition::And(left, right) => self.holds(left) && self.holds(right),
+            Condition::Or(left, right) => self.holds(left) || self.holds(right),
+        }
+    }
+
+    /// Returns true if the same edge was already used in the match.
+    /// An undirected edge is stored in both vertices, so both copies are compared.
+    fn is_used(&self, edge: &Edge<Key>) -> bool {
+        self.used.iter().any(|used| {
+            std::ptr::eq(*used, edge)
+                || (!Type::is_directed() && used.from() == edge.to() && used.to() == edge.from())
+        })
+    }
+
+    fn is_full(&self) -> bool {
+        self.query
+            .limit
+            .is_some_and(|limit| self.rows.len() >= limit)
+    }
+
+    /// Binds the vertex to the node pattern if it fits, and continues with the next step.
+    fn node(&mut self, node: &'q NodePattern, vertex: &'a Vertex<Key, Value>, at: (usize, usize)) {
+        match self.bound(&node.name) {
+            Some(Bound::Vertex(bound)) if bound.key() != vertex.key() => return,
+            _ => {}
+        }
+        let fits = node.properties.iter().all(|(property, literal)| {
+            let actual = match property.as_str() {
+                "key" => vertex.key().to_string(),
+                _ => vertex.value.to_string(),
+            };
+            Operator::Eq.apply(&actual, literal)
+        });
+        if !fits {
+            return;
+        }
+
+        self.bindings.push((&node.name, Bound::Vertex(vertex)));
+        self.step(at.0, at.1);
+        self.bindings.pop();
+    }
+
+    /// Matches the step of the pattern, or starts the next pattern.
+    fn step(&mut self, pattern: usize, step: usize) {
+        if self.is_full() {
+            return;
+        }
+
+        let Some(current) = self.query.patterns.get(pattern) else {
+            self.emit();
+            return;
+        };
+
+        if step == 0 {
+            for vertex in self.vertices.clone() {
+                self.node(&current.start, vertex, (pattern, 1));
+            }
+            return;
+        }
+
+        let Some((edge, node)) = current.steps.get(step - 1) else {
+            self.step(pattern + 1, 0);
+            return;
+        };
+
+        let from = self.vertex(if step == 1 {
+            &current.start.name
+        } else {
+            &current.steps[step - 2].1.name
+        });
+
+        // Pairs of an edge and the vertex at its other end
+        let mut candidates: Vec<(&'a Edge<Key>, &'a Key)> = Vec::new();
+        if !Type::is_directed() || edge.direction != Direction::Backward {
+            candidates.extend(from.adjancency_list().iter().map(|e| (e, e.to())));
+        }
+        if Type::is_directed() && edge.direction != Direction::Forward {
+            let incoming = self.incoming.get(from.key()).into_iter().flatten();
+            candidates.extend(
+                incoming
+                    // A loop is already among outgoing edges
+                    .filter(|e| edge.direction == Direction::Backward || e.from() != e.to())
+                    .map(|e| (*e, e.from())),
+            );
+        }
+
+        for (candidate, to) in candidates {
+            if self.is_used(candidate) {
+                continue;
+            }
+            if let Some((operator, weight)) = edge.weight {
+                if !operator.apply(&candidate.weight.to_string(), &weight.to_string()) {
+                    continue;
+                }
+            }
+            match self.bound(&edge.name) {
+                Some(Bound::Edge(bound)) if !std::ptr::eq(bound, candidate) => continue,
+                _ => {}
+            }
+
+            let vertex = self.graph.get_vertex(to).expect("edge leads to a vertex");
+            self.used.push(candidate);
+            self.bindings.push((&edge.name, Bound::Edge(candidate)));
+            self.node(node, vertex, (pattern, step + 1));
+            self.bindings.pop();
+            self.used.pop();
+        }
+    }
+
+    /// Adds the row of the complete match if it fits the condition.
+    fn emit(&mut self) {
+        if let Some(condition) = &self.query.condition {
+            if !self.holds(condition) {
+                return;
+            }
+        }
+
+        let row: Vec<QueryValue<'a, Key, Value>> = self
+            .query
+            .items
+            .iter()
+            .map(
+                |item| match (self.bound(&item.name), item.property.as_deref()) {
+                    (Some(Bound::Vertex(v)), None) => QueryValue::Vertex(v),
+                    (Some(Bound::Vertex(v)), Some("key")) => QueryValue::Key(v.key()),
+                    (Some(Bound::Vertex(v)), Some("value")) => QueryValue::Value(&v.value),
+                    (Some(Bound::Vertex(v)), Some(_)) => QueryValue::Number(self.degree(v) as i64),
+                    (Some(Bound::Edge(e)), None) => QueryValue::Edge(e),
+                    (Some(Bound::Edge(e)), Some("weight")) => QueryValue::Number(e.weight),
+                    (Some(Bound::Edge(e)), Some("from")) => QueryValue::Key(e.from()),
+                    (Some(Bound::Edge(e)), Some(_)) => QueryValue::Key(e.to()),
+                    (None, _) => unreachable!("names are checked before matching"),
+                },
+            )
+            .collect();
+
+        if self.query.distinct {
+            let text = self
+                .query
+                .items
+                .iter()
+                .map(|item| match &item.property {
+                    Some(property) => self.property(&item.name, property),
+                    None => match self.bound(&item.name) {
+                        Some(Bound::Vertex(v)) => v.key().to_string(),
+                        Some(Bound::Edge(e)) => format!("{}\t{}\t{}", e.from(), e.to(), e.weight),
+                        None => unreachable!("names are checked before matching"),
+                    },
+                })
+                .collect();
+            if !self.seen.insert(text) {
+                return;
+            }
+        }
+
+        self.rows.push(row);
+    }
+}
+
+impl<Key, Value, Type> Graph<Key, Value, Type>
+where
+    Key: Hash + Eq + Clone + Display,
+    Value: Display,
+    Type: GraphType,
+{
+    /// Runs the query written in the Cypher-like language described in the module documentation.
+    /// Returns Err with the position of the problem if the query is invalid,
+    /// or if its conditions are nested too deep.
+    ///
+    /// ```
+    /// use graph_rs::graph::Graph;
+    ///
+    /// let mut graph: Graph<i32, &str> = Graph::new();
+    /// graph.insert(1, "X");
+    /// graph.insert(2, "Y");
+    /// graph.insert_edge(1, 2, 7).unwrap();
+    ///
+    /// let result = graph
+    ///     .query(r#"MATCH (a)-[w>5]->(b) WHERE a.value = "X" RETURN b"#)
+    ///     .unwrap();
+    /// assert_eq!(result.lines(), vec!["2"]);
+    /// ```
+    pub fn query(&self, query: &str) -> Result<QueryResult<'_, Key, Value>, String> {
+        let mut parser = Parser {
+            tokens: tokenize(query)?,
+            position: 0,
+            end: query.len(),
+            anonymous: 0,
+            depth: 0,
+        };
+        let parsed = parser.query()?;
+        check(&parsed)?;
+
+        let mut vertices: Vec<&Vertex<Key, Value>> = self.vertecies().collect();
+        vertices.sort_by_cached_key(|v| v.key().to_string());
+        let mut incoming: HashMap<&Key, Vec<&Edge<Key>>> = HashMap::new();
+        if Type::is_directed() {
+            for vertex in &vertices {
+                for edge in vertex.adjancency_list() {
+                    incoming.entry(edge.to()).or_default().push(edge);
+                }
+            }
+        }
+
+        let mut matcher = Matcher {
+            graph: self,
+            query: &parsed,
+            vertices,
+            incoming,
+            bindings: Vec::new(),
+            used: Vec::new(),
+            rows: Vec::new(),
+            seen: HashSet::new(),
+        };
+        matcher.step(0, 0);
+
+        Ok(QueryResult {
+            columns: parsed
+                .items
+                .iter()
+                .map(|item| match &item.property {
+                    Some(property) => format!("{}.{}", item.name, property),
+                    None => item.name.clone(),
+                })
+                .collect(),
+            rows: matcher.rows,
+            arrow: if Type::is_directed() { "->" } else { "--" },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::{
+        assert_depth_limit,
+        marker::{Directed, Undirected},
+    };
+    use pretty_assertions::assert_eq;
+
+    fn sample() -> Graph<i32, String, Directed> {
+        let mut graph: Graph<i32, String, Directed> = Graph::new();
+        for (key, value) in [(1, "X"), (2, "Y"), (3, "Z"), (4, "X")] {
+            graph.insert(key, String::from(value));
+        }
+        for (from, to, weight) in [(1, 2, 7), (1, 3, 2), (2, 3, 9), (4, 3, 6), (3, 3, 1)] {
+            graph.insert_edge(from, to, weight).unwrap();
+        }
+        graph
+    }
+
+    fn lines(graph: &Graph<i32, String, Directed>, query: &str) -> Vec<String> {
+        graph.query(query).expect("query is valid").lines()
+    }
+
+    #[test]
+    fn query_test() {
+        let graph = sample();
+
+        let result = graph
+            .query(r#"MATCH (a)-[w>5]->(b) WHERE a.value = "X" RETURN b, w.weight"#)
+            .unwrap();
+        assert_eq!(result.columns, vec!["b", "w.weight"]);
+        assert_eq!(result.lines(), vec!["2\t7", "3\t6"]);
+        assert!(matches!(result.rows[0][0], QueryValue::Vertex(v) if *v.key() == 2));
+        assert!(matches!(result.rows[0][1], QueryValue::Number(7)));
+
+        assert_eq!(
+            lines(
+                &graph,
+                "MATCH (a)<--(b {value: 'X'}) RETURN DISTINCT a.value"
+            ),
+            vec!["Y", "Z"]
+        );
+        assert_eq!(
+            lines(
+                &graph,
+                "MATCH (a)-[e]-(b {key: 3}) WHERE NOT a.key = 3 RETURN e"
+            ),
+            vec!["1 -> 3", "2 -> 3", "4 -> 3"]
+        );
+        assert_eq!(
+            lines(
+                &graph,
+                "MATCH (a)-->(b)-->(c) WHERE c.degree >= 1 RETURN a, b, c LIMIT 2"
+            ),
+            vec!["1\t2\t3", "1\t3\t3"]
+        );
+        assert_eq!(
+            lines(
+                &graph,
+                "MATCH (a)-->(b), (c)-->(b) WHERE a.key < c.key AND (b.value CONTAINS 'Z' OR b.key = 2) RETURN a, c, b"
+            ),
+            vec!["1\t2\t3", "1\t3\t3", "1\t4\t3", "2\t3\t3", "2\t4\t3", "3\t4\t3"]
+        );
+
+        // The loop is matched once, the edge can't be used twice in one match
+        assert_eq!(lines(&graph, "MATCH (a)-[e]-(a) RETURN e"), vec!["3 -> 3"]);
+        assert_eq!(
+            lines(&graph, "MATCH (a)-[e]->(a)<-[f]-(a) RETURN e"),
+            Vec::<String>::new()
+        );
+    }
+
+    #[test]
+    fn query_undirected_test() {
+        let mut graph: Graph<char, i32, Undirected> = Graph::new();
+        for key in ['a', 'b', 'c'] {
+            graph.insert(key, 0);
+        }
+        graph.insert_edge('a', 'b', 1).unwrap();
+        graph.insert_edge('b', 'c', 2).unwrap();
+
+        let result = graph.query("MATCH (x)-[e]->(y) RETURN x, y, e").unwrap();
+        assert_eq!(
+            result.lines(),
+            vec![
+                "a\tb\ta -- b",
+                "b\ta\tb -- a",
+                "b\tc\tb -- c",
+                "c\tb\tc -- b"
+            ]
+        );
+
+        // Both copies of an undirected edge are the same edge
+        assert_eq!(
+            graph
+                .query("MATCH (x)--(y)--(z) RETURN x, y, z")
+                .unwrap()
+                .lines(),
+            vec!["a\tb\tc", "c\tb\ta"]
+        );
+
+        // A loop adds two to the degree of its vertex
+        graph.link('c', 'c', 3).unwrap();
+        assert_eq!(
+            graph.query("MATCH (x) RETURN x, x.degree").unwrap().lines(),
+            vec!["a\t1", "b\t2", "c\t3"]
+        );
+        assert_eq!(
+            graph
+                .query("MATCH (x) WHERE x.degree = 3 RETURN x")
+                .unwrap()
+                .lines(),
+            vec!["c"]
+        );
+    }
+
+    fn error(graph: &Graph<i32, String, Directed>, query: &str) -> String {
+        match graph.query(query) {
+            Ok(_) => panic!("'{}' must not be valid", query),
+            Err(message) => message,
+        }
+    }
+
+    #[test]
+    fn query_errors_test() {
+        let graph = sample();
+
+        assert_eq!(error(&graph, "MATCH (a) RETURN b"), "b is not defined");
+        assert_eq!(
+            error(&graph, "MATCH (a)-[e]->(b) RETURN e.value"),
+            "e has no property value, expected one of: weight, from, to"
+        );
+        assert_eq!(
+            error(&graph, "MATCH (a)-[a]->(b) RETURN a"),
+            "a is used both as a vertex and as an edge"
+        );
+        assert_eq!(
+            error(&graph, "MATCH (a RETURN a"),
+            "expected ')', found 'RETURN' at position 9"
+        );
+        assert_eq!(
+            error(&graph, "MATCH (a)<-->(b) RETURN a"),
+            "expected an edge with one direction, found '>' at position 12"
+        );
+        assert_eq!(
+            error(&graph, "MATCH (a) WHERE a.key = RETURN a"),
+            "expected a number or a string, found 'RETURN' at position 24"
+        );
+        assert_eq!(
+            error(&graph, "MATCH (a) RETURN a LIMIT"),
+            "expected a number or a string, found the end at position 24"
+        );
+        assert_eq!(
+            error(&graph, "MATCH (a) WHERE a.value = \"X RETURN a"),
+            "unterminated string at position 26"
+        );
+        assert_eq!(
+            error(&graph, "MATCH (a) RETURN a;"),
+            "unexpected ';' at position 18"
+        );
+
+        assert_depth_limit(|depth| {
+            graph.query(&format!(
+                "MATCH (a) WHERE {}a.key = 1 RETURN a",
+                "NOT ".repeat(depth)
+            ))
+        });
+        assert_depth_limit(|depth| {
+            graph.query(&format!(
+                "MATCH (a) WHERE {}a.key = 1{} RETURN a",
+                "(".repeat(depth),
+                ")".repeat(depth)
+            ))
+        });
+    }
+}
@@ -0,0 +1,240 @@
+//! SVG rendering of graphs.
+//!
+//! Vertices are drawn as rounded boxes labeled with their values,
+//! edges as lines labeled with their weights.
+
+use std::{fmt::Display, hash::Hash};
+
+use crate::{
+    graph::Graph,
+    layout::{self, ForceOptions, LayeredOptions, Layout, Point},
+    marker::GraphType,
+    xml,
+};
+
+const BOX_HEIGHT: f64 = 24.0;
+
+/// Approximate width of a character of the label font.
+const CHAR_WIDTH: f64 = 7.0;
+
+/// Returns half of the width and half of the height of the vertex box.
+fn half_size(label: &str) -> (f64, f64) {
+    let width = (label.chars().count() as f64 * CHAR_WIDTH + 16.0).max(32.0);
+    (width / 2.0, BOX_HEIGHT / 2.0)
+}
+
+/// Returns the point where the line from the center of the box toward
+/// the target crosses the border of the box.
+fn clip(center: Point, (half_width, half_height): (f64, f64), target: Point) -> Point {
+    let (dx, dy) = (target.x - center.x, target.y - center.y);
+    let scale = (half_width / dx.abs()).min(half_height / dy.abs()).min(1.0);
+    if !scale.is_finite() {
+        return center;
+    }
+    Point::new(center.x + dx * scale, center.y + dy * scale)
+}
+
+impl<Key, Value, Type> Graph<Key, Value, Type>
+where
+    Key: Hash + Eq + Clone + Ord,
+    Value: Display,
+    Type: GraphType,
+{
+    /// Renders the graph to SVG. Directed acyclic graphs are drawn in layers,
+    /// other graphs with the force-directed layout.
+    pub fn to_svg(&self) -> String {
+        let layout = layout::layered(self, &LayeredOptions::default())
+            .unwrap_or_else(|_| layout::force_directed(self, &ForceOptions::default()));
+        self.to_svg_with(&layout)
+    }
+
+    /// Renders the graph to SVG using the given layout.
+    /// Vertices that are missing from the layout are not drawn.
+    pub fn to_svg_with(&self, layout: &Layout<Key>) -> String {
+        let vertices: Vec<_> = self
+            .sorted_vertices()
+            .into_iter()
+            .filter_map(|v| {
+                let label = v.value.to_string();
+                let size = half_size(&label);
+                Some((v.key(), *layout.positions.get(v.key())?, label, size))
+            })
+            .collect();
+        let find = |key: &Key| {
+            vertices
+                .binary_search_by(|v| v.0.cmp(key))
+                .ok()
+                .map(|i| &vertices[i])
+        };
+
+        let mut buffer = format!(
+            concat!(
+                "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w:.0}\" height=\"{h:.0}\" ",
+                "viewBox=\"0 0 {w:.0} {h:.0}\" font-family=\"sans-serif\" font-size=\"12\">\n",
+            ),
+            w = layout.width,
+            h = layout.height
+        );
+        if Type::is_directed() {
+            buffer += concat!(
+                "  <defs>\n",
+                "    <marker id=\"arrow\" viewBox=\"0 0 10 10\" refX=\"10\" refY=\"5\" ",
+                "markerWidth=\"8\" markerHeight=\"8\" orient=\"auto\">\n",
+                "      <path d=\"M 0 0 L 10 5 L 0 10 z\" fill=\"#555\"/>\n",
+                "    </marker>\n",
+                "  </defs>\n",
+            );
+        }
+
+        let mut weights = String::new();
+        buffer += "  <g class=\"edges\" stroke=\"#555\" fill=\"none\">\n";
+        for edge in self.sorted_edges() {
+            let (from, to) = match (find(edge.from()), find(edge.to())) {
+                (Some(from), Some(to)) => (from, to),
+                _ => continue,
+            };
+            let marker = if Type::is_directed() {
+                " marker-end=\"url(#arrow)\""
+            } else {
+                ""
+            };
+
+            if from.0 == to.0 {
+                // Loops are drawn as circles on top of the box
+                let (center, (_, half_height)) = (from.1, from.3);
+                buffer += &format!(
+                    "    <circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"10\"/>\n",
+                    center.x,
+                    center.y - half_height - 10.0
+                );
+                if edge.weight != 0 {
+                    weights += &weight_label(
+                        Point::new(center.x + 14.0, center.y - half_height - 14.0),
+                        edge.weight,
+                    );
+                }
+                continue;
+            }
+
+            let mut points = vec![from.1];
+            if let Some(bends) = layout.bends.get(&(edge.from().clone(), edge.to().clone())) {
+                points.extend(bends);
+            }
+            points.push(to.1);
+            let last = points.len() - 1;
+            points[0] = clip(from.1, from.3, points[1]);
+            points[last] = clip(to.1, to.3, points[last - 1]);
+
+            let coordinates: Vec<String> = points
+                .iter()
+                .map(|p| format!("{:.1},{:.1}", p.x, p.y))
+                .collect();
+            buffer += &format!(
+                "    <polyline points=\"{}\"{}/>\n",
+                coordinates.join(" "),
+                marker
+            );
+
+            if edge.weight != 0 {
+                let (a, b) = (points[last / 2], points[last / 2 + 1]);
+                weights += &weight_label(
+                    Point::new((a.x + b.x) / 2.0, (a.y + b.y) / 2.0),
+                    edge.weight,
+                );
+            }
+        }
+        buffer += "  </g>\n";
+
+        if !weights.is_empty() {
+            buffer += "  <g class=\"weights\" fill=\"#a00\" text-anchor=\"middle\">\n";
+            buffer += &weights;
+            buffer += "  </g>\n";
+        }
+
+        buffer += "  <g class=\"vertices\">\n";
+        for (_, center, label, (half_width, half_height)) in &vertices {
+            buffer += &format!(
+                concat!(
+                    "    <rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{:.1}\" ",
+                    "rx=\"6\" fill=\"#fff\" stroke=\"#333\"/>\n",
+                    "    <text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"middle\" ",
+                    "dominant-baseline=\"central\">{}</text>\n",
+                ),
+                center.x - half_width,
+                center.y - half_height,
+                2.0 * half_width,
+                2.0 * half_height,
+                center.x,
+                center.y,
+                xml::escape(label)
+            );
+        }
+        buffer += "  </g>\n</svg>\n";
+
+        buffer
+    }
+}
+
+fn weight_label(at: Point, weight: i64) -> String {
+    format!(
+        "    <text x=\"{:.1}\" y=\"{:.1}\" dy=\"-4\">{}</text>\n",
+        at.x, at.y, weight
+    )
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::marker::{Directed, Undirected};
+
+    #[test]
+    fn clip_test() {
+        let center = Point::new(0.0, 0.0);
+        assert_eq!(
+            clip(center, (20.0, 10.0), Point::new(100.0, 0.0)),
+            Point::new(20.0, 0.0)
+        );
+        assert_eq!(
+            clip(center, (20.0, 10.0), Point::new(0.0, -50.0)),
+            Point::new(0.0, -10.0)
+        );
+        assert_eq!(clip(center, (20.0, 10.0), center), center);
+    }
+
+    #[test]
+    fn to_svg_test() {
+        let mut graph: Graph<i32, String, Directed> = Graph::new();
+        graph.insert(1, String::from("<start>"));
+        graph.insert(2, String::from("middle"));
+        graph.insert(3, String::from("end"));
+        graph.insert_edge(1, 2, 5).unwrap();
+        graph.insert_edge(2, 3, 0).unwrap();
+        graph.insert_edge(1, 3, 0).unwrap();
+
+        let svg = graph.to_svg();
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+        assert!(svg.ends_with("</svg>\n"));
+        assert_eq!(svg.matches("<rect").count(), 3);
+        assert_eq!(svg.matches("<polyline").count(), 3);
+        assert_eq!(svg.matches("marker-end").count(), 3);
+        assert!(svg.contains("&lt;start&gt;</text>"));
+        assert!(svg.contains(">5</text>"));
+        assert_eq!(xml::parse(&svg).map(|e| e.name), Ok(String::from("svg")));
+
+        let mut cyclic: Graph<i32, String, Undirected> = Graph::new();
+        cyclic.insert(1, String::from("a"));
+        cyclic.insert(2, String::from("b"));
+        cyclic.insert_edge(1, 2, 0).unwrap();
+        let svg = cyclic.to_svg();
+        assert_eq!(svg.matches("<polyline").count(), 1);
+        assert!(!svg.contains("marker-end"));
+
+        // The direction an undirected edge was inserted in doesn't change the drawing
+        let mut reversed: Graph<i32, String, Undirected> = Graph::new();
+        reversed.insert(1, String::from("a"));
+        reversed.insert(2, String::from("b"));
+        reversed.insert_edge(2, 1, 0).unwrap();
+        assert_eq!(reversed.to_svg(), svg);
+    }
+}
@@ -2,6 +2,8 @@ use crate::edge::Edge;
 
 /// Vertex is a container that represents a node in a graph.
 /// It contains a key and a value, and a list of edges that connect to it.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "VertexRepr<Key, Value>"))]
 pub struct Vertex<Key, Value>
 where
     Key: Eq + Clone,
@@ -13,9 +15,48 @@ where
     pub value: Value,
 
     /// Adjancency list is a list of edges that connect to the vertex.
+    #[cfg_attr(feature = "serde", serde(rename = "edges"))]
     adj: Vec<Edge<Key>>,
 }
 
+/// VertexRepr is the unchecked form of Vertex used during deserialization.
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct VertexRepr<Key, Value>
+where
+    Key: Eq,
+{
+    key: Key,
+    value: Value,
+    edges: Vec<Edge<Key>>,
+}
+
+#[cfg(feature = "serde")]
+impl<Key, Value> TryFrom<VertexRepr<Key, Value>> for Vertex<Key, Value>
+where
+    Key: Eq + Clone,
+{
+    type Error = &'static str;
+
+    /// Makes sure that every edge starts at the vertex and leads to a distinct vertex.
+    fn try_from(repr: VertexRepr<Key, Value>) -> Result<Self, Self::Error> {
+        for (i, edge) in repr.edges.iter().enumerate() {
+            if *edge.from() != repr.key {
+                return Err("edge does not start at the vertex it belongs to");
+            }
+            if repr.edges[..i].iter().any(|other| other.to() == edge.to()) {
+                return Err("vertex contains duplicated edges");
+            }
+        }
+
+        Ok(Vertex {
+            key: repr.key,
+            value: repr.value,
+            adj: repr.edges,
+        })
+    }
+}
+
 impl<Key, Value> Vertex<Key, Value>
 where
     Key: Eq + Clone,
@@ -28,6 +69,18 @@ where
         }
     }
 
+    /// Creates the vertex with the ready adjacency list.
+    /// The caller must make sure that every edge starts at the vertex
+    /// and leads to a distinct vertex.
+    pub(crate) fn from_parts(key: Key, value: Value, adj: Vec<Edge<Key>>) -> Self {
+        Vertex { key, value, adj }
+    }
+
+    /// Splits the vertex into its key, value and adjacency list.
+    pub(crate) fn into_parts(self) -> (Key, Value, Vec<Edge<Key>>) {
+        (self.key, self.value, self.adj)
+    }
+
     /// Inserts a new edge into the vertex's adjacency list.
     /// Returns the old edge if it existed in the list.
     /// Returns None if the edge did not exist in the list.
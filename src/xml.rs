@@ -0,0 +1,324 @@
+//! Minimal XML reader used by the XML based graph formats (GraphML, GEXF).
+//! It builds a small in-memory tree and supports only what those formats need:
+//! elements, attributes, text, CDATA, comments, processing instructions and
+//! the predefined and numeric character entities.
+
+use crate::MAX_DEPTH;
+
+/// Element is a node of the parsed XML tree.
+#[derive(Debug)]
+pub(crate) struct Element {
+    /// Name of the element including its namespace prefix, if any.
+    pub name: String,
+
+    /// Attributes in the order they appear in the document.
+    pub attributes: Vec<(String, String)>,
+
+    /// Child elements in document order.
+    pub children: Vec<Element>,
+
+    /// Concatenated text content of the element (without children's text).
+    pub text: String,
+}
+
+impl Element {
+    fn new(name: String, attributes: Vec<(String, String)>) -> Self {
+        Self {
+            name,
+            attributes,
+            children: Vec::new(),
+            text: String::new(),
+        }
+    }
+
+    /// Name of the element without its namespace prefix.
+    pub fn local_name(&self) -> &str {
+        local_name(&self.name)
+    }
+
+    /// Returns the value of the attribute if it exists, otherwise returns None.
+    pub fn attr(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(n, _)| n == name || local_name(n) == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Same as Element::attr, but returns an error if the attribute is missing.
+    pub fn required_attr(&self, name: &str) -> Result<&str, String> {
+        self.attr(name).ok_or_else(|| {
+            format!(
+                "element <{}> is missing required attribute '{}'",
+                self.name, name
+            )
+        })
+    }
+
+    /// Returns an iterator over the child elements with the given local name.
+    pub fn children_named<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Element> + 'a {
+        self.children
+            .iter()
+            .filter(move |child| child.local_name() == name)
+    }
+
+    /// Returns the first child element with the given local name.
+    pub fn child(&self, name: &str) -> Option<&Element> {
+        self.children
+            .iter()
+            .find(|child| child.local_name() == name)
+    }
+}
+
+fn local_name(name: &str) -> &str {
+    name.rsplit(':').next().unwrap_or(name)
+}
+
+/// Parses the document and returns its root element.
+pub(crate) fn parse(input: &str) -> Result<Element, String> {
+    let mut stack: Vec<Element> = Vec::new();
+    let mut root: Option<Element> = None;
+    let mut rest = input;
+
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix("<?") {
+            rest = skip_past(after, "?>")?;
+        } else if let Some(after) = rest.strip_prefix("<!--") {
+            rest = skip_past(after, "-->")?;
+        } else if let Some(after) = rest.strip_prefix("<![CDATA[") {
+            let end = after.find("]]>").ok_or("unterminated CDATA section")?;
+            match stack.last_mut() {
+                Some(element) => element.text += &after[..end],
+                None => return Err(String::from("CDATA section outside of root element")),
+            }
+            rest = &after[end + 3..];
+        } else if let Some(after) = rest.strip_prefix("<!") {
+            rest = skip_declaration(after)?;
+        } else if let Some(after) = rest.strip_prefix("</") {
+            let end = after.find('>').ok_or("unterminated closing tag")?;
+            let name = after[..end].trim();
+            let element = stack
+                .pop()
+                .ok_or_else(|| format!("unexpected closing tag </{}>", name))?;
+            if element.name != name {
+                return Err(format!(
+                    "mismatched closing tag: expected </{}>, found </{}>",
+                    element.name, name
+                ));
+            }
+            close(element, &mut stack, &mut root)?;
+            rest = &after[end + 1..];
+        } else if let Some(after) = rest.strip_prefix('<') {
+            let (element, self_closing, after) = parse_start_tag(after)?;
+            if self_closing {
+                close(element, &mut stack, &mut root)?;
+            } else if stack.len() == MAX_DEPTH {
+                return Err(format!("elements are nested deeper than {}", MAX_DEPTH));
+            } else {
+                stack.push(element);
+            }
+            rest = after;
+        } else {
+            let end = rest.find('<').unwrap_or(rest.len());
+            let text = &rest[..end];
+            match stack.last_mut() {
+                Some(element) => element.text += &unescape(text)?,
+                None if text.trim().is_empty() => {}
+                None => return Err(String::from("text outside of root element")),
+            }
+            rest = &rest[end..];
+        }
+    }
+
+    if let Some(element) = stack.last() {
+        return Err(format!("element <{}> is not closed", element.name));
+    }
+
+    root.ok_or_else(|| String::from("document has no root element"))
+}
+
+fn close(
+    element: Element,
+    stack: &mut [Element],
+    root: &mut Option<Element>,
+) -> Result<(), String> {
+    match stack.last_mut() {
+        Some(parent) => parent.children.push(element),
+        None if root.is_none() => *root = Some(element),
+        None => return Err(String::from("document has more than one root element")),
+    }
+    Ok(())
+}
+
+fn skip_past<'a>(input: &'a str, terminator: &str) -> Result<&'a str, String> {
+    let end = input
+        .find(terminator)
+        .ok_or_else(|| format!("expected '{}'", terminator))?;
+    Ok(&input[end + terminator.len()..])
+}
+
+/// Skips a <!DOCTYPE ...> like declaration, including an internal subset.
+fn skip_declaration(input: &str) -> Result<&str, String> {
+    let mut depth = 0;
+    for (i, c) in input.char_indices() {
+        match c {
+            '[' => depth += 1,
+            ']' => depth -= 1,
+            '>' if depth == 0 => return Ok(&input[i + 1..]),
+            _ => {}
+        }
+    }
+    Err(String::from("unterminated declaration"))
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '_' | '-' | '.' | ':')
+}
+
+fn parse_start_tag(input: &str) -> Result<(Element, bool, &str), String> {
+    let name_end = input.find(|c| !is_name_char(c)).unwrap_or(input.len());
+    if name_end == 0 {
+        return Err(String::from("expected element name after '<'"));
+    }
+    let name = String::from(&input[..name_end]);
+    let mut rest = &input[name_end..];
+    let mut attributes = Vec::new();
+
+    loop {
+        rest = rest.trim_start();
+        if let Some(after) = rest.strip_prefix("/>") {
+            return Ok((Element::new(name, attributes), true, after));
+        }
+        if let Some(after) = rest.strip_prefix('>') {
+            return Ok((Element::new(name, attributes), false, after));
+        }
+
+        let attr_end = rest.find(|c| !is_name_char(c)).unwrap_or(rest.len());
+        if attr_end == 0 {
+            return Err(format!("malformed start tag <{}>", name));
+        }
+        let attr_name = String::from(&rest[..attr_end]);
+        rest = rest[attr_end..].trim_start();
+        rest = rest
+            .strip_prefix('=')
+            .ok_or_else(|| format!("attribute '{}' has no value", attr_name))?
+            .trim_start();
+
+        let quote = rest
+            .chars()
+            .next()
+            .filter(|c| *c == '"' || *c == '\'')
+            .ok_or_else(|| format!("attribute '{}' value must be quoted", attr_name))?;
+        let value_end = rest[1..]
+            .find(quote)
+            .ok_or_else(|| format!("unterminated value of attribute '{}'", attr_name))?;
+        attributes.push((attr_name, unescape(&rest[1..1 + value_end])?));
+        rest = &rest[value_end + 2..];
+    }
+}
+
+/// Replaces character and entity references with the characters they stand for.
+pub(crate) fn unescape(text: &str) -> Result<String, String> {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find('&') {
+        result += &rest[..start];
+        let end = rest[start..]
+            .find(';')
+            .ok_or("unterminated entity reference")?;
+        let entity = &rest[start + 1..start + end];
+        let c = match entity {
+            "lt" => '<',
+            "gt" => '>',
+            "amp" => '&',
+            "quot" => '"',
+            "apos" => '\'',
+            _ => {
+                let code = if let Some(hex) = entity.strip_prefix("#x") {
+                    u32::from_str_radix(hex, 16).ok()
+                } else if let Some(dec) = entity.strip_prefix('#') {
+                    dec.parse().ok()
+                } else {
+                    None
+                };
+                code.and_then(char::from_u32)
+                    .ok_or_else(|| format!("unknown entity '&{};'", entity))?
+            }
+        };
+        result.push(c);
+        rest = &rest[start + end + 1..];
+    }
+
+    result += rest;
+    Ok(result)
+}
+
+/// Escapes the text so it can be used both as element content and as attribute value.
+pub(crate) fn escape(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '<' => result += "&lt;",
+            '>' => result += "&gt;",
+            '&' => result += "&amp;",
+            '"' => result += "&quot;",
+            '\'' => result += "&apos;",
+            _ => result.push(c),
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::assert_depth_limit;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parse_test() {
+        let root = parse(
+            r#"<?xml version="1.0"?>
+<!-- comment -->
+<!DOCTYPE root [ <!ENTITY x "y"> ]>
+<root a="1" b='&lt;2&gt;'>
+  <child id="c1">text &amp; more</child>
+  <ns:child id="c2"/>
+  <other><![CDATA[<raw>]]></other>
+</root>"#,
+        )
+        .expect("document is valid");
+
+        assert_eq!(root.name, "root");
+        assert_eq!(root.attr("a"), Some("1"));
+        assert_eq!(root.attr("b"), Some("<2>"));
+        assert_eq!(root.children.len(), 3);
+
+        let ids: Vec<&str> = root
+            .children_named("child")
+            .map(|c| c.attr("id").unwrap())
+            .collect();
+        assert_eq!(ids, vec!["c1", "c2"]);
+        assert_eq!(root.child("child").unwrap().text, "text & more");
+        assert_eq!(root.child("other").unwrap().text, "<raw>");
+    }
+
+    #[test]
+    fn parse_errors_test() {
+        assert!(parse("<a><b></a>").is_err());
+        assert!(parse("<a>").is_err());
+        assert!(parse("<a/><b/>").is_err());
+        assert!(parse("<a x=1/>").is_err());
+        assert!(parse("").is_err());
+
+        assert_depth_limit(|depth| parse(&("<a>".repeat(depth) + &"</a>".repeat(depth))));
+    }
+
+    #[test]
+    fn escape_test() {
+        let raw = r#"<a href="x">Tom & 'Jerry'</a>"#;
+        assert_eq!(unescape(&escape(raw)), Ok(String::from(raw)));
+        assert_eq!(unescape("&#65;&#x42;"), Ok(String::from("AB")));
+    }
+}